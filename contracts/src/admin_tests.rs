@@ -1,6 +1,18 @@
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, Error, InvokeError, Symbol};
-
-use crate::{NesteraContract, NesteraContractClient, SavingsError};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    vec,
+    xdr::ToXdr,
+    Address, Bytes, BytesN, Env, Error, IntoVal, InvokeError, Symbol, Val, Vec,
+};
+
+use crate::storage_types::{ControlDataKey, DataKey};
+use crate::{
+    AccountFreeze, BurnPayload, CompoundingFrequency, ContractError, GroupCreationFee, MintOutcome,
+    MintPayload, NesteraContract, NesteraContractClient, PauseScope, PlanKind, PlanType,
+    SavingsError, SignatureScheme, SignerSignature, TimelockAction,
+};
 
 fn setup() -> (Env, NesteraContractClient<'static>, Address) {
     let env = Env::default();
@@ -10,7 +22,7 @@ fn setup() -> (Env, NesteraContractClient<'static>, Address) {
     let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
 
     env.mock_all_auths();
-    client.initialize(&admin, &admin_pk);
+    client.initialize(&admin, &admin_pk, &None);
 
     (env, client, admin)
 }
@@ -74,7 +86,9 @@ fn paused_blocks_write_paths() {
     );
 
     assert_contract_error(
-        client.try_create_lock_save(&user, &100, &30).unwrap_err(),
+        client
+            .try_create_lock_save(&user, &100, &30, &false)
+            .unwrap_err(),
         SavingsError::ContractPaused,
     );
 
@@ -150,6 +164,300 @@ fn unpause_restores_write_paths() {
     assert!(client.try_initialize_user(&user).is_ok());
 }
 
+#[test]
+fn scoped_pause_blocks_only_its_operations() {
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let (client, admin, payload, signature) = setup_signed_mint(&env, &signer);
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+
+    client.pause_only(&admin, &PauseScope::Withdrawals);
+    assert!(client.is_paused_for(&PauseScope::Withdrawals));
+    assert!(!client.is_paused_for(&PauseScope::Deposits));
+    assert!(!client.is_paused());
+    client.deposit_flexi(&user, &100);
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &100);
+    assert_savings_error(
+        client.try_withdraw_flexi(&user, &50).unwrap_err(),
+        SavingsError::ProtocolPaused,
+    );
+    assert_savings_error(
+        client
+            .try_withdraw_from_plan(&user, &plan_id, &50)
+            .unwrap_err(),
+        SavingsError::ProtocolPaused,
+    );
+    assert_savings_error(
+        client.try_close_plan(&user, &plan_id).unwrap_err(),
+        SavingsError::ProtocolPaused,
+    );
+
+    client.pause_only(&admin, &PauseScope::Deposits);
+    client.unpause_only(&admin, &PauseScope::Withdrawals);
+    assert_savings_error(
+        client.try_deposit_flexi(&user, &100).unwrap_err(),
+        SavingsError::ProtocolPaused,
+    );
+    assert_savings_error(
+        client
+            .try_deposit_to_plan(&user, &plan_id, &50)
+            .unwrap_err(),
+        SavingsError::ProtocolPaused,
+    );
+    assert_contract_error(
+        client
+            .try_create_savings_plan(&user, &PlanType::Flexi, &10)
+            .unwrap_err(),
+        SavingsError::ProtocolPaused,
+    );
+    client.withdraw_flexi(&user, &50);
+    client.withdraw_from_plan(&user, &plan_id, &50);
+
+    client.pause_only(&admin, &PauseScope::Mint);
+    assert_contract_error(
+        client.try_mint(&payload, &signature).unwrap_err(),
+        SavingsError::ProtocolPaused,
+    );
+    client.unpause_only(&admin, &PauseScope::Mint);
+    assert_eq!(client.mint(&payload, &signature), 1_000);
+
+    // A global pause covers every scope
+    client.pause(&admin);
+    assert!(client.is_paused_for(&PauseScope::Mint));
+    assert_savings_error(
+        client
+            .try_pause_only(&Address::generate(&env), &PauseScope::Mint)
+            .unwrap_err(),
+        SavingsError::Unauthorized,
+    );
+}
+
+#[test]
+fn frozen_account_cannot_deposit_or_withdraw() {
+    let (env, client, admin) = setup();
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.initialize_user(&user);
+    client.initialize_user(&other);
+    client.deposit_flexi(&user, &100);
+    let lock_id = client.create_savings_plan(&user, &PlanType::Lock(400), &50);
+
+    assert_savings_error(
+        client.try_freeze_account(&other, &user, &7).unwrap_err(),
+        SavingsError::Unauthorized,
+    );
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    client.freeze_account(&admin, &user, &7);
+    assert!(client.is_account_frozen(&user));
+    assert_eq!(
+        client.get_account_freeze(&user),
+        Some(AccountFreeze {
+            reason: 7,
+            frozen_at: 500
+        })
+    );
+
+    assert_savings_error(
+        client.try_deposit_flexi(&user, &10).unwrap_err(),
+        SavingsError::FrozenAccount,
+    );
+    assert_savings_error(
+        client.try_withdraw_flexi(&user, &10).unwrap_err(),
+        SavingsError::FrozenAccount,
+    );
+    assert_savings_error(
+        client.try_withdraw_lock(&user, &lock_id).unwrap_err(),
+        SavingsError::FrozenAccount,
+    );
+    assert_savings_error(
+        client.try_early_withdraw_lock(&user, &lock_id).unwrap_err(),
+        SavingsError::FrozenAccount,
+    );
+    assert_savings_error(
+        client
+            .try_deposit_to_plan(&user, &lock_id, &10)
+            .unwrap_err(),
+        SavingsError::FrozenAccount,
+    );
+    // Other accounts are unaffected
+    client.deposit_flexi(&other, &10);
+
+    client.unfreeze_account(&admin, &user);
+    assert!(!client.is_account_frozen(&user));
+    client.withdraw_flexi(&user, &10);
+    client.withdraw_lock(&user, &lock_id);
+}
+
+#[test]
+fn schema_version_starts_current_and_migrates_in_order() {
+    let (env, client, _admin) = setup();
+    assert_eq!(client.get_schema_version(), 2);
+
+    // This code has no newer layout to migrate to
+    assert_savings_error(
+        client.try_migrate(&2).unwrap_err(),
+        SavingsError::InvalidPlanConfig,
+    );
+    assert_savings_error(
+        client.try_migrate(&3).unwrap_err(),
+        SavingsError::InvalidPlanConfig,
+    );
+
+    // A deployment from before schema versioning moves its early break fee
+    // into the protocol parameters
+    env.as_contract(&client.address, || {
+        env.storage()
+            .instance()
+            .remove(&ControlDataKey::SchemaVersion);
+        env.storage()
+            .instance()
+            .set(&DataKey::EarlyBreakFeeBps, &300u32);
+    });
+    assert_eq!(client.get_schema_version(), 1);
+    client.migrate(&2);
+    assert_eq!(client.get_schema_version(), 2);
+    assert_eq!(client.get_config().early_break_fee_bps, 300);
+
+    env.mock_auths(&[]);
+    assert!(client
+        .try_upgrade(&BytesN::from_array(&env, &[0u8; 32]))
+        .is_err());
+}
+
+#[test]
+fn timelocked_actions_execute_only_after_the_delay() {
+    let (env, client, _admin) = setup();
+    client.set_timelock_delay(&3_600);
+    assert_savings_error(
+        client.try_set_flexi_rate(&500).unwrap_err(),
+        SavingsError::TooEarly,
+    );
+    assert_savings_error(
+        client.try_set_timelock_delay(&0).unwrap_err(),
+        SavingsError::TooEarly,
+    );
+
+    let id = client.queue_action(&TimelockAction::SetFlexiRate(500));
+    assert_eq!(client.get_queued_action(&id).unwrap().execute_after, 3_600);
+    assert_savings_error(
+        client.try_execute_action(&id).unwrap_err(),
+        SavingsError::TooEarly,
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    client.execute_action(&id);
+    assert_eq!(client.get_flexi_rate(), 500);
+    assert_savings_error(
+        client.try_execute_action(&id).unwrap_err(),
+        SavingsError::PlanNotFound,
+    );
+
+    let cancelled = client.queue_action(&TimelockAction::SetEarlyBreakFeeBps(500));
+    client.cancel_action(&cancelled);
+    assert_eq!(client.get_queued_action(&cancelled), None);
+
+    // Lifting the timelock goes through the timelock too
+    let lift = client.queue_action(&TimelockAction::SetTimelockDelay(0));
+    env.ledger().with_mut(|li| li.timestamp = 7_200);
+    client.execute_action(&lift);
+    assert_eq!(client.get_timelock_delay(), 0);
+    client.set_flexi_rate(&300);
+
+    env.mock_auths(&[]);
+    assert!(client
+        .try_queue_action(&TimelockAction::SetFlexiRate(0))
+        .is_err());
+}
+
+#[test]
+fn plan_parameters_go_through_the_timelock() {
+    let (env, client, _admin) = setup();
+    client.set_timelock_delay(&3_600);
+    assert_savings_error(
+        client
+            .try_set_compounding(&PlanKind::Flexi, &CompoundingFrequency::Daily)
+            .unwrap_err(),
+        SavingsError::TooEarly,
+    );
+    assert_savings_error(
+        client.try_set_penalty_pool(&5_000, &600).unwrap_err(),
+        SavingsError::TooEarly,
+    );
+    assert_savings_error(client.try_rebalance().unwrap_err(), SavingsError::TooEarly);
+
+    let compounding = client.queue_action(&TimelockAction::SetCompounding(
+        PlanKind::Flexi,
+        CompoundingFrequency::Daily,
+    ));
+    let pool = client.queue_action(&TimelockAction::SetPenaltyPool(5_000, 600));
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    client.execute_action(&compounding);
+    client.execute_action(&pool);
+    assert_eq!(
+        client.get_compounding(&PlanKind::Flexi),
+        CompoundingFrequency::Daily
+    );
+    assert_eq!(client.get_penalty_pool_config().unwrap().share_bps, 5_000);
+}
+
+#[test]
+fn protocol_config_is_validated_and_applied() {
+    let (env, client, _admin) = setup();
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+
+    let mut config = client.get_config();
+    assert_eq!(config.min_deposit, 0);
+    assert_eq!(config.max_waitlist_size, 100);
+    assert_eq!(config.withdrawal_request_ttl, 7 * 24 * 60 * 60);
+
+    config.min_deposit = 100;
+    config.early_break_fee_bps = 250;
+    client.update_config(&config);
+    assert_eq!(client.get_config(), config);
+    assert_eq!(client.get_early_break_fee_bps(), 250);
+    assert_eq!(client.get_fee_config().early_break_fee_bps, 250);
+
+    assert_savings_error(
+        client.try_deposit_flexi(&user, &99).unwrap_err(),
+        SavingsError::AmountBelowMinimum,
+    );
+    client.deposit_flexi(&user, &100);
+
+    // The dedicated setters update the same parameters
+    client.set_early_break_fee_bps(&400);
+    assert_eq!(client.get_config().early_break_fee_bps, 400);
+    client.set_transfer_fees(&30, &20);
+    client.set_performance_fee(&PlanKind::Goal, &1_000);
+    let mut config = client.get_config();
+    assert_eq!(config.deposit_fee_bps, 30);
+    assert_eq!(config.withdrawal_fee_bps, 20);
+    assert_eq!(config.performance_fee_bps.get(PlanKind::Goal), Some(1_000));
+
+    config.withdrawal_fee_bps = 50;
+    config.group_creation_fee = GroupCreationFee::Flat(25);
+    client.update_config(&config);
+    assert_eq!(client.get_fee_config().withdrawal_fee_bps, 50);
+    assert_eq!(
+        client.get_fee_config().group_creation_fee,
+        GroupCreationFee::Flat(25)
+    );
+
+    config.performance_fee_bps.set(PlanKind::Flexi, 10_001);
+    assert_savings_error(
+        client.try_update_config(&config).unwrap_err(),
+        SavingsError::InvalidAmount,
+    );
+    config.performance_fee_bps.remove(PlanKind::Flexi);
+
+    config.group_change_ttl = 0;
+    assert_savings_error(
+        client.try_update_config(&config).unwrap_err(),
+        SavingsError::InvalidAmount,
+    );
+}
+
 #[test]
 fn admin_can_set_early_break_fee_and_recipient() {
     let (env, client, _admin) = setup();
@@ -177,3 +485,536 @@ fn non_admin_cannot_set_fee_config() {
     assert!(client.try_set_fee_recipient(&treasury).is_err());
     assert!(client.try_set_early_break_fee_bps(&500).is_err());
 }
+
+/// Contract whose admin key is `signer`, with a payload signed by it
+fn setup_signed_mint(
+    env: &Env,
+    signer: &SigningKey,
+) -> (
+    NesteraContractClient<'static>,
+    Address,
+    MintPayload,
+    Vec<SignerSignature>,
+) {
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    env.mock_all_auths();
+    client.initialize(
+        &admin,
+        &BytesN::from_array(env, &signer.verifying_key().to_bytes()),
+        &None,
+    );
+
+    let payload = MintPayload {
+        user: Address::generate(env),
+        amount: 1_000,
+        timestamp: 0,
+        expiry_duration: 3_600,
+        target_plan_id: None,
+    };
+    let signature = sign_payload(&client, signer, &payload);
+    (client, admin, payload, signature)
+}
+
+fn sign_payload(
+    client: &NesteraContractClient,
+    signer: &SigningKey,
+    payload: &MintPayload,
+) -> Vec<SignerSignature> {
+    sign_message(
+        signer,
+        domain_message(client, symbol_short!("mint"), payload.clone()),
+    )
+}
+
+/// The bytes signers sign: `(kind, version, contract, network_id, payload)`
+fn domain_message<T: IntoVal<Env, Val>>(
+    client: &NesteraContractClient,
+    kind: Symbol,
+    payload: T,
+) -> Bytes {
+    let env = &client.env;
+    (
+        kind,
+        1u32,
+        client.address.clone(),
+        env.ledger().network_id(),
+        IntoVal::<Env, Val>::into_val(&payload, env),
+    )
+        .to_xdr(env)
+}
+
+fn sign_message(signer: &SigningKey, xdr: Bytes) -> Vec<SignerSignature> {
+    let env = xdr.env().clone();
+    vec![
+        &env,
+        SignerSignature {
+            scheme: SignatureScheme::Ed25519,
+            public_key: BytesN::from_array(&env, &signer.verifying_key().to_bytes()),
+            signature: sign_bytes(signer, xdr),
+        },
+    ]
+}
+
+fn sign_bytes(signer: &SigningKey, xdr: Bytes) -> BytesN<64> {
+    let mut buf = [0u8; 512];
+    let message = &mut buf[..xdr.len() as usize];
+    xdr.copy_into_slice(message);
+    BytesN::from_array(xdr.env(), &signer.sign(message).to_bytes())
+}
+
+#[test]
+fn signed_payload_can_only_be_minted_once() {
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let (client, _admin, payload, signature) = setup_signed_mint(&env, &signer);
+
+    assert!(!client.is_payload_used(&payload));
+    assert_eq!(client.mint(&payload, &signature), 1_000);
+    assert!(client.is_payload_used(&payload));
+    assert_eq!(
+        client.try_mint(&payload, &signature).unwrap_err(),
+        Ok(Error::from_contract_error(
+            ContractError::PayloadAlreadyUsed as u32
+        ))
+    );
+}
+
+#[test]
+fn revoked_payload_cannot_be_minted() {
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let (client, admin, payload, signature) = setup_signed_mint(&env, &signer);
+    let hash = client.get_payload_hash(&payload);
+
+    let non_admin = Address::generate(&env);
+    assert_savings_error(
+        client.try_revoke_payload(&non_admin, &hash).unwrap_err(),
+        SavingsError::Unauthorized,
+    );
+
+    client.revoke_payload(&admin, &hash);
+    assert!(client.is_payload_revoked(&hash));
+    assert_eq!(
+        client.try_mint(&payload, &signature).unwrap_err(),
+        Ok(Error::from_contract_error(
+            ContractError::PayloadRevoked as u32
+        ))
+    );
+    assert!(!client.is_payload_used(&payload));
+}
+
+#[test]
+fn mint_batch_reports_each_payload() {
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let (client, admin, used, used_signature) = setup_signed_mint(&env, &signer);
+    client.mint(&used, &used_signature);
+
+    let fresh = MintPayload {
+        user: Address::generate(&env),
+        amount: 250,
+        timestamp: 0,
+        expiry_duration: 3_600,
+        target_plan_id: None,
+    };
+    let revoked = MintPayload {
+        amount: 400,
+        ..fresh.clone()
+    };
+    client.revoke_payload(&admin, &client.get_payload_hash(&revoked));
+
+    let payloads = vec![&env, fresh.clone(), used, revoked.clone(), fresh.clone()];
+    let signatures = vec![
+        &env,
+        sign_payload(&client, &signer, &fresh),
+        used_signature,
+        sign_payload(&client, &signer, &revoked),
+        sign_payload(&client, &signer, &fresh),
+    ];
+    let outcomes = client.mint_batch(&payloads, &signatures);
+    assert_eq!(
+        outcomes,
+        vec![
+            &env,
+            MintOutcome::Minted(250),
+            MintOutcome::Failed(ContractError::PayloadAlreadyUsed as u32),
+            MintOutcome::Failed(ContractError::PayloadRevoked as u32),
+            MintOutcome::Failed(ContractError::PayloadAlreadyUsed as u32),
+        ]
+    );
+
+    assert_eq!(
+        client
+            .try_mint_batch(
+                &payloads,
+                &vec![&env, sign_payload(&client, &signer, &fresh)]
+            )
+            .unwrap_err(),
+        Ok(Error::from_contract_error(
+            ContractError::InvalidBatch as u32
+        ))
+    );
+}
+
+#[test]
+fn minted_balance_can_be_burned_by_user_or_signed_payload() {
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let (client, _admin, payload, signature) = setup_signed_mint(&env, &signer);
+    let user = payload.user.clone();
+    client.mint(&payload, &signature);
+    assert_eq!(client.get_minted_balance(&user), 1_000);
+    assert_eq!(client.total_supply(), 1_000);
+
+    client.burn(&user, &300);
+    assert_eq!(client.get_minted_balance(&user), 700);
+    assert_savings_error(
+        client.try_burn(&user, &701).unwrap_err(),
+        SavingsError::InsufficientBalance,
+    );
+
+    let burn = BurnPayload {
+        user: user.clone(),
+        amount: 500,
+        timestamp: 0,
+        expiry_duration: 3_600,
+    };
+    let burn_signature = sign_message(
+        &signer,
+        domain_message(&client, symbol_short!("burn"), burn.clone()),
+    );
+    // A mint signature over the same fields is not a burn signature
+    assert!(client.try_admin_burn(&burn, &signature).is_err());
+
+    assert_eq!(client.admin_burn(&burn, &burn_signature), 500);
+    assert_eq!(client.get_minted_balance(&user), 200);
+    assert_eq!(client.total_supply(), 200);
+    assert_eq!(
+        client.try_admin_burn(&burn, &burn_signature).unwrap_err(),
+        Ok(Error::from_contract_error(
+            ContractError::PayloadAlreadyUsed as u32
+        ))
+    );
+}
+
+#[test]
+fn mint_respects_the_supply_cap() {
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let (client, _admin, payload, signature) = setup_signed_mint(&env, &signer);
+    let user = payload.user.clone();
+
+    client.set_supply_cap(&999);
+    assert_eq!(
+        client.try_mint(&payload, &signature).unwrap_err(),
+        Ok(Error::from_contract_error(
+            ContractError::SupplyCapExceeded as u32
+        ))
+    );
+    assert!(!client.is_payload_used(&payload));
+
+    client.set_supply_cap(&1_500);
+    client.mint(&payload, &signature);
+    client.burn(&user, &400);
+    assert_eq!(client.get_total_minted(), 1_000);
+    assert_eq!(client.get_total_burned(), 400);
+    assert_eq!(client.total_supply(), 600);
+
+    // Burned supply frees room under the cap
+    let next = MintPayload {
+        amount: 900,
+        ..payload
+    };
+    client.mint(&next, &sign_payload(&client, &signer, &next));
+    assert_eq!(client.total_supply(), 1_500);
+}
+
+#[test]
+fn mint_requires_threshold_of_signers() {
+    let env = Env::default();
+    let first = SigningKey::from_bytes(&[7u8; 32]);
+    let second = SigningKey::from_bytes(&[8u8; 32]);
+    let outsider = SigningKey::from_bytes(&[9u8; 32]);
+    let (client, _admin, payload, first_signature) = setup_signed_mint(&env, &first);
+
+    assert_savings_error(
+        client.try_set_threshold(&2).unwrap_err(),
+        SavingsError::InvalidAmount,
+    );
+    let second_key = BytesN::from_array(&env, &second.verifying_key().to_bytes());
+    client.add_signer(&second_key);
+    client.set_threshold(&2);
+    assert_eq!(client.get_signers().len(), 2);
+    assert_savings_error(
+        client.try_remove_signer(&second_key).unwrap_err(),
+        SavingsError::InvariantViolation,
+    );
+
+    // One valid signature, a repeat of it and an outsider's are not enough
+    let mut signatures = first_signature.clone();
+    signatures.append(&first_signature);
+    signatures.append(&sign_payload(&client, &outsider, &payload));
+    assert_eq!(
+        client.try_mint(&payload, &signatures).unwrap_err(),
+        Ok(Error::from_contract_error(
+            ContractError::InsufficientSignatures as u32
+        ))
+    );
+
+    signatures.append(&sign_payload(&client, &second, &payload));
+    assert_eq!(client.mint(&payload, &signatures), 1_000);
+}
+
+#[test]
+fn admin_key_rotation_takes_effect_once_confirmed() {
+    let env = Env::default();
+    let old = SigningKey::from_bytes(&[7u8; 32]);
+    let new = SigningKey::from_bytes(&[8u8; 32]);
+    let (client, _admin, payload, old_signature) = setup_signed_mint(&env, &old);
+    let new_key = BytesN::from_array(&env, &new.verifying_key().to_bytes());
+
+    assert_savings_error(
+        client
+            .try_confirm_rotation(&BytesN::from_array(&env, &[0u8; 64]))
+            .unwrap_err(),
+        SavingsError::MissingParameter,
+    );
+    client.rotate_admin_key(&new_key);
+    assert_eq!(client.get_pending_admin_key(), Some(new_key.clone()));
+
+    // The old key stays active until the new one confirms
+    let message = client.get_rotation_message(&new_key);
+    assert!(client
+        .try_confirm_rotation(&sign_bytes(&old, message.clone()))
+        .is_err());
+    client.confirm_rotation(&sign_bytes(&new, message));
+    assert_eq!(client.get_pending_admin_key(), None);
+    assert_eq!(client.get_signers(), vec![&env, new_key]);
+
+    assert!(client.try_mint(&payload, &old_signature).is_err());
+    assert_eq!(
+        client.mint(&payload, &sign_payload(&client, &new, &payload)),
+        1_000
+    );
+}
+
+#[test]
+fn mint_credits_target_plan() {
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let (client, _admin, payload, _signature) = setup_signed_mint(&env, &signer);
+    let user = payload.user.clone();
+    client.initialize_user(&user);
+    let lock_id = client.create_savings_plan(&user, &PlanType::Lock(31_536_000), &500);
+    let flexi_id = client.create_savings_plan(&user, &PlanType::Flexi, &0);
+
+    let into_flexi = MintPayload {
+        target_plan_id: Some(flexi_id),
+        ..payload.clone()
+    };
+    assert_eq!(
+        client
+            .try_mint(&into_flexi, &sign_payload(&client, &signer, &into_flexi))
+            .unwrap_err(),
+        Ok(Error::from_contract_error(
+            ContractError::InvalidTargetPlan as u32
+        ))
+    );
+
+    let into_lock = MintPayload {
+        target_plan_id: Some(lock_id),
+        ..payload
+    };
+    client.mint(&into_lock, &sign_payload(&client, &signer, &into_lock));
+    assert_eq!(
+        client.get_savings_plan(&user, &lock_id).unwrap().balance,
+        1_500
+    );
+    assert_eq!(client.get_user(&user).total_balance, 1_500);
+    assert_eq!(client.get_minted_balance(&user), 0);
+    assert_eq!(client.total_supply(), 1_000);
+}
+
+#[test]
+fn mint_enforces_per_user_limits() {
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let (client, _admin, payload, signature) = setup_signed_mint(&env, &signer);
+    let limit_exceeded = Ok(Error::from_contract_error(
+        ContractError::MintLimitExceeded as u32,
+    ));
+    let mint_at = |amount: i128, timestamp: u64| {
+        let next = MintPayload {
+            amount,
+            timestamp,
+            ..payload.clone()
+        };
+        client.try_mint(&next, &sign_payload(&client, &signer, &next))
+    };
+
+    client.set_mint_limits(&600, &1_000);
+    assert_eq!(
+        client.try_mint(&payload, &signature).unwrap_err(),
+        limit_exceeded
+    );
+
+    assert!(mint_at(600, 0).is_ok());
+    assert!(mint_at(400, 0).is_ok());
+    assert_eq!(mint_at(1, 0).unwrap_err(), limit_exceeded);
+    assert_eq!(client.get_mint_window(&payload.user).unwrap().minted, 1_000);
+
+    // A new window opens a day after the first mint
+    env.ledger().set_timestamp(24 * 60 * 60);
+    assert!(mint_at(500, 24 * 60 * 60).is_ok());
+    assert_eq!(client.get_minted_balance(&payload.user), 1_500);
+}
+
+#[test]
+fn signatures_are_bound_to_contract_and_format() {
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let (client, _admin, payload, signature) = setup_signed_mint(&env, &signer);
+    let (other, _, _, _) = setup_signed_mint(&env, &signer);
+    assert_eq!(
+        client.get_mint_message(&payload),
+        domain_message(&client, symbol_short!("mint"), payload.clone())
+    );
+
+    // A bare payload, as signed before versioning, no longer verifies
+    let bare = sign_message(&signer, payload.clone().to_xdr(&env));
+    assert!(client.try_mint(&payload, &bare).is_err());
+    // Nor does a signature made for another deployment
+    assert!(other.try_mint(&payload, &signature).is_err());
+
+    assert_eq!(client.mint(&payload, &signature), 1_000);
+}
+
+#[test]
+fn delegated_minter_mints_within_ceiling_until_expiry() {
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let minter = SigningKey::from_bytes(&[9u8; 32]);
+    let minter_pk = BytesN::from_array(&env, &minter.verifying_key().to_bytes());
+    let (client, _admin, payload, signature) = setup_signed_mint(&env, &signer);
+    let mint_as = |key: &SigningKey, amount: i128, timestamp: u64| {
+        let next = MintPayload {
+            amount,
+            timestamp,
+            ..payload.clone()
+        };
+        client.try_mint(&next, &sign_payload(&client, key, &next))
+    };
+
+    // Mints by the signer set record the signers that authorized them
+    client.mint(&payload, &signature);
+    let signer_pk = BytesN::from_array(&env, &signer.verifying_key().to_bytes());
+    assert_eq!(
+        client.get_mint_authorizers(&client.get_payload_hash(&payload)),
+        vec![&env, signer_pk]
+    );
+
+    // An unregistered key can't mint on its own
+    assert_eq!(
+        mint_as(&minter, 500, 1).unwrap_err(),
+        Ok(Error::from_contract_error(
+            ContractError::InsufficientSignatures as u32
+        ))
+    );
+
+    client.add_minter(&minter_pk, &800, &1_000);
+    assert_eq!(mint_as(&minter, 500, 1), Ok(Ok(500)));
+    let next = MintPayload {
+        amount: 500,
+        timestamp: 1,
+        ..payload.clone()
+    };
+    assert_eq!(
+        client.get_mint_authorizers(&client.get_payload_hash(&next)),
+        vec![&env, minter_pk.clone()]
+    );
+    assert_eq!(client.get_minter(&minter_pk).unwrap().minted, 500);
+
+    // The ceiling spans all of the key's mints
+    assert_eq!(
+        mint_as(&minter, 400, 2).unwrap_err(),
+        Ok(Error::from_contract_error(
+            ContractError::MinterCeilingExceeded as u32
+        ))
+    );
+    assert_eq!(mint_as(&minter, 300, 2), Ok(Ok(300)));
+
+    // Raising the ceiling keeps what was already minted
+    client.add_minter(&minter_pk, &1_000, &1_000);
+    assert_eq!(client.get_minter(&minter_pk).unwrap().minted, 800);
+
+    // Expired keys no longer count
+    env.ledger().with_mut(|li| li.timestamp = 1_001);
+    assert!(mint_as(&minter, 100, 1_001).is_err());
+
+    client.remove_minter(&minter_pk);
+    assert_eq!(client.get_minter(&minter_pk), None);
+    assert_savings_error(
+        client.try_remove_minter(&minter_pk).unwrap_err(),
+        SavingsError::InvalidSignature,
+    );
+    assert_savings_error(
+        client
+            .try_add_minter(&minter_pk, &1_000, &1_000)
+            .unwrap_err(),
+        SavingsError::InvalidTimestamp,
+    );
+}
+
+#[test]
+fn secp256k1_signers_count_towards_threshold() {
+    use k256::ecdsa::SigningKey as Secp256k1Key;
+
+    let env = Env::default();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let (client, _admin, payload, signature) = setup_signed_mint(&env, &signer);
+    let partner = Secp256k1Key::from_slice(&[5u8; 32]).unwrap();
+    let partner_pk = partner.verifying_key().to_encoded_point(false);
+    let key_id = client.add_secp256k1_signer(&BytesN::from_array(
+        &env,
+        partner_pk.as_bytes().try_into().unwrap(),
+    ));
+    assert!(client.get_signers().contains(&key_id));
+    client.set_threshold(&2);
+
+    let digest = env.crypto().sha256(&client.get_mint_message(&payload));
+    let (sig, recovery_id) = partner
+        .sign_prehash_recoverable(&digest.to_array())
+        .unwrap();
+    let secp256k1 = SignerSignature {
+        scheme: SignatureScheme::Secp256k1(recovery_id.to_byte() as u32),
+        public_key: key_id.clone(),
+        signature: BytesN::from_array(&env, &sig.to_bytes().into()),
+    };
+
+    // A secp256k1 signature alone doesn't meet the threshold
+    assert!(client
+        .try_mint(&payload, &vec![&env, secp256k1.clone()])
+        .is_err());
+    // Verified under the wrong scheme it is invalid
+    let wrong_scheme = SignerSignature {
+        scheme: SignatureScheme::Ed25519,
+        ..secp256k1.clone()
+    };
+    let mut signatures = signature.clone();
+    signatures.push_back(wrong_scheme);
+    assert!(client.try_mint(&payload, &signatures).is_err());
+
+    let mut signatures = signature;
+    signatures.push_back(secp256k1);
+    assert_eq!(client.mint(&payload, &signatures), 1_000);
+    assert_eq!(
+        client.get_mint_authorizers(&client.get_payload_hash(&payload)),
+        vec![
+            &env,
+            BytesN::from_array(&env, &signer.verifying_key().to_bytes()),
+            key_id
+        ]
+    );
+}
@@ -0,0 +1,572 @@
+use crate::assets;
+use crate::config;
+use crate::ensure_not_paused;
+use crate::errors::SavingsError;
+use crate::group;
+use crate::storage_types::{
+    DataKey, GroupAction, GroupApprovalPolicy, GroupProposal, GroupSave, GroupStatus,
+    GroupWithdrawalRequest,
+};
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+/// Default time members have to approve a withdrawal request (7 days); see
+/// `ProtocolConfig::withdrawal_request_ttl`
+pub const WITHDRAWAL_REQUEST_TTL: u64 = 7 * 24 * 60 * 60;
+
+/// Configures an M-of-N approval policy for a group's pooled funds.
+///
+/// Once a policy is set, distributions, refunds and loans out of the group
+/// must go through a proposal that collects `threshold` approvals from the
+/// designated `approvers` before it can be executed.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group to configure
+/// * `approvers` - Members allowed to propose and approve (N)
+/// * `threshold` - Approvals required to execute (M, 1 <= M <= N)
+/// * `proposal_ttl` - Seconds a proposal stays open (must be > 0)
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `Unauthorized` - If the caller is not the group creator
+/// * `NotGroupMember` - If an approver is not a member of the group
+/// * `InvalidGroupConfig` - If the approver set or threshold is invalid
+pub fn set_group_approval_policy(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    approvers: Vec<Address>,
+    threshold: u32,
+    proposal_ttl: u64,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group = load_group(env, group_id)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if approvers.is_empty() || threshold == 0 || threshold > approvers.len() {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    if proposal_ttl == 0 {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    for i in 0..approvers.len() {
+        let approver = approvers.get(i).unwrap();
+        if !group::is_member(env, group_id, &approver) {
+            return Err(SavingsError::NotGroupMember);
+        }
+        // Reject duplicates so N reflects distinct approvers
+        if approvers.last_index_of(&approver) != Some(i) {
+            return Err(SavingsError::InvalidGroupConfig);
+        }
+    }
+
+    let policy = GroupApprovalPolicy {
+        approvers,
+        threshold,
+        proposal_ttl,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupApprovalPolicy(group_id), &policy);
+
+    env.events()
+        .publish((symbol_short!("grp_pol"), group_id), threshold);
+
+    Ok(())
+}
+
+/// Returns the approval policy of a group, if one is configured.
+pub fn get_group_approval_policy(env: &Env, group_id: u64) -> Option<GroupApprovalPolicy> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GroupApprovalPolicy(group_id))
+}
+
+/// Opens a proposal to move pooled funds out of a group.
+///
+/// The proposer must be one of the designated approvers; their approval is
+/// counted immediately.
+///
+/// # Returns
+/// `Ok(u64)` - The ID of the new proposal
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `InvalidGroupConfig` - If the group has no approval policy
+/// * `Unauthorized` - If the proposer is not a designated approver
+/// * `InvalidAmount` - If amount <= 0
+/// * `InsufficientBalance` - If the group pool cannot cover the amount
+/// * `UserNotFound` - If the recipient has no account to be credited
+pub fn propose_group_action(
+    env: &Env,
+    proposer: Address,
+    group_id: u64,
+    action: GroupAction,
+    recipient: Address,
+    amount: i128,
+) -> Result<u64, SavingsError> {
+    ensure_not_paused(env)?;
+    proposer.require_auth();
+
+    let group = load_group(env, group_id)?;
+    let policy =
+        get_group_approval_policy(env, group_id).ok_or(SavingsError::InvalidGroupConfig)?;
+
+    if !policy.approvers.contains(&proposer) {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if amount > group.current_amount {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    if !crate::users::user_exists(env, &recipient) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let now = env.ledger().timestamp();
+    let proposal_id = get_next_proposal_id(env);
+    let mut approvals = Vec::new(env);
+    approvals.push_back(proposer.clone());
+
+    let proposal = GroupProposal {
+        id: proposal_id,
+        group_id,
+        proposer: proposer.clone(),
+        action,
+        recipient,
+        amount,
+        approvals,
+        created_at: now,
+        expires_at: now
+            .checked_add(policy.proposal_ttl)
+            .ok_or(SavingsError::Overflow)?,
+        is_executed: false,
+    };
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupProposal(proposal_id), &proposal);
+    env.storage()
+        .persistent()
+        .set(&DataKey::NextGroupProposalId, &(proposal_id + 1));
+
+    env.events()
+        .publish((symbol_short!("grp_prop"), proposer, group_id), proposal_id);
+
+    Ok(proposal_id)
+}
+
+/// Records an approval on an open proposal.
+///
+/// # Errors
+/// * `PlanNotFound` - If the proposal doesn't exist
+/// * `Unauthorized` - If the caller is not a designated approver
+/// * `PlanCompleted` - If the proposal was already executed
+/// * `TooLate` - If the proposal has expired
+/// * `InvalidGroupConfig` - If the caller already approved
+pub fn approve_group_action(
+    env: &Env,
+    approver: Address,
+    proposal_id: u64,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    approver.require_auth();
+
+    let mut proposal = get_group_proposal(env, proposal_id).ok_or(SavingsError::PlanNotFound)?;
+    let policy = get_group_approval_policy(env, proposal.group_id)
+        .ok_or(SavingsError::InvalidGroupConfig)?;
+
+    if !policy.approvers.contains(&approver) {
+        return Err(SavingsError::Unauthorized);
+    }
+    ensure_open(env, &proposal)?;
+
+    if proposal.approvals.contains(&approver) {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    proposal.approvals.push_back(approver.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupProposal(proposal_id), &proposal);
+
+    env.events().publish(
+        (symbol_short!("grp_appr"), approver, proposal.group_id),
+        proposal_id,
+    );
+
+    Ok(())
+}
+
+/// Executes a proposal once it has collected enough approvals.
+///
+/// The amount is taken out of the group's `current_amount` and credited to
+/// the recipient's Flexi balance. Refunds also reduce the recipient's
+/// recorded contribution, so they can never exceed what the member paid in.
+///
+/// # Errors
+/// * `PlanNotFound` - If the proposal or its group doesn't exist
+/// * `Unauthorized` - If the caller is not a designated approver
+/// * `PlanCompleted` - If the proposal was already executed
+/// * `TooLate` - If the proposal has expired
+/// * `InsufficientApprovals` - If fewer than `threshold` approvals were collected
+/// * `InsufficientBalance` - If the pool or refunded contribution is too small
+pub fn execute_group_action(
+    env: &Env,
+    executor: Address,
+    proposal_id: u64,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    executor.require_auth();
+
+    let mut proposal = get_group_proposal(env, proposal_id).ok_or(SavingsError::PlanNotFound)?;
+    let policy = get_group_approval_policy(env, proposal.group_id)
+        .ok_or(SavingsError::InvalidGroupConfig)?;
+
+    if !policy.approvers.contains(&executor) {
+        return Err(SavingsError::Unauthorized);
+    }
+    ensure_open(env, &proposal)?;
+
+    if proposal.approvals.len() < policy.threshold {
+        return Err(SavingsError::InsufficientApprovals);
+    }
+
+    let group_key = DataKey::GroupSave(proposal.group_id);
+    let mut group = load_group(env, proposal.group_id)?;
+    if proposal.amount > group.current_amount {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    if proposal.action == GroupAction::Refund {
+        let contribution_key =
+            DataKey::GroupMemberContribution(proposal.group_id, proposal.recipient.clone());
+        let contribution: i128 = env
+            .storage()
+            .persistent()
+            .get(&contribution_key)
+            .unwrap_or(0i128);
+        if proposal.amount > contribution {
+            return Err(SavingsError::InsufficientBalance);
+        }
+        env.storage()
+            .persistent()
+            .set(&contribution_key, &(contribution - proposal.amount));
+    }
+
+    group::accrue_interest(env, &mut group);
+    group.current_amount -= proposal.amount;
+    env.storage().persistent().set(&group_key, &group);
+
+    proposal.is_executed = true;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupProposal(proposal_id), &proposal);
+
+    assets::credit(
+        env,
+        &proposal.recipient,
+        &assets::get_group_asset(env, group.id),
+        proposal.amount,
+    )?;
+
+    env.events().publish(
+        (
+            symbol_short!("grp_exec"),
+            proposal.recipient,
+            proposal.group_id,
+        ),
+        proposal.amount,
+    );
+
+    Ok(())
+}
+
+/// Returns a group proposal by ID.
+pub fn get_group_proposal(env: &Env, proposal_id: u64) -> Option<GroupProposal> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GroupProposal(proposal_id))
+}
+
+/// Sets how many members must approve a creator's withdrawal request.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group to configure
+/// * `threshold` - Member approvals required; 0 disables withdrawals
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `Unauthorized` - If the caller is not the group creator
+pub fn set_group_withdrawal_threshold(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    threshold: u32,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let mut group = load_group(env, group_id)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    group.withdrawal_threshold = threshold;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupSave(group_id), &group);
+
+    Ok(())
+}
+
+/// Opens a request to withdraw from a group's pot.
+///
+/// Only the creator may request a withdrawal. The funds stay in the group
+/// until `withdrawal_threshold` other members approve the request.
+///
+/// # Returns
+/// `Ok(u64)` - The ID of the new request
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `Unauthorized` - If the caller is not the group creator
+/// * `PlanCompleted` - If the group is no longer active
+/// * `InvalidGroupConfig` - If the group has withdrawals disabled
+/// * `InvalidAmount` - If amount <= 0
+/// * `InsufficientBalance` - If the group pool cannot cover the amount
+/// * `UserNotFound` - If the recipient has no account to be credited
+pub fn request_group_withdrawal(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    recipient: Address,
+    amount: i128,
+) -> Result<u64, SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group = load_group(env, group_id)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if group.withdrawal_threshold == 0 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if amount > group.current_amount {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    if !crate::users::user_exists(env, &recipient) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let now = env.ledger().timestamp();
+    let request_id: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::NextGroupWithdrawalId)
+        .unwrap_or(1);
+
+    let request = GroupWithdrawalRequest {
+        id: request_id,
+        group_id,
+        recipient,
+        amount,
+        approvals: Vec::new(env),
+        created_at: now,
+        expires_at: now
+            .checked_add(config::get_config(env).withdrawal_request_ttl)
+            .ok_or(SavingsError::Overflow)?,
+        is_executed: false,
+        is_cancelled: false,
+    };
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupWithdrawalRequest(request_id), &request);
+    env.storage()
+        .persistent()
+        .set(&DataKey::NextGroupWithdrawalId, &(request_id + 1));
+
+    env.events()
+        .publish((symbol_short!("wdr_req"), creator, group_id), request_id);
+
+    Ok(request_id)
+}
+
+/// Approves a pending withdrawal request.
+///
+/// Once the request has `withdrawal_threshold` approvals from members other
+/// than the creator, the amount is taken out of the group's pot and credited
+/// to the recipient's Flexi balance.
+///
+/// # Returns
+/// `Ok(true)` if this approval released the funds, `Ok(false)` otherwise
+///
+/// # Errors
+/// * `PlanNotFound` - If the request or its group doesn't exist
+/// * `NotGroupMember` - If the caller is not a member of the group
+/// * `Unauthorized` - If the creator tries to approve their own request
+/// * `PlanCompleted` - If the request was executed or cancelled, or the group closed
+/// * `TooLate` - If the request has expired
+/// * `InvalidGroupConfig` - If the caller already approved
+/// * `InsufficientBalance` - If the pot no longer covers the amount
+pub fn approve_group_withdrawal(
+    env: &Env,
+    member: Address,
+    request_id: u64,
+) -> Result<bool, SavingsError> {
+    ensure_not_paused(env)?;
+    member.require_auth();
+
+    let mut request = get_group_withdrawal(env, request_id).ok_or(SavingsError::PlanNotFound)?;
+    let mut group = load_group(env, request.group_id)?;
+
+    if !group::is_member(env, request.group_id, &member) {
+        return Err(SavingsError::NotGroupMember);
+    }
+    if member == group.creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    ensure_request_open(env, &request)?;
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if request.approvals.contains(&member) {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    request.approvals.push_back(member.clone());
+    env.events().publish(
+        (symbol_short!("wdr_appr"), member, request.group_id),
+        request_id,
+    );
+
+    let executed = request.approvals.len() >= group.withdrawal_threshold;
+    if executed {
+        if request.amount > group.current_amount {
+            return Err(SavingsError::InsufficientBalance);
+        }
+        group::accrue_interest(env, &mut group);
+        group.current_amount -= request.amount;
+        env.storage()
+            .persistent()
+            .set(&DataKey::GroupSave(group.id), &group);
+        request.is_executed = true;
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupWithdrawalRequest(request_id), &request);
+
+    if executed {
+        assets::credit(
+            env,
+            &request.recipient,
+            &assets::get_group_asset(env, group.id),
+            request.amount,
+        )?;
+        env.events().publish(
+            (
+                symbol_short!("wdr_exec"),
+                request.recipient,
+                request.group_id,
+            ),
+            request.amount,
+        );
+    }
+
+    Ok(executed)
+}
+
+/// Cancels a pending withdrawal request.
+///
+/// # Errors
+/// * `PlanNotFound` - If the request or its group doesn't exist
+/// * `Unauthorized` - If the caller is not the group creator
+/// * `PlanCompleted` - If the request was already executed or cancelled
+pub fn cancel_group_withdrawal(
+    env: &Env,
+    creator: Address,
+    request_id: u64,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let mut request = get_group_withdrawal(env, request_id).ok_or(SavingsError::PlanNotFound)?;
+    let group = load_group(env, request.group_id)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if request.is_executed || request.is_cancelled {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    request.is_cancelled = true;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupWithdrawalRequest(request_id), &request);
+
+    env.events()
+        .publish((symbol_short!("wdr_cncl"), request.group_id), request_id);
+
+    Ok(())
+}
+
+/// Returns a group withdrawal request by ID.
+pub fn get_group_withdrawal(env: &Env, request_id: u64) -> Option<GroupWithdrawalRequest> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GroupWithdrawalRequest(request_id))
+}
+
+// ========== Helper Functions ==========
+
+fn ensure_request_open(env: &Env, request: &GroupWithdrawalRequest) -> Result<(), SavingsError> {
+    if request.is_executed || request.is_cancelled {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if env.ledger().timestamp() > request.expires_at {
+        return Err(SavingsError::TooLate);
+    }
+    Ok(())
+}
+
+fn load_group(env: &Env, group_id: u64) -> Result<GroupSave, SavingsError> {
+    group::get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)
+}
+
+fn ensure_open(env: &Env, proposal: &GroupProposal) -> Result<(), SavingsError> {
+    if proposal.is_executed {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if env.ledger().timestamp() > proposal.expires_at {
+        return Err(SavingsError::TooLate);
+    }
+    Ok(())
+}
+
+fn get_next_proposal_id(env: &Env) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::NextGroupProposalId)
+        .unwrap_or(1)
+}
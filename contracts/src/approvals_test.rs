@@ -0,0 +1,227 @@
+use crate::{GroupAction, NesteraContract, NesteraContractClient, SavingsError};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, Env, String,
+};
+
+fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &admin_pk, &None);
+
+    (env, client, admin)
+}
+
+/// Creates a group with a creator and two members who each contributed 500.
+fn setup_funded_group(
+    env: &Env,
+    client: &NesteraContractClient<'static>,
+) -> (u64, Address, Address, Address) {
+    let creator = Address::generate(env);
+    let member1 = Address::generate(env);
+    let member2 = Address::generate(env);
+
+    client.initialize_user(&creator);
+    client.initialize_user(&member1);
+    client.initialize_user(&member2);
+
+    let group_id = client.create_group_save(
+        &creator,
+        &String::from_str(env, "Treasury"),
+        &String::from_str(env, "Shared pot"),
+        &String::from_str(env, "savings"),
+        &10_000,
+        &0,
+        &100,
+        &true,
+        &1,
+        &1000,
+    );
+    client.join_group_save(&member1, &group_id);
+    client.join_group_save(&member2, &group_id);
+
+    client.contribute_to_group_save(&creator, &group_id, &500);
+    client.contribute_to_group_save(&member1, &group_id, &500);
+    client.contribute_to_group_save(&member2, &group_id, &500);
+
+    (group_id, creator, member1, member2)
+}
+
+#[test]
+fn test_two_of_three_distribution_executes() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, member2) = setup_funded_group(&env, &client);
+
+    let approvers = vec![&env, creator.clone(), member1.clone(), member2.clone()];
+    client.set_group_approval_policy(&creator, &group_id, &approvers, &2, &3600);
+
+    let proposal_id = client.propose_group_action(
+        &creator,
+        &group_id,
+        &GroupAction::Distribution,
+        &member2,
+        &600,
+    );
+
+    // Only the proposer has approved so far
+    let res = client.try_execute_group_action(&creator, &proposal_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientApprovals));
+
+    client.approve_group_action(&member1, &proposal_id);
+    client.execute_group_action(&member1, &proposal_id);
+
+    assert_eq!(client.get_flexi_balance(&member2), 600);
+    assert!(client.get_group_proposal(&proposal_id).unwrap().is_executed);
+
+    // Cannot execute twice
+    let res = client.try_execute_group_action(&member1, &proposal_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_proposal_expires() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, _member2) = setup_funded_group(&env, &client);
+
+    let approvers = vec![&env, creator.clone(), member1.clone()];
+    client.set_group_approval_policy(&creator, &group_id, &approvers, &2, &100);
+
+    let proposal_id =
+        client.propose_group_action(&creator, &group_id, &GroupAction::Loan, &member1, &200);
+
+    env.ledger().with_mut(|li| li.timestamp += 101);
+
+    let res = client.try_approve_group_action(&member1, &proposal_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooLate));
+}
+
+#[test]
+fn test_refund_limited_to_recorded_contribution() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, _member2) = setup_funded_group(&env, &client);
+
+    let approvers = vec![&env, creator.clone(), member1.clone()];
+    client.set_group_approval_policy(&creator, &group_id, &approvers, &1, &3600);
+
+    let too_much =
+        client.propose_group_action(&creator, &group_id, &GroupAction::Refund, &member1, &900);
+    let res = client.try_execute_group_action(&creator, &too_much);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientBalance));
+
+    let refund =
+        client.propose_group_action(&creator, &group_id, &GroupAction::Refund, &member1, &500);
+    client.execute_group_action(&creator, &refund);
+    assert_eq!(client.get_flexi_balance(&member1), 500);
+}
+
+#[test]
+fn test_policy_validation() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, _member2) = setup_funded_group(&env, &client);
+    let outsider = Address::generate(&env);
+
+    // Threshold above N
+    let approvers = vec![&env, creator.clone(), member1.clone()];
+    let res = client.try_set_group_approval_policy(&creator, &group_id, &approvers, &3, &3600);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    // Approvers must be members
+    let approvers = vec![&env, creator.clone(), outsider.clone()];
+    let res = client.try_set_group_approval_policy(&creator, &group_id, &approvers, &1, &3600);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::NotGroupMember));
+
+    // Only the creator configures the policy
+    let approvers = vec![&env, member1.clone()];
+    let res = client.try_set_group_approval_policy(&member1, &group_id, &approvers, &1, &3600);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+
+    // Non-approvers cannot propose
+    let approvers = vec![&env, creator.clone()];
+    client.set_group_approval_policy(&creator, &group_id, &approvers, &1, &3600);
+    let res = client.try_propose_group_action(
+        &member1,
+        &group_id,
+        &GroupAction::Distribution,
+        &member1,
+        &100,
+    );
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+}
+
+#[test]
+fn test_withdrawal_request_needs_member_approvals() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, member2) = setup_funded_group(&env, &client);
+
+    // Withdrawals are disabled until a threshold is configured
+    let res = client.try_request_group_withdrawal(&creator, &group_id, &creator, &300);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    client.set_group_withdrawal_threshold(&creator, &group_id, &2);
+    let request_id = client.request_group_withdrawal(&creator, &group_id, &creator, &300);
+
+    // The creator cannot approve their own request
+    let res = client.try_approve_group_withdrawal(&creator, &request_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+
+    assert!(!client.approve_group_withdrawal(&member1, &request_id));
+    assert_eq!(client.get_flexi_balance(&creator), 0);
+
+    assert!(client.approve_group_withdrawal(&member2, &request_id));
+    assert_eq!(client.get_flexi_balance(&creator), 300);
+    assert_eq!(
+        client.get_group_save(&group_id).unwrap().current_amount,
+        1200
+    );
+    assert!(
+        client
+            .get_group_withdrawal(&request_id)
+            .unwrap()
+            .is_executed
+    );
+}
+
+#[test]
+fn test_withdrawal_request_cancel_and_expiry() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, _member2) = setup_funded_group(&env, &client);
+    client.set_group_withdrawal_threshold(&creator, &group_id, &1);
+
+    let cancelled = client.request_group_withdrawal(&creator, &group_id, &creator, &300);
+    let res = client.try_cancel_group_withdrawal(&member1, &cancelled);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+    client.cancel_group_withdrawal(&creator, &cancelled);
+    let res = client.try_approve_group_withdrawal(&member1, &cancelled);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+
+    let expired = client.request_group_withdrawal(&creator, &group_id, &creator, &300);
+    env.ledger()
+        .with_mut(|li| li.timestamp += crate::approvals::WITHDRAWAL_REQUEST_TTL + 1);
+    let res = client.try_approve_group_withdrawal(&member1, &expired);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooLate));
+    assert_eq!(
+        client.get_group_save(&group_id).unwrap().current_amount,
+        1500
+    );
+}
+
+#[test]
+fn test_cancel_after_withdrawal_refunds_what_is_left() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, member2) = setup_funded_group(&env, &client);
+    client.set_group_withdrawal_threshold(&creator, &group_id, &1);
+
+    let request_id = client.request_group_withdrawal(&creator, &group_id, &creator, &300);
+    client.approve_group_withdrawal(&member1, &request_id);
+
+    // 1200 is left of 1500 contributed; it is shared pro-rata
+    client.cancel_group_save(&creator, &group_id);
+    assert_eq!(client.get_flexi_balance(&creator), 300 + 400);
+    assert_eq!(client.get_flexi_balance(&member1), 400);
+    assert_eq!(client.get_flexi_balance(&member2), 400);
+}
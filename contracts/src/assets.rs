@@ -0,0 +1,498 @@
+//! Movement of savings tokens between users and the contract.
+//!
+//! The default savings token is a SEP-41 contract recorded at `initialize`.
+//! Deposits pull tokens from the depositor with `transfer` and withdrawals
+//! push them back, always after the internal balances have been updated.
+//! Without a savings token the contract only keeps internal balances, as
+//! before token support.
+//!
+//! The admin may whitelist further assets. A plan or group records the asset
+//! it was opened in; without a record it holds the savings token. Flexi
+//! balances are always in the savings token, so payouts from plans and groups
+//! in another asset go to the user's balance of that asset, which they
+//! withdraw with `withdraw_asset`.
+//!
+//! Amounts are accounted for in canonical precision whatever the asset's
+//! decimals, and converted to token units by `normalize` when tokens move.
+
+use crate::errors::SavingsError;
+use crate::flexi;
+use crate::group;
+use crate::interest;
+use crate::normalize;
+use crate::plans;
+use crate::storage_types::{DataKeyExt, PauseScope, PlanType};
+use crate::strategies;
+use crate::users;
+use crate::withdrawal_queue;
+use crate::{ensure_not_frozen, ensure_not_paused, ensure_not_paused_for};
+use soroban_sdk::{symbol_short, token::TokenClient, Address, Env, Map, Vec};
+
+/// Maximum number of whitelisted assets besides the savings token
+pub const MAX_SUPPORTED_ASSETS: u32 = 10;
+
+/// Decimals of native XLM: amounts are in stroops
+pub const NATIVE_DECIMALS: u32 = 7;
+
+/// Returns the savings token, if one was set at initialization.
+pub fn get_savings_token(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKeyExt::SavingsToken)
+}
+
+/// Whitelists an asset for plans and groups.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If the asset is already supported
+/// * `MaxPlansExceeded` - If `MAX_SUPPORTED_ASSETS` assets are whitelisted
+pub fn add_supported_asset(env: &Env, asset: Address) -> Result<(), SavingsError> {
+    if is_supported(env, &asset) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    let mut assets = get_supported_assets(env);
+    if assets.len() >= MAX_SUPPORTED_ASSETS {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+    assets.push_back(asset.clone());
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::SupportedAssets, &assets);
+    normalize::record_decimals(env, &asset);
+    env.events().publish((symbol_short!("asset_add"),), asset);
+    Ok(())
+}
+
+/// Removes an asset from the whitelist. Existing plans and groups in the
+/// asset keep it, but no new ones can be opened.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `UnsupportedAsset` - If the asset is not whitelisted
+pub fn remove_supported_asset(env: &Env, asset: Address) -> Result<(), SavingsError> {
+    let mut assets = get_supported_assets(env);
+    let index = assets
+        .first_index_of(&asset)
+        .ok_or(SavingsError::UnsupportedAsset)?;
+    assets.remove(index);
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::SupportedAssets, &assets);
+    env.events().publish((symbol_short!("asset_rm"),), asset);
+    Ok(())
+}
+
+/// Returns the whitelisted assets besides the savings token.
+pub fn get_supported_assets(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::SupportedAssets)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Returns the asset a plan holds, or `None` for internal balances.
+pub fn get_plan_asset(env: &Env, user: &Address, plan_id: u64) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::PlanAsset(user.clone(), plan_id))
+        .or_else(|| get_savings_token(env))
+}
+
+/// Returns the asset a group holds, or `None` for internal balances.
+pub fn get_group_asset(env: &Env, group_id: u64) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::GroupAsset(group_id))
+        .or_else(|| get_savings_token(env))
+}
+
+/// Returns the user's balance of an asset other than the savings token.
+pub fn get_asset_balance(env: &Env, user: Address, asset: Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::AssetBalance(user, asset))
+        .unwrap_or(0)
+}
+
+/// Sets the Stellar Asset Contract of native XLM used by `deposit_native`.
+/// It must be the savings token or a whitelisted asset.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `UnsupportedAsset` - If the asset is not supported or does not use
+///   `NATIVE_DECIMALS`
+pub fn set_native_asset(env: &Env, sac: Address) -> Result<(), SavingsError> {
+    if !is_supported(env, &sac) || normalize::get_asset_decimals(env, &sac) != NATIVE_DECIMALS {
+        return Err(SavingsError::UnsupportedAsset);
+    }
+    env.storage().instance().set(&DataKeyExt::NativeAsset, &sac);
+    env.events().publish((symbol_short!("set_xlm"),), sac);
+    Ok(())
+}
+
+/// Returns the Stellar Asset Contract of native XLM, if configured.
+pub fn get_native_asset(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKeyExt::NativeAsset)
+}
+
+/// Deposits native XLM, in stroops, from the user's account.
+///
+/// When XLM is the savings token the deposit goes to the Flexi balance, as
+/// with `deposit_flexi`. Otherwise it is kept in the user's XLM balance,
+/// from which it can be withdrawn with `withdraw_asset`.
+///
+/// # Errors
+/// * `MissingParameter` - If the native asset is not configured
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `UserNotFound` - If the user has no account
+pub fn deposit_native(env: &Env, user: Address, amount: i128) -> Result<(), SavingsError> {
+    let native = get_native_asset(env).ok_or(SavingsError::MissingParameter)?;
+    if is_savings_token(env, &native) {
+        return flexi::flexi_deposit(env.clone(), user, amount);
+    }
+
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if !users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let native = Some(native);
+    credit(env, &user, &native, amount)?;
+    transfer_in(env, &native, &user, amount)?;
+
+    env.events()
+        .publish((symbol_short!("xlm_dep"), user), amount);
+    Ok(())
+}
+
+/// Returns the user's savings by asset, for wallet display.
+///
+/// `User.total_balance` counts plans in every asset, so plans held in other
+/// assets are moved to their own entry, alongside the user's withdrawable
+/// balance of that asset. Without a savings token the remaining internal
+/// balance has no asset and is only reported by `get_user`. Zero balances are
+/// left out.
+///
+/// # Errors
+/// * `UserNotFound` - If the user has no account
+pub fn get_user_balances(env: &Env, user: Address) -> Result<Map<Address, i128>, SavingsError> {
+    let mut in_savings_token = users::get_user(env, &user)?.total_balance;
+    let mut balances: Map<Address, i128> = Map::new(env);
+    let mut add = |asset: Address, amount: i128| {
+        if amount != 0 {
+            let current = balances.get(asset.clone()).unwrap_or(0);
+            balances.set(asset, current.saturating_add(amount));
+        }
+    };
+
+    for plan in plans::get_user_savings_plans(env, user.clone()).iter() {
+        if plan.is_withdrawn || matches!(plan.plan_type, PlanType::Group(..)) {
+            continue;
+        }
+        let asset: Option<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKeyExt::PlanAsset(user.clone(), plan.plan_id));
+        if let Some(asset) = asset {
+            in_savings_token -= plan.balance;
+            add(asset, plan.balance);
+        }
+    }
+    for asset in get_supported_assets(env).iter() {
+        add(asset.clone(), get_asset_balance(env, user.clone(), asset));
+    }
+    if let Some(token) = get_savings_token(env) {
+        add(token, in_savings_token);
+    }
+
+    Ok(balances)
+}
+
+/// Opens a savings plan in a whitelisted asset, collecting the initial
+/// deposit in that asset.
+///
+/// # Errors
+/// * `InvalidAmount` - If `initial_deposit` is not positive
+/// * `UnsupportedAsset` - If the asset is not whitelisted
+/// * `UserNotFound` - If the user has no account
+/// * `InvalidPlanConfig` - If the plan type is invalid or a group plan
+pub fn create_asset_plan(
+    env: &Env,
+    user: Address,
+    plan_type: PlanType,
+    asset: Address,
+    initial_deposit: i128,
+) -> Result<u64, SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+
+    if initial_deposit <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if !is_supported(env, &asset) {
+        return Err(SavingsError::UnsupportedAsset);
+    }
+    if !users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+    if let PlanType::Group(..) = plan_type {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    plans::ensure_valid_plan_type(env, &plan_type)?;
+
+    let other_asset = (!is_savings_token(env, &asset)).then(|| asset.clone());
+    let plan_id = plans::store_new_asset_plan(env, &user, plan_type, initial_deposit, other_asset);
+    transfer_in(env, &Some(asset), &user, initial_deposit)?;
+
+    Ok(plan_id)
+}
+
+/// Adds funds to a savings plan in the plan's asset, collecting them from
+/// the user through `deposit_to_plan`.
+///
+/// # Errors
+/// * `UnsupportedAsset` - If `asset` is not the plan's asset
+/// * Any error of `deposit_to_plan`
+pub fn deposit_asset(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    asset: Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    let plan_asset = get_plan_asset(env, &user, plan_id);
+    if plan_asset.as_ref() != Some(&asset) {
+        return Err(SavingsError::UnsupportedAsset);
+    }
+    plans::deposit_to_plan(env, user, plan_id, amount)
+}
+
+/// Sets the asset a group saves in. Only the creator may set it, and only
+/// before anyone has contributed.
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `Unauthorized` - If the caller is not the creator
+/// * `UnsupportedAsset` - If the asset is not whitelisted
+/// * `InvalidGroupConfig` - If the group already holds contributions
+pub fn set_group_asset(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    asset: Address,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group = group::get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if !is_supported(env, &asset) {
+        return Err(SavingsError::UnsupportedAsset);
+    }
+    if group.current_amount > 0 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    let key = DataKeyExt::GroupAsset(group_id);
+    if is_savings_token(env, &asset) {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &asset);
+    }
+    env.events()
+        .publish((symbol_short!("grp_asset"), group_id), asset);
+    Ok(())
+}
+
+/// Withdraws the user's balance of an asset other than the savings token
+/// to their wallet.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InsufficientBalance` - If the balance can't cover `amount`
+pub fn withdraw_asset(
+    env: &Env,
+    user: Address,
+    asset: Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let balance = get_asset_balance(env, user.clone(), asset.clone());
+    if balance < amount {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    env.storage().persistent().set(
+        &DataKeyExt::AssetBalance(user.clone(), asset.clone()),
+        &(balance - amount),
+    );
+    transfer_out(env, &Some(asset.clone()), &user, amount)?;
+
+    env.events()
+        .publish((symbol_short!("asset_wd"), user, asset), amount);
+    Ok(())
+}
+
+/// Sends tokens that were transferred to the contract by mistake to `to`.
+/// `amount` is in the token's own units.
+///
+/// Only tokens the contract never accounts for can be swept: assets that
+/// are or were supported, the yield asset and the rewards token are
+/// refused, so user balances are never touched.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `Unauthorized` - If the token is part of protocol accounting
+pub fn sweep_token(
+    env: &Env,
+    token: Address,
+    to: Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if is_protocol_asset(env, &token) {
+        return Err(SavingsError::Unauthorized);
+    }
+    TokenClient::new(env, &token).transfer(&env.current_contract_address(), &to, &amount);
+    env.events()
+        .publish((symbol_short!("sweep"), token, to), amount);
+    Ok(())
+}
+
+/// Credits a payout in `asset` to the user: savings-token payouts go to the
+/// Flexi balance, others to the user's balance of that asset, which is not
+/// part of `User.total_balance`.
+pub(crate) fn credit(
+    env: &Env,
+    user: &Address,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    match asset {
+        Some(asset) if !is_savings_token(env, asset) => {
+            if amount < 0 {
+                return Err(SavingsError::InvalidAmount);
+            }
+            let key = DataKeyExt::AssetBalance(user.clone(), asset.clone());
+            let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+            let new_balance = balance.checked_add(amount).ok_or(SavingsError::Overflow)?;
+            env.storage().persistent().set(&key, &new_balance);
+            Ok(())
+        }
+        _ => flexi::credit_flexi(env, user, amount),
+    }
+}
+
+/// Transfers `amount` of the savings token from `from` to the contract.
+/// `from` must authorize the transfer.
+pub(crate) fn pull(env: &Env, from: &Address, amount: i128) -> Result<(), SavingsError> {
+    transfer_in(env, &get_savings_token(env), from, amount)
+}
+
+/// Transfers `amount` of the savings token from the contract to `to`.
+pub(crate) fn push(env: &Env, to: &Address, amount: i128) -> Result<(), SavingsError> {
+    transfer_out(env, &get_savings_token(env), to, amount)
+}
+
+/// Transfers `amount`, in canonical precision, of `asset` from `from` to the
+/// contract, rounded up to the asset's units. Nothing moves without an asset.
+pub(crate) fn transfer_in(
+    env: &Env,
+    asset: &Option<Address>,
+    from: &Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    if amount < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if let Some(asset) = asset {
+        if amount > 0 {
+            let units = normalize::to_units_in(env, asset, amount)?;
+            TokenClient::new(env, asset).transfer(from, env.current_contract_address(), &units);
+        }
+    }
+    Ok(())
+}
+
+/// Transfers `amount`, in canonical precision, of `asset` from the contract
+/// to `to`, rounded down to the asset's units. Nothing moves without an
+/// asset. A withdrawal the liquidity buffer can't cover is queued instead.
+pub(crate) fn transfer_out(
+    env: &Env,
+    asset: &Option<Address>,
+    to: &Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    if amount < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if let Some(asset) = asset {
+        let units = normalize::to_units_out(env, asset, amount)?;
+        if units > 0 {
+            if withdrawal_queue::must_queue(env, asset, units) {
+                withdrawal_queue::enqueue(env, to, asset, amount);
+            } else {
+                TokenClient::new(env, asset).transfer(&env.current_contract_address(), to, &units);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Burns `amount`, in canonical precision, of the savings token held by the
+/// contract, rounded down to the token's units. Nothing moves without a
+/// savings token.
+pub(crate) fn burn(env: &Env, amount: i128) -> Result<(), SavingsError> {
+    if amount < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if let Some(token) = get_savings_token(env) {
+        let units = normalize::to_units_out(env, &token, amount)?;
+        if units > 0 {
+            TokenClient::new(env, &token).burn(&env.current_contract_address(), &units);
+        }
+    }
+    Ok(())
+}
+
+/// Whether a plan holds an asset other than the savings token.
+pub(crate) fn holds_other_asset(env: &Env, user: &Address, plan_id: u64) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKeyExt::PlanAsset(user.clone(), plan_id))
+}
+
+pub(crate) fn is_savings_token(env: &Env, asset: &Address) -> bool {
+    get_savings_token(env).as_ref() == Some(asset)
+}
+
+/// Whether the contract accounts for balances of `asset`.
+fn is_protocol_asset(env: &Env, asset: &Address) -> bool {
+    is_supported(env, asset)
+        || normalize::is_recorded(env, asset)
+        || strategies::get_yield_config(env).is_some_and(|config| &config.asset == asset)
+        || interest::get_rewards_token(env).is_some_and(|rewards| &rewards.token == asset)
+}
+
+fn is_supported(env: &Env, asset: &Address) -> bool {
+    is_savings_token(env, asset) || get_supported_assets(env).contains(asset)
+}
@@ -0,0 +1,258 @@
+use crate::{NesteraContract, NesteraContractClient, PlanType, SavingsError};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env, String,
+};
+
+const YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// Contract using a savings token, with a second asset minted to the user
+/// but not yet whitelisted.
+fn setup() -> (
+    Env,
+    NesteraContractClient<'static>,
+    Address,
+    Address,
+    Address,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let usdc = env
+        .register_stellar_asset_contract_v2(issuer.clone())
+        .address();
+    let eurc = env.register_stellar_asset_contract_v2(issuer).address();
+
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(usdc.clone()),
+    );
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &usdc).mint(&user, &10_000);
+    StellarAssetClient::new(&env, &eurc).mint(&user, &10_000);
+
+    (env, client, user, usdc, eurc)
+}
+
+#[test]
+fn test_supported_assets_whitelist() {
+    let (_env, client, user, usdc, eurc) = setup();
+    assert_eq!(
+        client.try_create_asset_plan(&user, &PlanType::Lock(YEAR), &eurc, &1_000),
+        Err(Ok(SavingsError::UnsupportedAsset))
+    );
+
+    client.add_supported_asset(&eurc);
+    assert_eq!(client.get_supported_assets().len(), 1);
+    assert_eq!(
+        client.try_add_supported_asset(&eurc),
+        Err(Ok(SavingsError::InvalidPlanConfig))
+    );
+    // The savings token is always supported
+    assert_eq!(
+        client.try_add_supported_asset(&usdc),
+        Err(Ok(SavingsError::InvalidPlanConfig))
+    );
+
+    client.remove_supported_asset(&eurc);
+    assert_eq!(client.get_supported_assets().len(), 0);
+    assert_eq!(
+        client.try_remove_supported_asset(&eurc),
+        Err(Ok(SavingsError::UnsupportedAsset))
+    );
+}
+
+#[test]
+fn test_asset_plan_takes_and_pays_out_its_own_asset() {
+    let (env, client, user, usdc, eurc) = setup();
+    client.add_supported_asset(&eurc);
+    let eurc_balances = TokenClient::new(&env, &eurc);
+
+    let plan_id = client.create_asset_plan(&user, &PlanType::Lock(YEAR), &eurc, &1_000);
+    assert_eq!(client.get_plan_asset(&user, &plan_id), Some(eurc.clone()));
+    assert_eq!(eurc_balances.balance(&user), 9_000);
+
+    assert_eq!(
+        client.try_deposit_asset(&user, &plan_id, &usdc, &500),
+        Err(Ok(SavingsError::UnsupportedAsset))
+    );
+    client.deposit_asset(&user, &plan_id, &eurc, &1_000);
+    assert_eq!(eurc_balances.balance(&client.address), 2_000);
+
+    // The matured plan pays out in EURC, not into the USDC Flexi balance
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    let payout = client.withdraw_lock(&user, &plan_id);
+    assert_eq!(client.get_flexi_balance(&user), 0);
+    assert_eq!(client.get_asset_balance(&user, &eurc), payout);
+
+    client.withdraw_asset(&user, &eurc, &2_000);
+    assert_eq!(eurc_balances.balance(&user), 10_000);
+    assert_eq!(
+        client.try_withdraw_asset(&user, &eurc, &payout),
+        Err(Ok(SavingsError::InsufficientBalance))
+    );
+}
+
+#[test]
+fn test_group_collects_and_refunds_its_asset() {
+    let (env, client, user, _usdc, eurc) = setup();
+    client.add_supported_asset(&eurc);
+    let group_id = client.create_group_save(
+        &user,
+        &String::from_str(&env, "Trip"),
+        &String::from_str(&env, "Description"),
+        &String::from_str(&env, "travel"),
+        &5_000,
+        &0,
+        &100,
+        &true,
+        &1,
+        &YEAR,
+    );
+    let member = Address::generate(&env);
+    client.initialize_user(&member);
+    StellarAssetClient::new(&env, &eurc).mint(&member, &1_000);
+    client.join_group_save(&member, &group_id);
+
+    client.set_group_asset(&user, &group_id, &eurc);
+    client.contribute_to_group_save(&member, &group_id, &400);
+    assert_eq!(TokenClient::new(&env, &eurc).balance(&member), 600);
+    assert_eq!(
+        client.try_set_group_asset(&user, &group_id, &eurc),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+
+    assert_eq!(client.leave_group_save(&member, &group_id), 400);
+    assert_eq!(client.get_asset_balance(&member, &eurc), 400);
+    assert_eq!(client.get_flexi_balance(&member), 0);
+}
+
+#[test]
+fn test_user_balances_are_reported_per_asset() {
+    let (env, client, user, usdc, eurc) = setup();
+    client.add_supported_asset(&eurc);
+
+    client.deposit_flexi(&user, &1_500);
+    let plan_id = client.create_asset_plan(&user, &PlanType::Lock(YEAR), &eurc, &2_000);
+    client.create_asset_plan(&user, &PlanType::Lock(YEAR), &usdc, &500);
+
+    let balances = client.get_user_balances(&user);
+    assert_eq!(balances.len(), 2);
+    assert_eq!(balances.get(usdc.clone()), Some(2_000));
+    assert_eq!(balances.get(eurc.clone()), Some(2_000));
+
+    // Paid-out EURC stays in the user's EURC balance until withdrawn
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    let payout = client.withdraw_lock(&user, &plan_id);
+    let balances = client.get_user_balances(&user);
+    assert_eq!(balances.get(eurc), Some(payout));
+    assert_eq!(balances.get(usdc), Some(2_000));
+}
+
+#[test]
+fn test_early_break_penalty_in_other_asset_goes_to_treasurer_without_recipient() {
+    let (_env, client, user, _usdc, eurc) = setup();
+    client.add_supported_asset(&eurc);
+    client.set_early_break_fee_bps(&1_000);
+
+    let plan_id = client.create_asset_plan(&user, &PlanType::Lock(YEAR), &eurc, &2_000);
+    assert_eq!(client.early_withdraw_lock(&user, &plan_id), 1_800);
+    assert_eq!(client.get_asset_balance(&user, &eurc), 1_800);
+
+    // With no fee recipient set the penalty is not stranded
+    let treasurer = client.get_treasurer();
+    assert_eq!(client.get_asset_balance(&treasurer, &eurc), 200);
+}
+
+#[test]
+fn test_deposit_native_xlm() {
+    let (env, client, user, usdc, _eurc) = setup();
+    let xlm = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    StellarAssetClient::new(&env, &xlm).mint(&user, &50_000_000);
+
+    assert_eq!(
+        client.try_deposit_native(&user, &10_000_000),
+        Err(Ok(SavingsError::MissingParameter))
+    );
+    assert_eq!(
+        client.try_set_native_asset(&xlm),
+        Err(Ok(SavingsError::UnsupportedAsset))
+    );
+
+    client.add_supported_asset(&xlm);
+    client.set_native_asset(&xlm);
+    // 1.5 XLM in stroops
+    client.deposit_native(&user, &15_000_000);
+    assert_eq!(client.get_asset_balance(&user, &xlm), 15_000_000);
+    assert_eq!(client.get_flexi_balance(&user), 0);
+    assert_eq!(
+        TokenClient::new(&env, &xlm).balance(&client.address),
+        15_000_000
+    );
+    let balances = client.get_user_balances(&user);
+    assert_eq!(balances.get(xlm), Some(15_000_000));
+    assert_eq!(balances.get(usdc), None);
+}
+
+#[test]
+fn test_deposit_native_goes_to_flexi_when_xlm_is_the_savings_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let xlm = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    client.initialize(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(xlm.clone()),
+    );
+    client.set_native_asset(&xlm);
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &xlm).mint(&user, &10_000_000);
+
+    client.deposit_native(&user, &10_000_000);
+    assert_eq!(client.get_flexi_balance(&user), 10_000_000);
+    assert_eq!(TokenClient::new(&env, &xlm).balance(&user), 0);
+}
+
+#[test]
+fn test_sweep_token_only_rescues_unaccounted_tokens() {
+    let (env, client, user, usdc, eurc) = setup();
+    let stray = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    StellarAssetClient::new(&env, &stray).mint(&client.address, &700);
+    client.deposit_flexi(&user, &1_000);
+
+    client.sweep_token(&stray, &user, &700);
+    assert_eq!(TokenClient::new(&env, &stray).balance(&user), 700);
+
+    assert_eq!(
+        client.try_sweep_token(&usdc, &user, &1_000),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+    // A removed asset may still back existing plans
+    client.add_supported_asset(&eurc);
+    client.remove_supported_asset(&eurc);
+    assert_eq!(
+        client.try_sweep_token(&eurc, &user, &1),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_sweep_token(&stray, &user, &0),
+        Err(Ok(SavingsError::InvalidAmount))
+    );
+}
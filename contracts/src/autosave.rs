@@ -1,8 +1,17 @@
+use crate::assets;
+use crate::config;
+use crate::ensure_not_paused;
 use crate::errors::SavingsError;
 use crate::flexi;
-use crate::storage_types::{AutoSave, DataKey};
+use crate::normalize;
+use crate::plans;
+use crate::storage_types::{AutoSave, DataKey, DataKeyExt, PlanAutoSave, PlanType, SavingsPlan};
 use crate::users;
-use soroban_sdk::{Address, Env, Vec};
+use soroban_sdk::{symbol_short, token::TokenClient, Address, Env, Vec};
+
+/// Default number of consecutive failed debits after which a plan AutoSave
+/// is switched off; see `ProtocolConfig::max_autosave_failures`
+pub const MAX_AUTOSAVE_FAILURES: u32 = 3;
 
 /// Creates a new AutoSave schedule for recurring Flexi deposits
 ///
@@ -160,6 +169,153 @@ pub fn get_user_autosaves(env: &Env, user: &Address) -> Vec<u64> {
         .unwrap_or(Vec::new(env))
 }
 
+/// Sets up a recurring debit into one of the user's savings plans, replacing
+/// any existing one for the plan.
+///
+/// The user grants the contract an allowance on the plan's token; a keeper
+/// then calls `execute_plan_autosave` each interval, which pulls `amount`
+/// with `transfer_from`. The first debit is due one interval from now.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InvalidTimestamp` - If `interval` is zero
+/// * `PlanNotFound` - If the user has no such plan
+/// * `PlanCompleted` - If the plan was already withdrawn
+/// * `InvalidPlanConfig` - If the plan is a group plan
+/// * `MissingParameter` - If the plan has no token to debit
+pub fn setup_autosave(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    amount: i128,
+    interval: u64,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if interval == 0 {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    if plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if let PlanType::Group(..) = plan.plan_type {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if assets::get_plan_asset(env, &user, plan_id).is_none() {
+        return Err(SavingsError::MissingParameter);
+    }
+
+    let next_execution_time = env
+        .ledger()
+        .timestamp()
+        .checked_add(interval)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage().persistent().set(
+        &DataKeyExt::PlanAutoSave(user.clone(), plan_id),
+        &PlanAutoSave {
+            amount,
+            interval,
+            next_execution_time,
+            failures: 0,
+        },
+    );
+    env.events().publish(
+        (symbol_short!("as_setup"), user, plan_id),
+        (amount, interval),
+    );
+    Ok(())
+}
+
+/// Pulls a due plan AutoSave debit from the user's allowance into the plan.
+///
+/// Anyone (typically a keeper) may call this. If the allowance or the user's
+/// token balance can't cover the debit, the period is skipped and counted as
+/// a failure; after `max_autosave_failures` failures in a row the AutoSave
+/// is removed.
+///
+/// # Returns
+/// `Ok(true)` if the amount was debited, `Ok(false)` if the period failed
+///
+/// # Errors
+/// * `PlanNotFound` - If the plan has no AutoSave
+/// * `TooEarly` - If the next debit is not yet due
+/// * Any error of depositing into the plan
+pub fn execute_plan_autosave(env: &Env, user: Address, plan_id: u64) -> Result<bool, SavingsError> {
+    ensure_not_paused(env)?;
+
+    let key = DataKeyExt::PlanAutoSave(user.clone(), plan_id);
+    let mut schedule: PlanAutoSave = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(SavingsError::PlanNotFound)?;
+    let now = env.ledger().timestamp();
+    if now < schedule.next_execution_time {
+        return Err(SavingsError::TooEarly);
+    }
+    schedule.next_execution_time = schedule
+        .next_execution_time
+        .checked_add(schedule.interval)
+        .ok_or(SavingsError::Overflow)?;
+
+    let token =
+        assets::get_plan_asset(env, &user, plan_id).ok_or(SavingsError::MissingParameter)?;
+    let units = normalize::to_units_in(env, &token, schedule.amount)?;
+    let client = TokenClient::new(env, &token);
+    let contract = env.current_contract_address();
+    if client.allowance(&user, &contract) < units || client.balance(&user) < units {
+        schedule.failures += 1;
+        if schedule.failures >= config::get_config(env).max_autosave_failures {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &schedule);
+        }
+        env.events()
+            .publish((symbol_short!("as_fail"), user, plan_id), schedule.failures);
+        return Ok(false);
+    }
+
+    schedule.failures = 0;
+    env.storage().persistent().set(&key, &schedule);
+    plans::credit_plan(env, user.clone(), plan_id, schedule.amount)?;
+    client.transfer_from(&contract, &user, &contract, &units);
+
+    env.events()
+        .publish((symbol_short!("as_exec"), user, plan_id), schedule.amount);
+    Ok(true)
+}
+
+/// Stops a plan's AutoSave.
+///
+/// # Errors
+/// * `PlanNotFound` - If the plan has no AutoSave
+pub fn cancel_plan_autosave(env: &Env, user: Address, plan_id: u64) -> Result<(), SavingsError> {
+    user.require_auth();
+
+    let key = DataKeyExt::PlanAutoSave(user, plan_id);
+    if !env.storage().persistent().has(&key) {
+        return Err(SavingsError::PlanNotFound);
+    }
+    env.storage().persistent().remove(&key);
+    Ok(())
+}
+
+/// Gets a plan's AutoSave, if set up.
+pub fn get_plan_autosave(env: &Env, user: Address, plan_id: u64) -> Option<PlanAutoSave> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::PlanAutoSave(user, plan_id))
+}
+
 // ========== Helper Functions ==========
 
 fn get_next_schedule_id(env: &Env) -> u64 {
@@ -0,0 +1,117 @@
+use crate::{NesteraContract, NesteraContractClient, PlanType, SavingsError};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn setup() -> (Env, NesteraContractClient<'static>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+
+    (env, client, user, token)
+}
+
+#[test]
+fn test_plan_autosave_pulls_from_allowance_each_interval() {
+    let (env, client, user, token) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &0);
+    let balances = TokenClient::new(&env, &token);
+    balances.approve(&user, &client.address, &250, &1_000);
+
+    client.setup_autosave(&user, &plan_id, &100, &DAY);
+    assert_eq!(
+        client.try_execute_plan_autosave(&user, &plan_id),
+        Err(Ok(SavingsError::TooEarly))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+    assert!(client.execute_plan_autosave(&user, &plan_id));
+    env.ledger().with_mut(|li| li.timestamp = 2 * DAY);
+    assert!(client.execute_plan_autosave(&user, &plan_id));
+
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        200
+    );
+    assert_eq!(client.get_user(&user).total_balance, 200);
+    assert_eq!(balances.balance(&user), 800);
+    assert_eq!(balances.balance(&client.address), 200);
+    assert_eq!(
+        client
+            .get_plan_autosave(&user, &plan_id)
+            .unwrap()
+            .next_execution_time,
+        3 * DAY
+    );
+}
+
+#[test]
+fn test_plan_autosave_stops_after_repeated_failures() {
+    let (env, client, user, token) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &0);
+    // Allowance covers one debit only
+    TokenClient::new(&env, &token).approve(&user, &client.address, &100, &1_000);
+    client.setup_autosave(&user, &plan_id, &100, &DAY);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+    assert!(client.execute_plan_autosave(&user, &plan_id));
+    for period in 2..=3 {
+        env.ledger().with_mut(|li| li.timestamp = period * DAY);
+        assert!(!client.execute_plan_autosave(&user, &plan_id));
+    }
+    assert_eq!(
+        client.get_plan_autosave(&user, &plan_id).unwrap().failures,
+        2
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 4 * DAY);
+    assert!(!client.execute_plan_autosave(&user, &plan_id));
+    assert_eq!(client.get_plan_autosave(&user, &plan_id), None);
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        100
+    );
+}
+
+#[test]
+fn test_setup_autosave_validation() {
+    let (_env, client, user, _token) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &0);
+    assert_eq!(
+        client.try_setup_autosave(&user, &plan_id, &0, &DAY),
+        Err(Ok(SavingsError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_setup_autosave(&user, &plan_id, &100, &0),
+        Err(Ok(SavingsError::InvalidTimestamp))
+    );
+    assert_eq!(
+        client.try_setup_autosave(&user, &99, &100, &DAY),
+        Err(Ok(SavingsError::PlanNotFound))
+    );
+
+    client.setup_autosave(&user, &plan_id, &100, &DAY);
+    client.cancel_plan_autosave(&user, &plan_id);
+    assert_eq!(
+        client.try_cancel_plan_autosave(&user, &plan_id),
+        Err(Ok(SavingsError::PlanNotFound))
+    );
+}
@@ -0,0 +1,110 @@
+//! Protocol-wide parameters, kept in one struct the admin can update.
+//!
+//! Each parameter defaults to the constant it replaced, so deployments that
+//! never called `update_config` behave as before.
+//!
+//! `ProtocolConfig` is the only place these parameters are stored. The
+//! dedicated setters, such as `set_early_break_fee_bps` or
+//! `set_transfer_fees`, update their fields of it and share its validation.
+//! Interest rates, fee tiers and other tables live with the modules that
+//! read them, addresses such as the fee recipient and treasurer are kept on
+//! their own, and Flexi withdrawal cooldowns are opt-in settings of each
+//! user rather than protocol parameters.
+
+use crate::approvals::WITHDRAWAL_REQUEST_TTL;
+use crate::autosave::MAX_AUTOSAVE_FAILURES;
+use crate::errors::SavingsError;
+use crate::group::MAX_WAITLIST_SIZE;
+use crate::storage_types::{ControlDataKey, DataKey, GroupCreationFee, ProtocolConfig};
+use crate::voting::GROUP_CHANGE_TTL;
+use soroban_sdk::{symbol_short, Env, Map};
+
+/// Returns the protocol parameters.
+pub fn get_config(env: &Env) -> ProtocolConfig {
+    env.storage()
+        .instance()
+        .get(&ControlDataKey::ProtocolConfig)
+        .unwrap_or(ProtocolConfig {
+            min_deposit: 0,
+            early_break_fee_bps: 0,
+            deposit_fee_bps: 0,
+            withdrawal_fee_bps: 0,
+            performance_fee_bps: Map::new(env),
+            referral_share_bps: 0,
+            group_creation_fee: GroupCreationFee::None,
+            max_group_members: 0,
+            max_waitlist_size: MAX_WAITLIST_SIZE,
+            withdrawal_request_ttl: WITHDRAWAL_REQUEST_TTL,
+            group_change_ttl: GROUP_CHANGE_TTL,
+            max_autosave_failures: MAX_AUTOSAVE_FAILURES,
+        })
+}
+
+/// Replaces the protocol parameters.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `min_deposit` is negative, a fee or share exceeds
+///   10_000 bps, a flat group creation fee is not positive, or a size limit,
+///   TTL or failure limit is zero
+pub(crate) fn update_config(env: &Env, config: ProtocolConfig) -> Result<(), SavingsError> {
+    let group_fee_valid = match config.group_creation_fee {
+        GroupCreationFee::None => true,
+        GroupCreationFee::Flat(amount) => amount > 0,
+        GroupCreationFee::Bps(bps) => bps <= 10_000,
+    };
+    if config.min_deposit < 0
+        || config.early_break_fee_bps > 10_000
+        || config.deposit_fee_bps > 10_000
+        || config.withdrawal_fee_bps > 10_000
+        || config
+            .performance_fee_bps
+            .values()
+            .iter()
+            .any(|bps| bps > 10_000)
+        || config.referral_share_bps > 10_000
+        || !group_fee_valid
+        || config.max_waitlist_size == 0
+        || config.withdrawal_request_ttl == 0
+        || config.group_change_ttl == 0
+        || config.max_autosave_failures == 0
+    {
+        return Err(SavingsError::InvalidAmount);
+    }
+    store(env, &config);
+    Ok(())
+}
+
+/// Fails if a deposit is below the minimum deposit.
+///
+/// # Errors
+/// * `AmountBelowMinimum` - If `amount` is below `min_deposit`
+pub(crate) fn ensure_min_deposit(env: &Env, amount: i128) -> Result<(), SavingsError> {
+    if amount < get_config(env).min_deposit {
+        return Err(SavingsError::AmountBelowMinimum);
+    }
+    Ok(())
+}
+
+/// Moves the early break fee from its own key, where schema version 1 kept
+/// it, into the protocol parameters.
+pub(crate) fn migrate_early_break_fee(env: &Env) {
+    let key = DataKey::EarlyBreakFeeBps;
+    if let Some(bps) = env.storage().instance().get::<_, u32>(&key) {
+        let mut config = get_config(env);
+        config.early_break_fee_bps = bps;
+        env.storage()
+            .instance()
+            .set(&ControlDataKey::ProtocolConfig, &config);
+        env.storage().instance().remove(&key);
+    }
+}
+
+pub(crate) fn store(env: &Env, config: &ProtocolConfig) {
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::ProtocolConfig, config);
+    env.events()
+        .publish((symbol_short!("config"),), config.clone());
+}
@@ -0,0 +1,236 @@
+use crate::ensure_not_paused;
+use crate::errors::SavingsError;
+use crate::group;
+use crate::storage_types::{DataKey, GroupSave, MemberCycleRecord, MemberCycleStatus};
+use soroban_sdk::{Address, Env};
+
+/// Enables per-cycle contribution tracking for a group.
+///
+/// Cycles are counted from the group's `start_time`; in each cycle every
+/// member is expected to contribute at least `contribution_amount`.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `cycle_duration` - Cycle length in seconds (e.g. 604800 for weekly); 0 disables tracking
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `Unauthorized` - If the caller is not the group creator
+/// * `InvalidGroupConfig` - If cycles would change after contributions were made
+pub fn set_group_cycle_duration(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    cycle_duration: u64,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let mut group = group::get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    // Recorded cycle progress would no longer line up with the new cycles
+    if group.current_amount != 0 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    group.cycle_duration = cycle_duration;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupSave(group_id), &group);
+
+    Ok(())
+}
+
+/// Sets the amount due per cycle and the penalty for missing it.
+///
+/// Members who have not contributed the amount due by the end of a cycle
+/// accrue `late_penalty_bps` of that amount as a penalty, which is deducted
+/// from their share at distribution and shared among members who are not
+/// behind.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `required_amount_per_cycle` - Amount due per cycle; `contribution_amount` when `None`
+/// * `late_penalty_bps` - Penalty per missed cycle in basis points (0-10_000)
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `Unauthorized` - If the caller is not the group creator
+/// * `InvalidAmount` - If the amount due is not positive or bps exceed 10_000
+pub fn set_group_cycle_requirements(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    required_amount_per_cycle: Option<i128>,
+    late_penalty_bps: u32,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let mut group = group::get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if required_amount_per_cycle.is_some_and(|amount| amount <= 0) || late_penalty_bps > 10_000 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    group.required_amount_per_cycle = required_amount_per_cycle;
+    group.late_penalty_bps = late_penalty_bps;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupSave(group_id), &group);
+
+    Ok(())
+}
+
+/// Returns how many cycles a member has paid and missed.
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `InvalidGroupConfig` - If the group doesn't track cycles
+/// * `NotGroupMember` - If the user is not a member of the group
+pub fn get_member_cycle_status(
+    env: &Env,
+    group_id: u64,
+    user: &Address,
+) -> Result<MemberCycleStatus, SavingsError> {
+    let group = group::get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.cycle_duration == 0 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    if !group::is_member(env, group_id, user) {
+        return Err(SavingsError::NotGroupMember);
+    }
+
+    let cycle = current_cycle(env, &group);
+    let record = get_record(env, &group, user);
+    let paid_current = paid_current(&group, &record, cycle);
+    let paid_past = record.paid_cycles - paid_current as u32;
+    let missed_cycles = missed_cycles(env, &group, user);
+
+    Ok(MemberCycleStatus {
+        current_cycle: cycle,
+        paid_cycles: paid_past,
+        missed_cycles,
+        paid_current,
+        late_penalty: penalty_for(&group, missed_cycles),
+    })
+}
+
+/// Returns the number of finished cycles since joining in which the member
+/// did not pay the amount due.
+pub(crate) fn missed_cycles(env: &Env, group: &GroupSave, user: &Address) -> u32 {
+    if group.cycle_duration == 0 {
+        return 0;
+    }
+    let cycle = current_cycle(env, group);
+    let record = get_record(env, group, user);
+    let paid_past = record.paid_cycles - paid_current(group, &record, cycle) as u32;
+    cycle
+        .saturating_sub(record.joined_cycle)
+        .saturating_sub(paid_past)
+}
+
+/// Returns the late penalty a member has accrued for missed cycles.
+pub(crate) fn late_penalty(env: &Env, group: &GroupSave, user: &Address) -> i128 {
+    if group.late_penalty_bps == 0 {
+        return 0;
+    }
+    penalty_for(group, missed_cycles(env, group, user))
+}
+
+/// Remembers the cycle a member joined in, so earlier cycles aren't missed.
+pub(crate) fn record_join(env: &Env, group: &GroupSave, user: &Address) {
+    if group.cycle_duration == 0 {
+        return;
+    }
+    let cycle = current_cycle(env, group);
+    let record = MemberCycleRecord {
+        joined_cycle: cycle,
+        paid_cycles: 0,
+        current_cycle: cycle,
+        cycle_amount: 0,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupMemberCycle(group.id, user.clone()), &record);
+}
+
+/// Adds a contribution to the member's current cycle and marks the cycle
+/// paid once the amount due has been reached.
+pub(crate) fn record_cycle_contribution(
+    env: &Env,
+    group: &GroupSave,
+    user: &Address,
+    amount: i128,
+) {
+    if group.cycle_duration == 0 {
+        return;
+    }
+
+    let cycle = current_cycle(env, group);
+    let mut record = get_record(env, group, user);
+    if record.current_cycle != cycle {
+        record.current_cycle = cycle;
+        record.cycle_amount = 0;
+    }
+
+    let due = amount_due(group);
+    let was_paid = record.cycle_amount >= due;
+    record.cycle_amount = record.cycle_amount.saturating_add(amount);
+    if !was_paid && record.cycle_amount >= due {
+        record.paid_cycles += 1;
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupMemberCycle(group.id, user.clone()), &record);
+}
+
+// ========== Helper Functions ==========
+
+/// Cycles stop advancing at the group's `end_time`.
+fn current_cycle(env: &Env, group: &GroupSave) -> u32 {
+    let now = env.ledger().timestamp().min(group.end_time);
+    let elapsed = now.saturating_sub(group.start_time);
+    (elapsed / group.cycle_duration) as u32
+}
+
+fn amount_due(group: &GroupSave) -> i128 {
+    group
+        .required_amount_per_cycle
+        .unwrap_or(group.contribution_amount)
+}
+
+fn paid_current(group: &GroupSave, record: &MemberCycleRecord, cycle: u32) -> bool {
+    record.current_cycle == cycle && record.cycle_amount >= amount_due(group)
+}
+
+fn penalty_for(group: &GroupSave, missed_cycles: u32) -> i128 {
+    amount_due(group)
+        .saturating_mul(missed_cycles as i128)
+        .saturating_mul(group.late_penalty_bps as i128)
+        / 10_000
+}
+
+/// Members without a record (such as the creator) joined in cycle 0.
+fn get_record(env: &Env, group: &GroupSave, user: &Address) -> MemberCycleRecord {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GroupMemberCycle(group.id, user.clone()))
+        .unwrap_or(MemberCycleRecord {
+            joined_cycle: 0,
+            paid_cycles: 0,
+            current_cycle: 0,
+            cycle_amount: 0,
+        })
+}
@@ -33,6 +33,12 @@ pub enum SavingsError {
     /// This prevents duplicate user entries and maintains data integrity.
     UserAlreadyExists = 11,
 
+    /// Returned when a frozen account tries to deposit or withdraw.
+    ///
+    /// The admin freezes accounts on compliance requests or when they are
+    /// compromised; `get_account_freeze` reports why.
+    FrozenAccount = 12,
+
     // ========== Savings Plan Errors (20-39) ==========
     /// Returned when attempting to access a savings plan that does not exist.
     ///
@@ -70,6 +76,12 @@ pub enum SavingsError {
     /// - Required fields are missing for specific plan types
     InvalidPlanConfig = 25,
 
+    /// Returned when withdrawing from a plan the user has frozen.
+    ///
+    /// Withdrawals reopen once the plan is unfrozen and any unfreeze delay
+    /// has passed.
+    PlanFrozen = 26,
+
     // ========== Balance and Amount Errors (40-49) ==========
     /// Returned when attempting to withdraw more than the available balance.
     ///
@@ -93,6 +105,9 @@ pub enum SavingsError {
     /// Some operations may require minimum amounts for efficiency or viability.
     AmountBelowMinimum = 43,
 
+    /// Returned when a swap returns less than the caller's minimum output.
+    SlippageExceeded = 44,
+
     // ========== Timestamp and Time-Related Errors (50-59) ==========
     /// Returned when timestamps are invalid or inconsistent.
     ///
@@ -114,6 +129,12 @@ pub enum SavingsError {
     /// This may apply to time-limited offers or expiring opportunities.
     TooLate = 52,
 
+    /// Returned when a Flexi withdrawal falls within the user's self-imposed
+    /// cooldown since their last withdrawal.
+    ///
+    /// The remaining time can be queried with `get_flexi_cooldown_remaining`.
+    CooldownActive = 53,
+
     // ========== Interest and Yield Errors (60-69) ==========
     /// Returned when the specified interest rate is invalid.
     ///
@@ -149,6 +170,22 @@ pub enum SavingsError {
     /// This includes invalid member counts, contribution amounts, or schedules.
     InvalidGroupConfig = 73,
 
+    /// Returned when a group proposal is executed before enough approvals were collected.
+    ///
+    /// Groups with an approval policy require M of N designated approvers to
+    /// sign off before pooled funds can leave the group.
+    InsufficientApprovals = 74,
+
+    /// Returned when a group contribution is below the group's minimum.
+    ///
+    /// Groups may require each contribution to meet a minimum amount.
+    ContributionTooSmall = 75,
+
+    /// Returned when a contribution would take a member past the group's cap.
+    ///
+    /// Groups may limit how much any single member contributes in total.
+    ContributionLimitExceeded = 76,
+
     // ========== General Contract Errors (80-99) ==========
     /// Returned when a required parameter is missing or null.
     ///
@@ -199,6 +236,14 @@ pub enum SavingsError {
     ///
     /// This prevents state transitions that would break core assumptions.
     InvariantViolation = 89,
+
+    /// Returned when the price oracle has no recent enough price and the
+    /// fallback policy is to reject.
+    StalePrice = 90,
+
+    /// Returned when the admin has paused one kind of operation, such as
+    /// deposits or withdrawals, while the rest of the contract stays open.
+    ProtocolPaused = 91,
 }
 
 #[cfg(test)]
@@ -213,25 +258,32 @@ mod tests {
             SavingsError::Unauthorized as u32,
             SavingsError::UserNotFound as u32,
             SavingsError::UserAlreadyExists as u32,
+            SavingsError::FrozenAccount as u32,
             SavingsError::PlanNotFound as u32,
             SavingsError::DuplicatePlanId as u32,
             SavingsError::PlanLocked as u32,
             SavingsError::PlanCompleted as u32,
             SavingsError::MaxPlansExceeded as u32,
             SavingsError::InvalidPlanConfig as u32,
+            SavingsError::PlanFrozen as u32,
             SavingsError::InsufficientBalance as u32,
             SavingsError::InvalidAmount as u32,
             SavingsError::AmountExceedsLimit as u32,
             SavingsError::AmountBelowMinimum as u32,
+            SavingsError::SlippageExceeded as u32,
             SavingsError::InvalidTimestamp as u32,
             SavingsError::TooEarly as u32,
             SavingsError::TooLate as u32,
+            SavingsError::CooldownActive as u32,
             SavingsError::InvalidInterestRate as u32,
             SavingsError::YieldCalculationError as u32,
             SavingsError::GroupFull as u32,
             SavingsError::NotGroupMember as u32,
             SavingsError::GroupCycleIncomplete as u32,
             SavingsError::InvalidGroupConfig as u32,
+            SavingsError::InsufficientApprovals as u32,
+            SavingsError::ContributionTooSmall as u32,
+            SavingsError::ContributionLimitExceeded as u32,
             SavingsError::MissingParameter as u32,
             SavingsError::DataCorruption as u32,
             SavingsError::Overflow as u32,
@@ -242,6 +294,8 @@ mod tests {
             SavingsError::UnsupportedAsset as u32,
             SavingsError::InvalidSignature as u32,
             SavingsError::InvariantViolation as u32,
+            SavingsError::StalePrice as u32,
+            SavingsError::ProtocolPaused as u32,
         ];
 
         let mut sorted = errors.clone();
@@ -0,0 +1,505 @@
+use crate::assets;
+use crate::config;
+use crate::errors::SavingsError;
+use crate::flexi;
+use crate::storage_types::{
+    ControlDataKey, DataKey, FeeConfig, FeeStats, FeeTier, GroupCreationFee, PlanKind,
+};
+use crate::users;
+use soroban_sdk::{symbol_short, Address, Env, Map, Vec};
+
+/// Types of fee counted in `FeeStats`
+pub(crate) enum FeeKind {
+    Deposit,
+    Withdrawal,
+    Performance,
+    Penalty,
+}
+
+/// Sets the fee charged when a group is created.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If a flat fee is not positive or bps exceed 10_000
+pub fn set_group_creation_fee(env: &Env, fee: GroupCreationFee) -> Result<(), SavingsError> {
+    let mut config = config::get_config(env);
+    config.group_creation_fee = fee.clone();
+    config::update_config(env, config)?;
+
+    env.events().publish((symbol_short!("set_gfee"),), fee);
+    Ok(())
+}
+
+/// Sets the fee, in basis points, charged on early breaks.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `bps` exceeds 10_000
+pub fn set_early_break_fee_bps(env: &Env, bps: u32) -> Result<(), SavingsError> {
+    let mut config = config::get_config(env);
+    config.early_break_fee_bps = bps;
+    config::update_config(env, config)?;
+    env.events().publish((symbol_short!("set_brk"),), bps);
+    Ok(())
+}
+
+/// Sets the address protocol fees are paid to.
+///
+/// The caller is responsible for checking admin authorization.
+pub fn set_fee_recipient(env: &Env, recipient: Address) {
+    env.storage()
+        .instance()
+        .set(&DataKey::FeeRecipient, &recipient);
+    env.events().publish((symbol_short!("set_fee"),), recipient);
+}
+
+/// Returns the protocol's current fee settings.
+pub fn get_fee_config(env: &Env) -> FeeConfig {
+    let config = config::get_config(env);
+    FeeConfig {
+        group_creation_fee: config.group_creation_fee,
+        early_break_fee_bps: config.early_break_fee_bps,
+        fee_recipient: env.storage().instance().get(&DataKey::FeeRecipient),
+        deposit_fee_bps: config.deposit_fee_bps,
+        withdrawal_fee_bps: config.withdrawal_fee_bps,
+    }
+}
+
+/// Sets the fees, in basis points, on deposits and withdrawals of the savings
+/// token, whether through the Flexi balance or a savings plan.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If either fee exceeds 10_000
+pub fn set_transfer_fees(
+    env: &Env,
+    deposit_bps: u32,
+    withdrawal_bps: u32,
+) -> Result<(), SavingsError> {
+    let mut config = config::get_config(env);
+    config.deposit_fee_bps = deposit_bps;
+    config.withdrawal_fee_bps = withdrawal_bps;
+    config::update_config(env, config)?;
+    env.events()
+        .publish((symbol_short!("set_xfee"),), (deposit_bps, withdrawal_bps));
+    Ok(())
+}
+
+/// Sets the share of a referred user's fees and penalties paid to their
+/// referrer, in basis points.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `bps` exceeds 10_000
+pub fn set_referral_share(env: &Env, bps: u32) -> Result<(), SavingsError> {
+    let mut config = config::get_config(env);
+    config.referral_share_bps = bps;
+    config::update_config(env, config)?;
+    env.events().publish((symbol_short!("set_ref"),), bps);
+    Ok(())
+}
+
+pub fn get_referral_share(env: &Env) -> u32 {
+    config::get_config(env).referral_share_bps
+}
+
+/// Pays the referral share of a fee or penalty `user` paid in the savings
+/// token to their referrer's Flexi balance and returns it. Users without a
+/// referrer share nothing.
+///
+/// # Errors
+/// * `InvariantViolation` - If the share would fall outside `0..=amount`
+pub(crate) fn pay_referral_share(
+    env: &Env,
+    user: &Address,
+    amount: i128,
+) -> Result<i128, SavingsError> {
+    let referrer = match users::get_referrer(env, user) {
+        Some(referrer) if amount > 0 => referrer,
+        _ => return Ok(0),
+    };
+    let share = amount
+        .checked_mul(get_referral_share(env) as i128)
+        .ok_or(SavingsError::Overflow)?
+        / 10_000;
+    if share < 0 || share > amount {
+        return Err(SavingsError::InvariantViolation);
+    }
+    if share > 0 {
+        flexi::credit_flexi(env, &referrer, share)?;
+        env.events()
+            .publish((symbol_short!("ref_pay"), referrer, user.clone()), share);
+    }
+    Ok(share)
+}
+
+/// Exempts an address, such as a partner integration, from deposit and
+/// withdrawal fees.
+///
+/// The caller is responsible for checking admin authorization.
+pub(crate) fn add_fee_exemption(env: &Env, user: Address) {
+    env.storage()
+        .persistent()
+        .set(&ControlDataKey::FeeExempt(user.clone()), &true);
+    env.events().publish((symbol_short!("fee_exm"), user), true);
+}
+
+/// Makes an exempt address pay deposit and withdrawal fees again.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `UserNotFound` - If the address is not exempt
+pub(crate) fn remove_fee_exemption(env: &Env, user: Address) -> Result<(), SavingsError> {
+    if !is_fee_exempt(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+    env.storage()
+        .persistent()
+        .remove(&ControlDataKey::FeeExempt(user.clone()));
+    env.events()
+        .publish((symbol_short!("fee_exm"), user), false);
+    Ok(())
+}
+
+pub fn is_fee_exempt(env: &Env, user: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&ControlDataKey::FeeExempt(user.clone()))
+}
+
+/// Replaces the transfer fee discount tiers.
+///
+/// Tiers must be ordered by strictly increasing `min_amount`. An empty table
+/// turns discounts off.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If the tiers are out of order or a discount exceeds
+///   10_000
+pub fn set_fee_tiers(env: &Env, tiers: Vec<FeeTier>) -> Result<(), SavingsError> {
+    for i in 0..tiers.len() {
+        let tier = tiers.get(i).unwrap();
+        if tier.discount_bps > 10_000
+            || (i > 0 && tier.min_amount <= tiers.get(i - 1).unwrap().min_amount)
+        {
+            return Err(SavingsError::InvalidAmount);
+        }
+    }
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::FeeTiers, &tiers);
+    env.events()
+        .publish((symbol_short!("set_ftier"),), tiers.len());
+    Ok(())
+}
+
+pub fn get_fee_tiers(env: &Env) -> Vec<FeeTier> {
+    env.storage()
+        .instance()
+        .get(&ControlDataKey::FeeTiers)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Returns the user's lifetime deposit and withdrawal volume.
+pub fn get_user_volume(env: &Env, user: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&ControlDataKey::UserVolume(user.clone()))
+        .unwrap_or(0)
+}
+
+/// Returns the highest tier the user's lifetime volume or total balance
+/// reaches, if any.
+pub fn get_user_fee_tier(env: &Env, user: &Address) -> Option<FeeTier> {
+    let balance = users::get_user(env, user).map_or(0, |user| user.total_balance);
+    let amount = get_user_volume(env, user).max(balance);
+    let mut current = None;
+    for tier in get_fee_tiers(env).iter() {
+        if amount < tier.min_amount {
+            break;
+        }
+        current = Some(tier);
+    }
+    current
+}
+
+/// Takes the deposit or withdrawal fee out of a user's own `amount` into the
+/// treasury and returns it. The fee never exceeds `amount`, so it can't come
+/// out of anyone else's principal. Exempt users pay no fee, and the user's
+/// fee tier discounts it. The transfer counts towards the user's volume
+/// after its fee is charged.
+///
+/// # Errors
+/// * `InvariantViolation` - If the fee would fall outside `0..=amount`
+pub(crate) fn collect_transfer_fee(
+    env: &Env,
+    user: &Address,
+    amount: i128,
+    is_deposit: bool,
+) -> Result<i128, SavingsError> {
+    let tier = get_user_fee_tier(env, user);
+    let volume = get_user_volume(env, user)
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage()
+        .persistent()
+        .set(&ControlDataKey::UserVolume(user.clone()), &volume);
+
+    if is_fee_exempt(env, user) {
+        return Ok(0);
+    }
+    let config = config::get_config(env);
+    let fee_bps = if is_deposit {
+        config.deposit_fee_bps
+    } else {
+        config.withdrawal_fee_bps
+    };
+    let discount_bps = tier.map_or(0, |tier| tier.discount_bps);
+    let fee = amount
+        .checked_mul(fee_bps as i128)
+        .and_then(|fee| fee.checked_mul(10_000 - discount_bps as i128))
+        .ok_or(SavingsError::Overflow)?
+        / 100_000_000;
+    if fee < 0 || fee > amount {
+        return Err(SavingsError::InvariantViolation);
+    }
+    if fee > 0 {
+        let kind = if is_deposit {
+            FeeKind::Deposit
+        } else {
+            FeeKind::Withdrawal
+        };
+        record_fee(env, kind, &assets::get_savings_token(env), fee)?;
+        let treasury_fee = fee - pay_referral_share(env, user, fee)?;
+        credit_treasury(env, treasury_fee)?;
+        env.events()
+            .publish((symbol_short!("trsy_in"), user.clone()), treasury_fee);
+    }
+    Ok(fee)
+}
+
+/// Sets the performance fee, in basis points of earned interest, for a plan
+/// kind.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `bps` exceeds 10_000
+pub fn set_performance_fee(env: &Env, kind: PlanKind, bps: u32) -> Result<(), SavingsError> {
+    let mut config = config::get_config(env);
+    config.performance_fee_bps.set(kind, bps);
+    config::update_config(env, config)?;
+    env.events().publish((symbol_short!("set_pfee"), kind), bps);
+    Ok(())
+}
+
+pub fn get_performance_fee(env: &Env, kind: PlanKind) -> u32 {
+    config::get_config(env)
+        .performance_fee_bps
+        .get(kind)
+        .unwrap_or(0)
+}
+
+/// Takes the performance fee out of `interest` a plan of `kind` earned in
+/// `asset` into the treasury and returns it. The fee is a share of the
+/// interest only, never of principal. Interest in assets other than the
+/// savings token pays no fee, as the treasury only holds the savings token.
+///
+/// # Errors
+/// * `InvariantViolation` - If the fee would fall outside `0..=interest`
+pub(crate) fn collect_performance_fee(
+    env: &Env,
+    user: &Address,
+    kind: PlanKind,
+    asset: &Option<Address>,
+    interest: i128,
+) -> Result<i128, SavingsError> {
+    if asset
+        .as_ref()
+        .is_some_and(|asset| !assets::is_savings_token(env, asset))
+    {
+        return Ok(0);
+    }
+    let fee = interest
+        .checked_mul(get_performance_fee(env, kind) as i128)
+        .ok_or(SavingsError::Overflow)?
+        / 10_000;
+    if fee < 0 || fee > interest {
+        return Err(SavingsError::InvariantViolation);
+    }
+    if fee > 0 {
+        record_fee(env, FeeKind::Performance, asset, fee)?;
+        let treasury_fee = fee - pay_referral_share(env, user, fee)?;
+        credit_treasury(env, treasury_fee)?;
+        let total = get_performance_fees(env)
+            .checked_add(treasury_fee)
+            .ok_or(SavingsError::Overflow)?;
+        env.storage()
+            .instance()
+            .set(&ControlDataKey::PerformanceFees, &total);
+        env.events()
+            .publish((symbol_short!("perf_fee"), user.clone()), treasury_fee);
+    }
+    Ok(fee)
+}
+
+/// Cumulative performance fees taken into the treasury; part of, not in
+/// addition to, the treasury balance.
+pub fn get_performance_fees(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&ControlDataKey::PerformanceFees)
+        .unwrap_or(0)
+}
+
+/// Fees collected on deposits, withdrawals and earned interest, held by the
+/// contract.
+pub fn get_treasury_balance(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&ControlDataKey::TreasuryBalance)
+        .unwrap_or(0)
+}
+
+/// Sets the address allowed to withdraw from the treasury.
+///
+/// The caller is responsible for checking admin authorization.
+pub(crate) fn set_treasurer(env: &Env, treasurer: Address) {
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::Treasurer, &treasurer);
+    env.events()
+        .publish((symbol_short!("set_trsr"),), treasurer);
+}
+
+/// Returns the treasurer, or the admin while none is set.
+pub fn get_treasurer(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&ControlDataKey::Treasurer)
+        .unwrap_or_else(|| env.storage().instance().get(&DataKey::Admin).unwrap())
+}
+
+/// Sends collected fees from the treasury to `to`.
+///
+/// The caller is responsible for checking treasurer authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InsufficientBalance` - If the treasury holds less than `amount`
+pub(crate) fn withdraw_treasury(env: &Env, to: Address, amount: i128) -> Result<(), SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let treasury = get_treasury_balance(env);
+    if amount > treasury {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::TreasuryBalance, &(treasury - amount));
+    env.events()
+        .publish((symbol_short!("trsy_wd"), to.clone()), amount);
+    assets::push(env, &to, amount)
+}
+
+/// Returns the lifetime fees charged, by type and by asset.
+pub fn get_fee_stats(env: &Env) -> FeeStats {
+    env.storage()
+        .instance()
+        .get(&ControlDataKey::FeeStats)
+        .unwrap_or(FeeStats {
+            deposit: 0,
+            withdrawal: 0,
+            performance: 0,
+            penalties: 0,
+            by_asset: Map::new(env),
+        })
+}
+
+/// Adds a fee of `amount` charged in `asset` to the lifetime fee stats.
+pub(crate) fn record_fee(
+    env: &Env,
+    kind: FeeKind,
+    asset: &Option<Address>,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    if amount <= 0 {
+        return Ok(());
+    }
+    let mut stats = get_fee_stats(env);
+    let total = match kind {
+        FeeKind::Deposit => &mut stats.deposit,
+        FeeKind::Withdrawal => &mut stats.withdrawal,
+        FeeKind::Performance => &mut stats.performance,
+        FeeKind::Penalty => &mut stats.penalties,
+    };
+    *total = total.checked_add(amount).ok_or(SavingsError::Overflow)?;
+    if let Some(asset) = asset {
+        let in_asset = stats
+            .by_asset
+            .get(asset.clone())
+            .unwrap_or(0)
+            .checked_add(amount)
+            .ok_or(SavingsError::Overflow)?;
+        stats.by_asset.set(asset.clone(), in_asset);
+    }
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::FeeStats, &stats);
+    Ok(())
+}
+
+pub(crate) fn credit_treasury(env: &Env, amount: i128) -> Result<(), SavingsError> {
+    let treasury = get_treasury_balance(env)
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::TreasuryBalance, &treasury);
+    Ok(())
+}
+
+/// Charges the group creation fee from the creator's Flexi balance and
+/// credits it to the treasury.
+///
+/// # Returns
+/// `Ok(i128)` - The fee charged
+///
+/// # Errors
+/// * `UserNotFound` - If the creator has no account to be charged
+/// * `InsufficientBalance` - If the creator's Flexi balance can't cover the fee
+pub(crate) fn charge_group_creation_fee(
+    env: &Env,
+    creator: &Address,
+    target_amount: i128,
+) -> Result<i128, SavingsError> {
+    let fee = match config::get_config(env).group_creation_fee {
+        GroupCreationFee::None => 0,
+        GroupCreationFee::Flat(amount) => amount,
+        GroupCreationFee::Bps(bps) => {
+            target_amount
+                .checked_mul(bps as i128)
+                .ok_or(SavingsError::Overflow)?
+                / 10_000
+        }
+    };
+
+    if fee <= 0 {
+        return Ok(0);
+    }
+
+    flexi::debit_flexi(env, creator, fee)?;
+    credit_treasury(env, fee)?;
+
+    env.events()
+        .publish((symbol_short!("grp_fee"), creator.clone()), fee);
+
+    Ok(fee)
+}
@@ -1,12 +1,17 @@
 // New/Correct
-use crate::ensure_not_paused;
+use crate::assets;
+use crate::config;
 use crate::errors::SavingsError;
-use crate::storage_types::{DataKey, User};
-use soroban_sdk::{Address, Env};
+use crate::fees;
+use crate::storage_types::{DataKey, DataKeyExt, FlexiBucket, PauseScope, User};
+use crate::users;
+use crate::{ensure_not_frozen, ensure_not_paused, ensure_not_paused_for};
+use soroban_sdk::{Address, Env, Symbol, Vec};
 
 /// Handles depositing funds into the Flexi Save pool.
 pub fn flexi_deposit(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
-    ensure_not_paused(&env)?;
+    ensure_not_paused_for(&env, PauseScope::Deposits)?;
+    ensure_not_frozen(&env, &user)?;
 
     // 1. Verify the caller is the user
     user.require_auth();
@@ -15,34 +20,36 @@ pub fn flexi_deposit(env: Env, user: Address, amount: i128) -> Result<(), Saving
     if amount <= 0 {
         return Err(SavingsError::InvalidAmount);
     }
+    config::ensure_min_deposit(&env, amount)?;
+    let fee = fees::collect_transfer_fee(&env, &user, amount, true)?;
+    let credited = amount - fee;
 
     // 3. Update the specific Flexi balance
     let flexi_key = DataKey::FlexiBalance(user.clone());
     let current_flexi_balance = env.storage().persistent().get(&flexi_key).unwrap_or(0i128);
 
-    let new_flexi_balance = current_flexi_balance + amount;
+    let new_flexi_balance = current_flexi_balance + credited;
     env.storage()
         .persistent()
         .set(&flexi_key, &new_flexi_balance);
 
-    // 4. Sync with the main User struct (Total Balance)
-    // This ensures client.get_user() shows the increased balance in tests
-    let user_key = DataKey::User(user.clone());
-    if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
-        user_data.total_balance += amount;
-        env.storage().persistent().set(&user_key, &user_data);
-    } else {
-        // Optional: If user doesn't exist in User storage yet,
-        // you might want to initialize them here or return an error
+    // 4. `User.total_balance` is derived from the Flexi balance, so only
+    // check that the user exists
+    if !users::user_exists(&env, &user) {
         return Err(SavingsError::UserNotFound);
     }
 
-    Ok(())
+    // 5. Divert the deposit's round-up into the user's round-up goal, if set
+    crate::plans::apply_roundup(&env, &user, credited);
+
+    // 6. Collect the deposited tokens, fee included
+    assets::pull(&env, &user, amount)
 }
 
 /// Handles withdrawing funds from the Flexi Save pool.
 pub fn flexi_withdraw(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
-    ensure_not_paused(&env)?;
+    ensure_not_paused_for(&env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(&env, &user)?;
 
     // 1. Verify the caller is the user
     user.require_auth();
@@ -52,11 +59,17 @@ pub fn flexi_withdraw(env: Env, user: Address, amount: i128) -> Result<(), Savin
         return Err(SavingsError::InvalidAmount);
     }
 
+    // Respect the user's self-imposed cooldown between withdrawals
+    if get_flexi_cooldown_remaining(&env, user.clone()) > 0 {
+        return Err(SavingsError::CooldownActive);
+    }
+
     // 3. Check and update the specific Flexi balance
     let flexi_key = DataKey::FlexiBalance(user.clone());
     let current_flexi_balance = env.storage().persistent().get(&flexi_key).unwrap_or(0i128);
 
-    if current_flexi_balance < amount {
+    // Funds set aside in buckets must be moved out before withdrawal
+    if unallocated_balance(&env, &user, current_flexi_balance) < amount {
         return Err(SavingsError::InsufficientBalance);
     }
 
@@ -65,16 +78,232 @@ pub fn flexi_withdraw(env: Env, user: Address, amount: i128) -> Result<(), Savin
         .persistent()
         .set(&flexi_key, &new_flexi_balance);
 
-    // 4. Sync with the main User struct (Total Balance)
-    // This is necessary so that client.get_user() reflects the withdrawal
-    let user_key = DataKey::User(user.clone());
-    if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
-        user_data.total_balance -= amount;
-        env.storage().persistent().set(&user_key, &user_data);
+    env.storage().persistent().set(
+        &DataKeyExt::LastFlexiWithdrawal(user.clone()),
+        &env.ledger().timestamp(),
+    );
+
+    // 5. Send the tokens, less the withdrawal fee, once balances are updated
+    let fee = fees::collect_transfer_fee(&env, &user, amount, false)?;
+    assets::push(&env, &user, amount - fee)
+}
+/// Credits funds flowing back from another product (group refunds, payouts)
+/// to a user's Flexi balance.
+///
+/// Unlike `flexi_deposit` this does not require the user's authorization, so
+/// callers must perform their own permission checks. A zero amount is a no-op.
+pub(crate) fn credit_flexi(env: &Env, user: &Address, amount: i128) -> Result<(), SavingsError> {
+    if amount < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if amount == 0 {
+        return Ok(());
+    }
+    if !users::user_exists(env, user) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let flexi_key = DataKey::FlexiBalance(user.clone());
+    let current_flexi_balance = env.storage().persistent().get(&flexi_key).unwrap_or(0i128);
+    let new_flexi_balance = current_flexi_balance
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+
+    env.storage()
+        .persistent()
+        .set(&flexi_key, &new_flexi_balance);
+
+    Ok(())
+}
+
+/// Debits a user's Flexi balance to pay for another product (such as fees).
+///
+/// Like `credit_flexi` this does not require the user's authorization, so
+/// callers must perform their own permission checks. A zero amount is a no-op.
+pub(crate) fn debit_flexi(env: &Env, user: &Address, amount: i128) -> Result<(), SavingsError> {
+    if amount < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if amount == 0 {
+        return Ok(());
+    }
+    if !users::user_exists(env, user) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let flexi_key = DataKey::FlexiBalance(user.clone());
+    let current_flexi_balance = env.storage().persistent().get(&flexi_key).unwrap_or(0i128);
+    if unallocated_balance(env, user, current_flexi_balance) < amount {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&flexi_key, &(current_flexi_balance - amount));
+
+    Ok(())
+}
+
+/// Sets a cooldown the user must wait between Flexi withdrawals. Zero turns
+/// the cooldown off.
+///
+/// # Errors
+/// * `UserNotFound` - If the user has no account
+pub fn set_flexi_cooldown(env: &Env, user: Address, seconds: u64) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    if !crate::users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
     }
 
+    let key = DataKeyExt::FlexiCooldown(user);
+    if seconds == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &seconds);
+    }
+    Ok(())
+}
+
+/// Seconds until the user may withdraw from Flexi again, or zero.
+pub fn get_flexi_cooldown_remaining(env: &Env, user: Address) -> u64 {
+    let cooldown: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::FlexiCooldown(user.clone()))
+        .unwrap_or(0);
+    let last: Option<u64> = env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::LastFlexiWithdrawal(user));
+    match last {
+        Some(last) => last
+            .saturating_add(cooldown)
+            .saturating_sub(env.ledger().timestamp()),
+        None => 0,
+    }
+}
+
+/// Maximum number of named buckets per user
+pub const MAX_FLEXI_BUCKETS: u32 = 10;
+
+/// Creates an empty named bucket under the user's Flexi balance.
+///
+/// # Errors
+/// * `UserNotFound` - If the user has no account
+/// * `InvalidPlanConfig` - If the name is taken
+/// * `MaxPlansExceeded` - If the user already has `MAX_FLEXI_BUCKETS` buckets
+pub fn create_bucket(env: &Env, user: Address, name: Symbol) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    if !crate::users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let mut buckets = get_buckets(env, user.clone());
+    if buckets.iter().any(|bucket| bucket.name == name) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if buckets.len() >= MAX_FLEXI_BUCKETS {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+
+    buckets.push_back(FlexiBucket { name, balance: 0 });
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::FlexiBuckets(user), &buckets);
     Ok(())
 }
+
+/// Moves funds between the user's Flexi buckets. `None` stands for the
+/// unallocated part of the Flexi balance, so funds can be set aside into a
+/// bucket or released from one.
+///
+/// The Flexi balance and `User.total_balance` are unchanged.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `PlanNotFound` - If a named bucket doesn't exist
+/// * `InsufficientBalance` - If the source can't cover `amount`
+pub fn move_between_buckets(
+    env: &Env,
+    user: Address,
+    from: Option<Symbol>,
+    to: Option<Symbol>,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let mut buckets = get_buckets(env, user.clone());
+    let find = |name: &Symbol| {
+        buckets
+            .iter()
+            .position(|bucket| bucket.name == *name)
+            .map(|i| i as u32)
+            .ok_or(SavingsError::PlanNotFound)
+    };
+    let from_index = from.as_ref().map(find).transpose()?;
+    let to_index = to.as_ref().map(find).transpose()?;
+
+    match from_index {
+        Some(i) => {
+            let mut bucket = buckets.get(i).unwrap();
+            if bucket.balance < amount {
+                return Err(SavingsError::InsufficientBalance);
+            }
+            bucket.balance -= amount;
+            buckets.set(i, bucket);
+        }
+        None => {
+            let balance = env
+                .storage()
+                .persistent()
+                .get(&DataKey::FlexiBalance(user.clone()))
+                .unwrap_or(0i128);
+            if unallocated_balance(env, &user, balance) < amount {
+                return Err(SavingsError::InsufficientBalance);
+            }
+        }
+    }
+    if let Some(i) = to_index {
+        let mut bucket = buckets.get(i).unwrap();
+        bucket.balance = bucket
+            .balance
+            .checked_add(amount)
+            .ok_or(SavingsError::Overflow)?;
+        buckets.set(i, bucket);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::FlexiBuckets(user), &buckets);
+    Ok(())
+}
+
+/// Returns the user's named Flexi buckets and their balances.
+pub fn get_buckets(env: &Env, user: Address) -> Vec<FlexiBucket> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::FlexiBuckets(user))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Part of a Flexi balance that is not set aside in a named bucket.
+pub(crate) fn unallocated_balance(env: &Env, user: &Address, flexi_balance: i128) -> i128 {
+    let allocated: i128 = get_buckets(env, user.clone())
+        .iter()
+        .map(|bucket| bucket.balance)
+        .sum();
+    flexi_balance - allocated
+}
+
 /// Returns the user's Flexi Save balance.
 /// This is a read-only (view) function.
 pub fn get_flexi_balance(env: &Env, user: Address) -> Result<i128, SavingsError> {
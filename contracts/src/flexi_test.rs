@@ -0,0 +1,356 @@
+use crate::{
+    FeeTier, NesteraContract, NesteraContractClient, PlanType, SavingsError, TimelockAction,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, BytesN, Env, Symbol,
+};
+
+fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &admin_pk, &None);
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+
+    (env, client, user)
+}
+
+#[test]
+fn test_flexi_buckets_partition_the_balance() {
+    let (env, client, user) = setup();
+    let rent = Symbol::new(&env, "Rent");
+    let emergency = Symbol::new(&env, "Emergency");
+    client.deposit_flexi(&user, &1_000);
+    client.create_bucket(&user, &rent);
+    client.create_bucket(&user, &emergency);
+
+    client.move_between_buckets(&user, &None, &Some(rent.clone()), &600);
+    client.move_between_buckets(&user, &Some(rent.clone()), &Some(emergency.clone()), &100);
+
+    let buckets = client.get_buckets(&user);
+    assert_eq!(buckets.get(0).unwrap().balance, 500);
+    assert_eq!(buckets.get(1).unwrap().balance, 100);
+    assert_eq!(client.get_flexi_balance(&user), 1_000);
+    assert_eq!(client.get_user(&user).total_balance, 1_000);
+
+    // Only the unallocated 400 can be withdrawn
+    let res = client.try_withdraw_flexi(&user, &401);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientBalance));
+    client.withdraw_flexi(&user, &400);
+
+    let res = client.try_move_between_buckets(&user, &Some(emergency), &None, &101);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientBalance));
+    let res = client.try_create_bucket(&user, &rent);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+}
+
+#[test]
+fn test_flexi_cooldown_blocks_rapid_withdrawals() {
+    let (env, client, user) = setup();
+    client.deposit_flexi(&user, &1_000);
+    client.set_flexi_cooldown(&user, &3_600);
+
+    client.withdraw_flexi(&user, &100);
+    assert_eq!(client.get_flexi_cooldown_remaining(&user), 3_600);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let res = client.try_withdraw_flexi(&user, &100);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::CooldownActive));
+    assert_eq!(client.get_flexi_cooldown_remaining(&user), 2_600);
+
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    client.withdraw_flexi(&user, &100);
+    assert_eq!(client.get_flexi_balance(&user), 800);
+}
+
+#[test]
+fn test_flexi_deposit_and_withdraw_move_savings_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(issuer).address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+    assert_eq!(client.get_savings_token(), Some(token.clone()));
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    let balances = TokenClient::new(&env, &token);
+
+    client.deposit_flexi(&user, &800);
+    assert_eq!(balances.balance(&user), 200);
+    assert_eq!(balances.balance(&contract_id), 800);
+
+    client.withdraw_flexi(&user, &300);
+    assert_eq!(balances.balance(&user), 500);
+    assert_eq!(balances.balance(&contract_id), 500);
+    assert_eq!(client.get_flexi_balance(&user), 500);
+
+    // A deposit the user can't fund is rolled back
+    assert!(client.try_deposit_flexi(&user, &600).is_err());
+    assert_eq!(client.get_flexi_balance(&user), 500);
+}
+
+#[test]
+fn test_transfer_fees_accrue_to_treasury_from_the_payer_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(issuer).address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.initialize_user(&user);
+    client.initialize_user(&other);
+    StellarAssetClient::new(&env, &token).mint(&user, &1_000);
+    StellarAssetClient::new(&env, &token).mint(&other, &1_000);
+    let balances = TokenClient::new(&env, &token);
+
+    client.deposit_flexi(&other, &1_000);
+    client.set_transfer_fees(&100, &200);
+    assert_eq!(client.get_fee_config().deposit_fee_bps, 100);
+
+    // The deposit fee comes out of the deposit itself
+    client.deposit_flexi(&user, &1_000);
+    assert_eq!(balances.balance(&contract_id), 2_000);
+    assert_eq!(client.get_flexi_balance(&user), 990);
+    assert_eq!(client.get_treasury_balance(), 10);
+
+    // So does the withdrawal fee
+    client.withdraw_flexi(&user, &500);
+    assert_eq!(balances.balance(&user), 490);
+    assert_eq!(client.get_flexi_balance(&user), 490);
+    assert_eq!(client.get_treasury_balance(), 20);
+
+    // Other users' principal stays whole and backed
+    assert_eq!(client.get_flexi_balance(&other), 1_000);
+    assert_eq!(
+        balances.balance(&contract_id),
+        client.get_flexi_balance(&user)
+            + client.get_flexi_balance(&other)
+            + client.get_treasury_balance()
+    );
+
+    assert_eq!(
+        client
+            .try_withdraw_treasury(&admin, &admin, &21)
+            .unwrap_err(),
+        Ok(SavingsError::InsufficientBalance)
+    );
+    client.withdraw_treasury(&admin, &admin, &20);
+    assert_eq!(balances.balance(&admin), 20);
+    assert_eq!(client.get_treasury_balance(), 0);
+
+    assert_eq!(
+        client.try_set_transfer_fees(&10_001, &0).unwrap_err(),
+        Ok(SavingsError::InvalidAmount)
+    );
+}
+
+#[test]
+fn test_fee_stats_count_fees_by_type_and_asset() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(issuer).address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    client.initialize(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &token).mint(&user, &20_000);
+
+    client.set_transfer_fees(&100, &200);
+    client.set_early_break_fee_bps(&1_000);
+    client.deposit_flexi(&user, &1_000);
+    client.withdraw_flexi(&user, &500);
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(3_600), &10_000);
+    client.early_withdraw_lock(&user, &plan_id);
+
+    // The lock plan pays the deposit fee too, leaving 9_900 to break
+    let stats = client.get_fee_stats();
+    assert_eq!(stats.deposit, 110);
+    assert_eq!(stats.withdrawal, 10);
+    assert_eq!(stats.performance, 0);
+    assert_eq!(stats.penalties, 990);
+    assert_eq!(stats.by_asset.get(token).unwrap(), 1_110);
+}
+
+#[test]
+fn test_fee_tiers_discount_transfer_fees_by_volume() {
+    let (env, client, user) = setup();
+    let tier = |min_amount, discount_bps| FeeTier {
+        min_amount,
+        discount_bps,
+    };
+    assert_eq!(
+        client
+            .try_set_fee_tiers(&vec![&env, tier(5_000, 5_000), tier(1_000, 10_000)])
+            .unwrap_err(),
+        Ok(SavingsError::InvalidAmount)
+    );
+    client.set_fee_tiers(&vec![&env, tier(1_000, 5_000), tier(5_000, 10_000)]);
+    client.set_transfer_fees(&100, &0);
+
+    client.deposit_flexi(&user, &1_000);
+    assert_eq!(client.get_treasury_balance(), 10);
+    assert_eq!(client.get_user_volume(&user), 1_000);
+    assert_eq!(client.get_user_fee_tier(&user), Some(tier(1_000, 5_000)));
+
+    client.deposit_flexi(&user, &1_000);
+    client.deposit_flexi(&user, &3_000);
+    assert_eq!(client.get_treasury_balance(), 30);
+
+    // Volume of 5_000 reaches the top tier, which waives the fee
+    client.deposit_flexi(&user, &1_000);
+    assert_eq!(client.get_treasury_balance(), 30);
+    assert_eq!(client.get_flexi_balance(&user), 5_970);
+}
+
+#[test]
+fn test_fee_exempt_users_pay_no_transfer_fees() {
+    let (env, client, user) = setup();
+    let partner = Address::generate(&env);
+    client.initialize_user(&partner);
+    client.set_transfer_fees(&100, &100);
+
+    client.add_fee_exemption(&partner);
+    assert!(client.is_fee_exempt(&partner));
+    assert!(!client.is_fee_exempt(&user));
+    client.deposit_flexi(&partner, &1_000);
+    client.withdraw_flexi(&partner, &500);
+    assert_eq!(client.get_flexi_balance(&partner), 500);
+    assert_eq!(client.get_treasury_balance(), 0);
+
+    client.remove_fee_exemption(&partner);
+    client.deposit_flexi(&partner, &1_000);
+    client.deposit_flexi(&user, &1_000);
+    assert_eq!(client.get_treasury_balance(), 20);
+    assert_eq!(
+        client.try_remove_fee_exemption(&partner).unwrap_err(),
+        Ok(SavingsError::UserNotFound)
+    );
+}
+
+#[test]
+fn test_referrers_share_the_fees_of_users_they_referred() {
+    let (env, client, referrer) = setup();
+    let user = Address::generate(&env);
+    assert_eq!(
+        client
+            .try_initialize_referred_user(&user, &Address::generate(&env))
+            .unwrap_err(),
+        Ok(SavingsError::UserNotFound)
+    );
+    assert_eq!(
+        client
+            .try_initialize_referred_user(&user, &user)
+            .unwrap_err(),
+        Ok(SavingsError::InvalidPlanConfig)
+    );
+    client.initialize_referred_user(&user, &referrer);
+    assert_eq!(client.get_referrer(&user), Some(referrer.clone()));
+    assert_eq!(client.get_referrer(&referrer), None);
+
+    client.set_transfer_fees(&100, &0);
+    client.set_referral_share(&2_500);
+    client.deposit_flexi(&user, &4_000);
+    assert_eq!(client.get_flexi_balance(&user), 3_960);
+    assert_eq!(client.get_flexi_balance(&referrer), 10);
+    assert_eq!(client.get_treasury_balance(), 30);
+
+    // The referrer's own fees are not shared with anyone
+    client.deposit_flexi(&referrer, &1_000);
+    assert_eq!(client.get_flexi_balance(&referrer), 1_000);
+    assert_eq!(client.get_treasury_balance(), 40);
+
+    assert_eq!(
+        client.try_set_referral_share(&10_001).unwrap_err(),
+        Ok(SavingsError::InvalidAmount)
+    );
+}
+
+#[test]
+fn test_treasury_withdrawals_need_the_treasurer_or_the_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(issuer).address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &token).mint(&user, &10_000);
+    client.set_transfer_fees(&100, &0);
+    client.deposit_flexi(&user, &10_000);
+    assert_eq!(client.get_treasury_balance(), 100);
+
+    let treasurer = Address::generate(&env);
+    let wallet = Address::generate(&env);
+    assert_eq!(client.get_treasurer(), admin);
+    client.set_treasurer(&treasurer);
+    assert_eq!(
+        client
+            .try_withdraw_treasury(&admin, &wallet, &10)
+            .unwrap_err(),
+        Ok(SavingsError::Unauthorized)
+    );
+    client.withdraw_treasury(&treasurer, &wallet, &40);
+    let balances = TokenClient::new(&env, &token);
+    assert_eq!(balances.balance(&wallet), 40);
+
+    client.set_timelock_delay(&3_600);
+    assert_eq!(
+        client
+            .try_withdraw_treasury(&treasurer, &wallet, &10)
+            .unwrap_err(),
+        Ok(SavingsError::TooEarly)
+    );
+    let id = client.queue_action(&TimelockAction::WithdrawTreasury(wallet.clone(), 60));
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    client.execute_action(&id);
+    assert_eq!(balances.balance(&wallet), 100);
+    assert_eq!(client.get_treasury_balance(), 0);
+
+    // Replacing the treasurer is timelocked as well
+    assert_eq!(
+        client.try_set_treasurer(&admin).unwrap_err(),
+        Ok(SavingsError::TooEarly)
+    );
+    let id = client.queue_action(&TimelockAction::SetTreasurer(admin.clone()));
+    env.ledger().with_mut(|li| li.timestamp = 7_200);
+    client.execute_action(&id);
+    assert_eq!(client.get_treasurer(), admin);
+}
@@ -1,9 +1,13 @@
 use soroban_sdk::{symbol_short, Address, Env, Vec};
 
-use crate::ensure_not_paused;
+use crate::assets;
+use crate::config;
 use crate::errors::SavingsError;
-use crate::storage_types::{DataKey, GoalSave, User};
+use crate::flexi;
+use crate::penalty_pool;
+use crate::storage_types::{DataKey, GoalSave, PauseScope};
 use crate::users;
+use crate::{ensure_not_frozen, ensure_not_paused_for};
 
 pub fn create_goal_save(
     env: &Env,
@@ -12,7 +16,8 @@ pub fn create_goal_save(
     target_amount: i128,
     initial_deposit: i128,
 ) -> Result<u64, SavingsError> {
-    ensure_not_paused(env)?;
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
     user.require_auth();
 
     if target_amount <= 0 {
@@ -49,6 +54,8 @@ pub fn create_goal_save(
     add_goal_to_user(env, &user, goal_id);
     increment_next_goal_id(env);
 
+    assets::pull(env, &user, initial_deposit)?;
+
     Ok(goal_id)
 }
 
@@ -58,12 +65,14 @@ pub fn deposit_to_goal_save(
     goal_id: u64,
     amount: i128,
 ) -> Result<(), SavingsError> {
-    ensure_not_paused(env)?;
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
     user.require_auth();
 
     if amount <= 0 {
         return Err(SavingsError::InvalidAmount);
     }
+    config::ensure_min_deposit(env, amount)?;
 
     let mut goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
 
@@ -88,7 +97,7 @@ pub fn deposit_to_goal_save(
         .persistent()
         .set(&DataKey::GoalSave(goal_id), &goal_save);
 
-    Ok(())
+    assets::pull(env, &user, amount)
 }
 
 pub fn withdraw_completed_goal_save(
@@ -96,7 +105,8 @@ pub fn withdraw_completed_goal_save(
     user: Address,
     goal_id: u64,
 ) -> Result<i128, SavingsError> {
-    ensure_not_paused(env)?;
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
     user.require_auth();
 
     if !users::user_exists(env, &user) {
@@ -123,20 +133,16 @@ pub fn withdraw_completed_goal_save(
         .persistent()
         .set(&DataKey::GoalSave(goal_id), &goal_save);
 
-    let user_key = DataKey::User(user.clone());
-    if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
-        user_data.total_balance = user_data
-            .total_balance
-            .checked_add(goal_save.current_amount)
-            .ok_or(SavingsError::Overflow)?;
-        env.storage().persistent().set(&user_key, &user_data);
-    }
+    // Goal funds land in the user's Flexi balance, which counts towards
+    // `User.total_balance`
+    flexi::credit_flexi(env, &user, goal_save.current_amount)?;
 
     Ok(goal_save.current_amount)
 }
 
 pub fn break_goal_save(env: &Env, user: Address, goal_id: u64) -> Result<i128, SavingsError> {
-    ensure_not_paused(env)?;
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
     user.require_auth();
 
     if !users::user_exists(env, &user) {
@@ -157,11 +163,7 @@ pub fn break_goal_save(env: &Env, user: Address, goal_id: u64) -> Result<i128, S
         return Err(SavingsError::PlanCompleted);
     }
 
-    let fee_bps: u32 = env
-        .storage()
-        .instance()
-        .get(&DataKey::EarlyBreakFeeBps)
-        .unwrap_or(0);
+    let fee_bps = config::get_config(env).early_break_fee_bps;
 
     if fee_bps > 10_000 {
         return Err(SavingsError::InvalidAmount);
@@ -188,16 +190,10 @@ pub fn break_goal_save(env: &Env, user: Address, goal_id: u64) -> Result<i128, S
         .persistent()
         .set(&DataKey::GoalSave(goal_id), &goal_save);
 
-    let user_key = DataKey::User(user.clone());
-    if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
-        user_data.total_balance = user_data
-            .total_balance
-            .checked_add(net_amount)
-            .ok_or(SavingsError::Overflow)?;
-        env.storage().persistent().set(&user_key, &user_data);
-    }
+    flexi::credit_flexi(env, &user, net_amount)?;
 
-    if fee_amount > 0 {
+    let recipient_fee = penalty_pool::route_penalty(env, &user, fee_amount)?;
+    if recipient_fee > 0 {
         if let Some(fee_recipient) = env
             .storage()
             .instance()
@@ -210,12 +206,12 @@ pub fn break_goal_save(env: &Env, user: Address, goal_id: u64) -> Result<i128, S
                 .get::<DataKey, i128>(&fee_key)
                 .unwrap_or(0i128);
             let new_fee_balance = current_fee_balance
-                .checked_add(fee_amount)
+                .checked_add(recipient_fee)
                 .ok_or(SavingsError::Overflow)?;
             env.storage().persistent().set(&fee_key, &new_fee_balance);
             env.events().publish(
                 (symbol_short!("brk_fee"), fee_recipient, goal_id),
-                fee_amount,
+                recipient_fee,
             );
         }
     }
@@ -300,7 +296,7 @@ mod tests {
         let admin_pk = soroban_sdk::BytesN::from_array(&env, &[1u8; 32]);
 
         env.mock_all_auths();
-        client.initialize(&admin, &admin_pk);
+        client.initialize(&admin, &admin_pk, &None);
 
         (env, client, admin)
     }
@@ -1,9 +1,29 @@
-use crate::ensure_not_paused;
+use crate::assets;
+use crate::config;
+use crate::cycles;
 use crate::errors::SavingsError;
-use crate::storage_types::{DataKey, GroupSave};
+use crate::fees;
+use crate::history;
+use crate::penalty_pool;
+use crate::rates;
+use crate::rotation;
+use crate::storage_types::{
+    DataKey, ExpiryPolicy, GroupInvite, GroupMode, GroupSave, GroupStatus, LeavePolicy, PauseScope,
+};
 use crate::users;
+use crate::{ensure_not_frozen, ensure_not_paused, ensure_not_paused_for};
 use soroban_sdk::{Address, Env, String, Vec};
 
+/// Maximum number of entries returned by a single paginated group query
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Default maximum number of users waiting for a slot in a full group; see
+/// `ProtocolConfig::max_waitlist_size`
+pub const MAX_WAITLIST_SIZE: u32 = 100;
+
+/// Default percentage of members required to pass a group change
+pub const DEFAULT_CHANGE_QUORUM: u32 = 51;
+
 /// Creates a new group savings plan.
 ///
 /// The creator is automatically added as the first member (member_count = 1).
@@ -20,7 +40,8 @@ use soroban_sdk::{Address, Env, String, Vec};
 /// * `contribution_amount` - Contribution amount or minimum (must be > 0)
 /// * `is_public` - Whether the group is public or private
 /// * `start_time` - Unix timestamp when the group starts
-/// * `end_time` - Unix timestamp when the group ends (must be > start_time)
+/// * `end_time` - Deadline after which contributions are rejected and the
+///   group can be finalized (must be > start_time)
 ///
 /// # Returns
 /// `Ok(u64)` - The unique ID of the created group
@@ -30,6 +51,8 @@ use soroban_sdk::{Address, Env, String, Vec};
 /// * `InvalidAmount` - If target_amount or contribution_amount <= 0
 /// * `InvalidTimestamp` - If start_time >= end_time
 /// * `InvalidGroupConfig` - If other parameters are invalid
+/// * `InsufficientBalance` - If the creator's Flexi balance can't cover the
+///   group creation fee
 pub fn create_group_save(
     env: &Env,
     creator: Address,
@@ -74,12 +97,15 @@ pub fn create_group_save(
         return Err(SavingsError::InvalidGroupConfig);
     }
 
+    // Charge the protocol's group creation fee, if any
+    fees::charge_group_creation_fee(env, &creator, target_amount)?;
+
     // Get the next group ID
     let next_id_key = DataKey::NextGroupId;
     let group_id: u64 = env.storage().persistent().get(&next_id_key).unwrap_or(1u64);
 
     // Create the GroupSave struct with initial values
-    let new_group = GroupSave {
+    let mut new_group = GroupSave {
         id: group_id,
         creator: creator.clone(),
         title,
@@ -90,12 +116,30 @@ pub fn create_group_save(
         contribution_type,
         contribution_amount,
         is_public,
-        member_count: 1, // Creator is the first member
+        member_count: 0,
         start_time,
         end_time,
         is_completed: false,
+        leave_policy: LeavePolicy::Refund,
+        payout_beneficiary: None,
+        status: GroupStatus::Active,
+        mode: GroupMode::Pooled,
+        expiry_policy: ExpiryPolicy::Refund,
+        max_members: 0,
+        change_quorum: DEFAULT_CHANGE_QUORUM,
+        withdrawal_threshold: 0,
+        min_contribution: None,
+        max_contribution_per_member: None,
+        accrued_interest: 0,
+        last_accrual: env.ledger().timestamp(),
+        cycle_duration: 0,
+        required_amount_per_cycle: None,
+        late_penalty_bps: 0,
     };
 
+    // The creator is the first member
+    push_member(env, &mut new_group, &creator);
+
     // Store the GroupSave in persistent storage
     let group_key = DataKey::GroupSave(group_id);
     env.storage().persistent().set(&group_key, &new_group);
@@ -105,12 +149,6 @@ pub fn create_group_save(
         .persistent()
         .set(&next_id_key, &(group_id + 1u64));
 
-    // Initialize the members list with the creator
-    let members_key = DataKey::GroupMembers(group_id);
-    let mut members = Vec::new(env);
-    members.push_back(creator.clone());
-    env.storage().persistent().set(&members_key, &members);
-
     // Initialize creator's contribution to 0
     let contribution_key = DataKey::GroupMemberContribution(group_id, creator.clone());
     env.storage().persistent().set(&contribution_key, &0i128);
@@ -118,6 +156,11 @@ pub fn create_group_save(
     // Add group_id to the creator's UserGroupSaves list
     add_group_to_user_list(env, &creator, group_id)?;
 
+    // Make public groups discoverable
+    if is_public {
+        list_public_group(env, group_id);
+    }
+
     // Create a SavingsPlan for the creator so they can retrieve it via get_group_save
     let now = env.ledger().timestamp();
     let savings_plan = crate::storage_types::SavingsPlan {
@@ -189,6 +232,134 @@ pub fn get_user_groups(env: &Env, user: &Address) -> Vec<u64> {
         .unwrap_or(Vec::new(env))
 }
 
+/// VIEW FUNCTION - Gets a page of the group IDs a user participates in
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - The user address
+/// * `start` - Index of the first group ID to return
+/// * `limit` - Maximum number of group IDs to return (capped at `MAX_PAGE_SIZE`)
+///
+/// # Returns
+/// Up to `limit` group IDs starting at `start`, in join order
+pub fn get_user_groups_page(env: &Env, user: &Address, start: u32, limit: u32) -> Vec<u64> {
+    let groups = get_user_groups(env, user);
+    let start = start.min(groups.len());
+    let end = start
+        .saturating_add(limit.min(MAX_PAGE_SIZE))
+        .min(groups.len());
+    groups.slice(start..end)
+}
+
+/// VIEW FUNCTION - Lists public groups that can still be discovered
+///
+/// Scans `limit` slots (capped at `MAX_PAGE_SIZE`) of the public group index
+/// starting at `start`. The index only holds public groups that are active
+/// and have not reached their target; with `only_open` set, groups that are
+/// past their end time or no longer accept new members are skipped as well,
+/// so a page may hold fewer than `limit` entries.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `start` - Index slot to start scanning from
+/// * `limit` - Number of index slots to scan
+/// * `only_open` - Whether to return only groups that can be joined right now
+///
+/// # Returns
+/// The listed groups found in the scanned slots
+pub fn list_public_groups(env: &Env, start: u32, limit: u32, only_open: bool) -> Vec<GroupSave> {
+    let count: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::PublicGroupCount)
+        .unwrap_or(0);
+    let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+    let now = env.ledger().timestamp();
+
+    let mut groups = Vec::new(env);
+    for index in start..end {
+        let group_id: Option<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PublicGroupByIndex(index));
+        let group = match group_id.and_then(|id| get_group_save(env, id)) {
+            Some(group) => group,
+            None => continue,
+        };
+        if only_open
+            && (group.mode != GroupMode::Pooled || now > group.end_time || is_full(env, &group))
+        {
+            continue;
+        }
+        groups.push_back(group);
+    }
+    groups
+}
+
+/// Checks whether a group has reached its own member cap or the protocol's.
+pub(crate) fn is_full(env: &Env, group: &GroupSave) -> bool {
+    let cap = config::get_config(env).max_group_members;
+    (group.max_members != 0 && group.member_count >= group.max_members)
+        || (cap != 0 && group.member_count >= cap)
+}
+
+/// Helper function adding a group to the public discovery index.
+fn list_public_group(env: &Env, group_id: u64) {
+    let count: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::PublicGroupCount)
+        .unwrap_or(0);
+    env.storage()
+        .persistent()
+        .set(&DataKey::PublicGroupByIndex(count), &group_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::PublicGroupIndex(group_id), &count);
+    env.storage()
+        .persistent()
+        .set(&DataKey::PublicGroupCount, &(count + 1));
+}
+
+/// Helper function removing a group from the public discovery index by moving
+/// the last listed group into its slot. Does nothing if the group isn't listed.
+pub(crate) fn delist_public_group(env: &Env, group_id: u64) {
+    let index_key = DataKey::PublicGroupIndex(group_id);
+    let index: u32 = match env.storage().persistent().get(&index_key) {
+        Some(index) => index,
+        None => return,
+    };
+    let count: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::PublicGroupCount)
+        .unwrap_or(0);
+    let last = count.saturating_sub(1);
+
+    if index != last {
+        if let Some(last_group) = env
+            .storage()
+            .persistent()
+            .get::<DataKey, u64>(&DataKey::PublicGroupByIndex(last))
+        {
+            env.storage()
+                .persistent()
+                .set(&DataKey::PublicGroupByIndex(index), &last_group);
+            env.storage()
+                .persistent()
+                .set(&DataKey::PublicGroupIndex(last_group), &index);
+        }
+    }
+
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PublicGroupByIndex(last));
+    env.storage().persistent().remove(&index_key);
+    env.storage()
+        .persistent()
+        .set(&DataKey::PublicGroupCount, &last);
+}
+
 /// Helper function to add a group ID to a user's list of groups.
 ///
 /// # Arguments
@@ -226,6 +397,7 @@ fn add_group_to_user_list(env: &Env, user: &Address, group_id: u64) -> Result<()
 /// - Group doesn't exist
 /// - Group is not public
 /// - User is already a member
+/// - Group has reached its member cap
 pub fn join_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
     ensure_not_paused(env)?;
     // Ensure user exists
@@ -246,33 +418,224 @@ pub fn join_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), Sa
         return Err(SavingsError::InvalidGroupConfig);
     }
 
+    ensure_joinable(env, &group, &user)?;
+
+    admit_member(env, &mut group, &user)?;
+    env.storage().persistent().set(&group_key, &group);
+
+    // Emit event for joining group
+    env.events()
+        .publish((soroban_sdk::symbol_short!("grp_join"), user), group_id);
+
+    Ok(())
+}
+
+/// Helper function checking that a group can take `user` as a new member.
+fn ensure_joinable(env: &Env, group: &GroupSave, user: &Address) -> Result<(), SavingsError> {
+    // Settled or cancelled groups no longer accept funds or members
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    // The payout order is fixed once a rotation has started
+    if group.mode == GroupMode::Rotating {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    // No new members after the deadline
+    if env.ledger().timestamp() > group.end_time {
+        return Err(SavingsError::TooLate);
+    }
+
     // Check if user is already a member
-    let members_key = DataKey::GroupMembers(group_id);
-    let mut members: Vec<Address> = env
-        .storage()
+    if is_member(env, group.id, user) {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    // Respect the member cap
+    if is_full(env, group) {
+        return Err(SavingsError::GroupFull);
+    }
+
+    Ok(())
+}
+
+/// Invites a user to join a group, including private groups.
+///
+/// Re-inviting a user replaces their pending invite.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `invitee` - The user being invited
+/// * `ttl` - Seconds until the invite expires (must be > 0)
+///
+/// # Returns
+/// `Ok(u64)` - The timestamp after which the invite can no longer be accepted
+/// `Err(SavingsError)` if:
+/// - Group doesn't exist (`PlanNotFound`)
+/// - Caller is not the creator (`Unauthorized`)
+/// - Group is no longer active (`PlanCompleted`)
+/// - Invitee has no account (`UserNotFound`)
+/// - Invitee is already a member (`InvalidGroupConfig`)
+/// - `ttl` is zero (`InvalidTimestamp`)
+pub fn invite_to_group(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    invitee: Address,
+    ttl: u64,
+) -> Result<u64, SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if !users::user_exists(env, &invitee) {
+        return Err(SavingsError::UserNotFound);
+    }
+    if is_member(env, group_id, &invitee) {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    if ttl == 0 {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    let now = env.ledger().timestamp();
+    let invite = GroupInvite {
+        invited_by: creator,
+        created_at: now,
+        expires_at: now.checked_add(ttl).ok_or(SavingsError::Overflow)?,
+    };
+    env.storage()
         .persistent()
-        .get(&members_key)
-        .unwrap_or(Vec::new(env));
+        .set(&DataKey::GroupInvite(group_id, invitee.clone()), &invite);
 
-    // Check if user is already a member
-    for i in 0..members.len() {
-        if let Some(member) = members.get(i) {
-            if member == user {
-                return Err(SavingsError::InvalidGroupConfig);
-            }
-        }
+    env.events()
+        .publish((soroban_sdk::symbol_short!("grp_inv"), invitee), group_id);
+
+    Ok(invite.expires_at)
+}
+
+/// Accepts a pending invite and joins the group.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `invitee` - The invited user (must authorize)
+/// * `group_id` - The group ID
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if:
+/// - There is no pending invite (`PlanNotFound`)
+/// - The invite has expired (`TooLate`)
+/// - The group can no longer take the user as a member
+pub fn accept_group_invite(env: &Env, invitee: Address, group_id: u64) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    invitee.require_auth();
+
+    let invite_key = DataKey::GroupInvite(group_id, invitee.clone());
+    let invite: GroupInvite = env
+        .storage()
+        .persistent()
+        .get(&invite_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+    if env.ledger().timestamp() > invite.expires_at {
+        return Err(SavingsError::TooLate);
     }
 
-    // Add user to members list
-    members.push_back(user.clone());
-    env.storage().persistent().set(&members_key, &members);
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    ensure_joinable(env, &group, &invitee)?;
 
-    // Increment member count
-    group.member_count += 1;
+    admit_member(env, &mut group, &invitee)?;
     env.storage().persistent().set(&group_key, &group);
+    env.storage().persistent().remove(&invite_key);
+
+    env.events()
+        .publish((soroban_sdk::symbol_short!("grp_join"), invitee), group_id);
+
+    Ok(())
+}
+
+/// Withdraws a pending invite.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `invitee` - The invited user
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if the group or invite doesn't exist (`PlanNotFound`)
+/// or the caller is not the creator (`Unauthorized`)
+pub fn revoke_invite(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    invitee: Address,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    let invite_key = DataKey::GroupInvite(group_id, invitee.clone());
+    if !env.storage().persistent().has(&invite_key) {
+        return Err(SavingsError::PlanNotFound);
+    }
+    env.storage().persistent().remove(&invite_key);
+
+    env.events()
+        .publish((soroban_sdk::symbol_short!("grp_rvk"), invitee), group_id);
+
+    Ok(())
+}
+
+/// VIEW FUNCTION - Gets a user's pending invite to a group
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `group_id` - The group ID
+/// * `invitee` - The invited user
+///
+/// # Returns
+/// The invite, if one is pending (it may have expired)
+pub fn get_group_invite(env: &Env, group_id: u64, invitee: Address) -> Option<GroupInvite> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GroupInvite(group_id, invitee))
+}
+
+/// Helper function adding a user to a group: appends them to the member list,
+/// records their group membership and sets up their contribution entry and
+/// SavingsPlan. The caller persists the group.
+fn admit_member(env: &Env, group: &mut GroupSave, user: &Address) -> Result<(), SavingsError> {
+    let group_id = group.id;
+
+    // Add user to members list and increment member count
+    push_member(env, group, user);
+
+    // Cycles before joining don't count as missed
+    cycles::record_join(env, group, user);
 
     // Add group to user's list of groups
-    add_group_to_user_list(env, &user, group_id)?;
+    add_group_to_user_list(env, user, group_id)?;
 
     // Initialize user's contribution to 0
     let contribution_key = DataKey::GroupMemberContribution(group_id, user.clone());
@@ -300,9 +663,135 @@ pub fn join_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), Sa
     let plan_key = DataKey::SavingsPlan(user.clone(), group_id);
     env.storage().persistent().set(&plan_key, &savings_plan);
 
-    // Emit event for joining group
+    Ok(())
+}
+
+/// Puts a user on the waitlist of a group that has reached its member cap.
+///
+/// Waitlisted users are promoted to members in the order they joined the
+/// waitlist whenever a member leaves or is removed.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - The address of the user waiting for a slot
+/// * `group_id` - The ID of the full group
+///
+/// # Returns
+/// `Ok(u32)` - The user's position in the waitlist (0 = next in line)
+/// `Err(SavingsError)` if:
+/// - User doesn't exist (`UserNotFound`)
+/// - Group doesn't exist (`PlanNotFound`)
+/// - Group is no longer active (`PlanCompleted`)
+/// - Group is not public, is rotating, still has free slots, or the user is
+///   already a member or waitlisted (`InvalidGroupConfig`)
+/// - Group is past its deadline (`TooLate`)
+/// - The waitlist itself is full (`GroupFull`)
+pub fn join_group_waitlist(env: &Env, user: Address, group_id: u64) -> Result<u32, SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    if !users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.is_completed || group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if !group.is_public || group.mode == GroupMode::Rotating || !is_full(env, &group) {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    if env.ledger().timestamp() > group.end_time {
+        return Err(SavingsError::TooLate);
+    }
+
+    let mut waitlist = get_group_waitlist(env, group_id);
+    if is_member(env, group_id, &user) || waitlist.contains(&user) {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    if waitlist.len() >= config::get_config(env).max_waitlist_size {
+        return Err(SavingsError::GroupFull);
+    }
+
+    let position = waitlist.len();
+    waitlist.push_back(user.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupWaitlist(group_id), &waitlist);
+
     env.events()
-        .publish((soroban_sdk::symbol_short!("grp_join"), user), group_id);
+        .publish((soroban_sdk::symbol_short!("grp_wait"), user), group_id);
+
+    Ok(position)
+}
+
+/// Takes a user off a group's waitlist.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - The waitlisted user (must authorize)
+/// * `group_id` - The group ID
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError::NotGroupMember)` if the user is not on the waitlist
+pub fn leave_group_waitlist(env: &Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    let mut waitlist = get_group_waitlist(env, group_id);
+    let index = waitlist
+        .first_index_of(&user)
+        .ok_or(SavingsError::NotGroupMember)?;
+    waitlist.remove(index);
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupWaitlist(group_id), &waitlist);
+
+    Ok(())
+}
+
+/// VIEW FUNCTION - Gets a group's waitlist, next in line first
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `group_id` - The group ID
+///
+/// # Returns
+/// The waitlisted addresses in promotion order
+pub fn get_group_waitlist(env: &Env, group_id: u64) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GroupWaitlist(group_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Helper function filling a freed slot with the earliest waitlisted user.
+///
+/// Users who no longer have an account or already became members are
+/// skipped. The caller persists the group.
+fn promote_from_waitlist(env: &Env, group: &mut GroupSave) -> Result<(), SavingsError> {
+    if group.is_completed || group.status != GroupStatus::Active || is_full(env, group) {
+        return Ok(());
+    }
+
+    let mut waitlist = get_group_waitlist(env, group.id);
+    let mut promoted = None;
+    while let Some(candidate) = waitlist.pop_front() {
+        if users::user_exists(env, &candidate) && !is_member(env, group.id, &candidate) {
+            promoted = Some(candidate);
+            break;
+        }
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupWaitlist(group.id), &waitlist);
+
+    if let Some(user) = promoted {
+        admit_member(env, group, &user)?;
+        env.events()
+            .publish((soroban_sdk::symbol_short!("grp_join"), user), group.id);
+    }
 
     Ok(())
 }
@@ -311,7 +800,7 @@ pub fn join_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), Sa
 ///
 /// # Arguments
 /// * `env` - The contract environment
-/// * `user` - The address of the user contributing
+/// * `user` - The address of the user contributing (must authorize)
 /// * `group_id` - The ID of the group
 /// * `amount` - The amount to contribute (must be > 0)
 ///
@@ -321,17 +810,34 @@ pub fn join_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), Sa
 /// - Amount is invalid (<= 0)
 /// - User is not a member
 /// - Group doesn't exist
+/// - Amount is below the group's minimum contribution
+/// - The member's total would exceed the group's per-member cap
 pub fn contribute_to_group_save(
     env: &Env,
     user: Address,
     group_id: u64,
     amount: i128,
 ) -> Result<(), SavingsError> {
-    ensure_not_paused(env)?;
-    // Validate amount > 0
-    if amount <= 0 {
-        return Err(SavingsError::InvalidAmount);
-    }
+    user.require_auth();
+    record_contribution(env, user.clone(), group_id, amount)?;
+    assets::transfer_in(env, &assets::get_group_asset(env, group_id), &user, amount)
+}
+
+/// Credits a contribution to a member's record and the group's balance. The
+/// caller is responsible for collecting the funds.
+fn record_contribution(
+    env: &Env,
+    user: Address,
+    group_id: u64,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
+    // Validate amount > 0
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    config::ensure_min_deposit(env, amount)?;
 
     // Fetch the group
     let group_key = DataKey::GroupSave(group_id);
@@ -341,36 +847,52 @@ pub fn contribute_to_group_save(
         .get(&group_key)
         .ok_or(SavingsError::PlanNotFound)?;
 
+    // Settled or cancelled groups no longer accept funds or members
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+
     // Check if user is a member
-    let members_key = DataKey::GroupMembers(group_id);
-    let members: Vec<Address> = env
-        .storage()
-        .persistent()
-        .get(&members_key)
-        .ok_or(SavingsError::NotGroupMember)?;
+    if !is_member(env, group_id, &user) {
+        return Err(SavingsError::NotGroupMember);
+    }
 
-    let mut is_member = false;
-    for i in 0..members.len() {
-        if let Some(member) = members.get(i) {
-            if member == user {
-                is_member = true;
-                break;
-            }
-        }
+    // Contributions close at the group deadline
+    if env.ledger().timestamp() > group.end_time {
+        return Err(SavingsError::TooLate);
     }
 
-    if !is_member {
-        return Err(SavingsError::NotGroupMember);
+    if let Some(min_contribution) = group.min_contribution {
+        if amount < min_contribution {
+            return Err(SavingsError::ContributionTooSmall);
+        }
     }
 
-    // Update user's contribution
     let contribution_key = DataKey::GroupMemberContribution(group_id, user.clone());
     let current_contribution: i128 = env
         .storage()
         .persistent()
         .get(&contribution_key)
         .unwrap_or(0i128);
-    let new_contribution = current_contribution + amount;
+    let new_contribution = current_contribution
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+
+    if let Some(max_contribution) = group.max_contribution_per_member {
+        if new_contribution > max_contribution {
+            return Err(SavingsError::ContributionLimitExceeded);
+        }
+    }
+
+    // Rotating groups take fixed per-round contributions
+    if group.mode == GroupMode::Rotating {
+        rotation::record_contribution(env, &group, &user, amount)?;
+    }
+
+    // Settle interest on the balance held so far before it changes
+    accrue_interest(env, &mut group);
+
+    // Update user's contribution
     env.storage()
         .persistent()
         .set(&contribution_key, &new_contribution);
@@ -378,14 +900,18 @@ pub fn contribute_to_group_save(
     // Update group's current_amount
     group.current_amount += amount;
 
-    // Check if goal is reached
-    if group.current_amount >= group.target_amount {
+    // Check if goal is reached (rotating groups complete after the last payout)
+    if group.mode == GroupMode::Pooled && group.current_amount >= group.target_amount {
         group.is_completed = true;
+        delist_public_group(env, group_id);
     }
 
     // Save updated group
     env.storage().persistent().set(&group_key, &group);
 
+    history::record_contribution(env, group_id, &user, amount, group.current_amount);
+    cycles::record_cycle_contribution(env, &group, &user, amount);
+
     // Update the user's SavingsPlan to reflect the new balance
     let plan_key = DataKey::SavingsPlan(user.clone(), group_id);
     if let Some(mut plan) = env
@@ -428,6 +954,52 @@ pub fn contribute_to_group_save(
     Ok(())
 }
 
+/// Contributes to a group on behalf of one of its members.
+///
+/// The payer authorizes and funds the contribution, but it is credited to
+/// the beneficiary's contribution record exactly as if the beneficiary had
+/// contributed, including contribution limits and cycle tracking. The payer
+/// does not need to be a member of the group.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `payer` - The address funding the contribution (must authorize)
+/// * `group_id` - The ID of the group
+/// * `beneficiary` - The member credited with the contribution
+/// * `amount` - The amount to contribute (must be > 0)
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if:
+/// - Payer doesn't exist (`UserNotFound`)
+/// - Beneficiary is not a member (`NotGroupMember`)
+/// - Any check of `contribute_to_group_save` fails
+pub fn contribute_for(
+    env: &Env,
+    payer: Address,
+    group_id: u64,
+    beneficiary: Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &payer)?;
+    payer.require_auth();
+
+    if !users::user_exists(env, &payer) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    record_contribution(env, beneficiary.clone(), group_id, amount)?;
+    assets::transfer_in(env, &assets::get_group_asset(env, group_id), &payer, amount)?;
+
+    env.events().publish(
+        (soroban_sdk::symbol_short!("grp_cfor"), payer, beneficiary),
+        (group_id, amount),
+    );
+
+    Ok(())
+}
+
 /// VIEW FUNCTION - Gets a member's contribution to a group
 ///
 /// # Arguments
@@ -447,6 +1019,9 @@ pub fn get_member_contribution(env: &Env, group_id: u64, user: &Address) -> i128
 
 /// VIEW FUNCTION - Gets all members of a group
 ///
+/// Reads one storage entry per member; prefer `get_group_members_page` for
+/// listing members of large groups.
+///
 /// # Arguments
 /// * `env` - The contract environment
 /// * `group_id` - The group ID
@@ -454,11 +1029,114 @@ pub fn get_member_contribution(env: &Env, group_id: u64, user: &Address) -> i128
 /// # Returns
 /// A vector of member addresses
 pub fn get_group_members(env: &Env, group_id: u64) -> Vec<Address> {
-    let members_key = DataKey::GroupMembers(group_id);
+    let member_count = get_group_save(env, group_id)
+        .map(|group| group.member_count)
+        .unwrap_or(0);
+    read_members(env, group_id, 0, member_count)
+}
+
+/// VIEW FUNCTION - Gets a page of a group's members
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `group_id` - The group ID
+/// * `start` - Index of the first member to return
+/// * `limit` - Maximum number of members to return (capped at `MAX_PAGE_SIZE`)
+///
+/// # Returns
+/// Up to `limit` member addresses starting at `start`
+pub fn get_group_members_page(env: &Env, group_id: u64, start: u32, limit: u32) -> Vec<Address> {
+    let member_count = get_group_save(env, group_id)
+        .map(|group| group.member_count)
+        .unwrap_or(0);
+    let end = start
+        .saturating_add(limit.min(MAX_PAGE_SIZE))
+        .min(member_count);
+    read_members(env, group_id, start, end)
+}
+
+/// Checks whether a user is in a group's member list.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `group_id` - The group ID
+/// * `user` - The user address
+///
+/// # Returns
+/// `true` if the user is currently a member of the group
+pub(crate) fn is_member(env: &Env, group_id: u64, user: &Address) -> bool {
     env.storage()
         .persistent()
-        .get(&members_key)
-        .unwrap_or(Vec::new(env))
+        .has(&DataKey::GroupMemberIndex(group_id, user.clone()))
+}
+
+/// Helper function appending a member to a group's indexed member list and
+/// incrementing `member_count`. The caller persists the group.
+fn push_member(env: &Env, group: &mut GroupSave, user: &Address) {
+    let index = group.member_count;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupMemberByIndex(group.id, index), user);
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupMemberIndex(group.id, user.clone()), &index);
+    group.member_count += 1;
+}
+
+/// Helper function removing a member from a group's indexed member list by
+/// moving the last member into the freed slot, and decrementing
+/// `member_count`. The caller persists the group.
+///
+/// # Returns
+/// `Err(SavingsError::NotGroupMember)` if the user is not a member
+fn pop_member(env: &Env, group: &mut GroupSave, user: &Address) -> Result<(), SavingsError> {
+    let index_key = DataKey::GroupMemberIndex(group.id, user.clone());
+    let index: u32 = env
+        .storage()
+        .persistent()
+        .get(&index_key)
+        .ok_or(SavingsError::NotGroupMember)?;
+
+    let last = group
+        .member_count
+        .checked_sub(1)
+        .ok_or(SavingsError::DataCorruption)?;
+    if index != last {
+        let last_member: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::GroupMemberByIndex(group.id, last))
+            .ok_or(SavingsError::DataCorruption)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::GroupMemberByIndex(group.id, index), &last_member);
+        env.storage()
+            .persistent()
+            .set(&DataKey::GroupMemberIndex(group.id, last_member), &index);
+    }
+
+    env.storage()
+        .persistent()
+        .remove(&DataKey::GroupMemberByIndex(group.id, last));
+    env.storage().persistent().remove(&index_key);
+    group.member_count = last;
+
+    Ok(())
+}
+
+/// Helper function reading members at indexes `start..end`.
+fn read_members(env: &Env, group_id: u64, start: u32, end: u32) -> Vec<Address> {
+    let mut members = Vec::new(env);
+    for index in start..end {
+        if let Some(member) = env
+            .storage()
+            .persistent()
+            .get(&DataKey::GroupMemberByIndex(group_id, index))
+        {
+            members.push_back(member);
+        }
+    }
+    members
 }
 
 /// Helper function to remove a group ID from a user's list of groups.
@@ -517,7 +1195,8 @@ fn remove_group_from_user_list(
 /// - User is not a member of the group
 /// - Group is already completed
 pub fn break_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
-    ensure_not_paused(env)?;
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
 
     // Ensure user exists
     if !users::user_exists(env, &user) {
@@ -533,78 +1212,1311 @@ pub fn break_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), S
         .ok_or(SavingsError::PlanNotFound)?;
 
     // Check that the group is not already completed
-    if group.is_completed {
+    if group.is_completed || group.status != GroupStatus::Active {
         return Err(SavingsError::PlanCompleted);
     }
 
-    // Check if user is a member
-    let members_key = DataKey::GroupMembers(group_id);
-    let members: Vec<Address> = env
+    // Remove membership and collect the user's contribution
+    let user_contribution = detach_member(env, &mut group, &user)?;
+
+    // Update group's current_amount
+    group.current_amount = group.current_amount.saturating_sub(user_contribution);
+
+    // Fill the freed slot from the waitlist
+    promote_from_waitlist(env, &mut group)?;
+
+    // Save updated group
+    env.storage().persistent().set(&group_key, &group);
+
+    // Emit event for leaving group
+    env.events().publish(
+        (soroban_sdk::symbol_short!("grp_leave"), user, group_id),
+        user_contribution,
+    );
+
+    Ok(())
+}
+
+/// Allows a member to leave a group savings plan.
+///
+/// What happens to the member's recorded contribution depends on the group's
+/// `leave_policy`:
+/// - `LeavePolicy::Refund` - the contribution leaves the pool and is credited
+///   to the member's Flexi balance
+/// - `LeavePolicy::Forfeit` - the contribution stays in the pool
+///
+/// The creator cannot leave their own group.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - The address of the member leaving
+/// * `group_id` - The ID of the group to leave
+///
+/// # Returns
+/// `Ok(i128)` - The amount refunded to the member (0 when forfeited)
+/// `Err(SavingsError)` if:
+/// - User doesn't exist
+/// - Group doesn't exist
+/// - Group is already completed
+/// - User is not a member, or is the creator
+pub fn leave_group_save(env: &Env, user: Address, group_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+
+    if !users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
         .storage()
         .persistent()
-        .get(&members_key)
-        .ok_or(SavingsError::NotGroupMember)?;
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
 
-    let mut is_member = false;
-    let mut member_index: Option<u32> = None;
+    if group.is_completed || group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
 
-    for i in 0..members.len() {
-        if let Some(member) = members.get(i) {
-            if member == user {
-                is_member = true;
-                member_index = Some(i);
-                break;
-            }
-        }
+    if group.creator == user {
+        return Err(SavingsError::InvalidGroupConfig);
     }
 
-    if !is_member {
-        return Err(SavingsError::NotGroupMember);
+    let contribution = detach_member(env, &mut group, &user)?;
+
+    let refund = match group.leave_policy {
+        LeavePolicy::Refund => contribution,
+        LeavePolicy::Forfeit => 0,
+    };
+    group.current_amount = group
+        .current_amount
+        .checked_sub(refund)
+        .ok_or(SavingsError::Underflow)?;
+
+    // Fill the freed slot from the waitlist
+    promote_from_waitlist(env, &mut group)?;
+
+    env.storage().persistent().set(&group_key, &group);
+
+    assets::credit(env, &user, &assets::get_group_asset(env, group_id), refund)?;
+
+    env.events().publish(
+        (soroban_sdk::symbol_short!("grp_exit"), user, group_id),
+        refund,
+    );
+
+    Ok(refund)
+}
+
+/// Allows the group creator to remove a member from the group.
+///
+/// The removed member's recorded contribution is always returned to their
+/// Flexi balance, regardless of the group's leave policy, so that removal
+/// can never be used to confiscate funds.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `member` - The member to remove
+///
+/// # Returns
+/// `Ok(i128)` - The amount refunded to the removed member
+/// `Err(SavingsError)` if:
+/// - Group doesn't exist
+/// - Caller is not the creator
+/// - Group is already completed
+/// - Member is not in the group, or is the creator
+pub fn remove_group_member(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    member: Address,
+) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
     }
 
-    // Remove user from members list
-    let mut new_members = Vec::new(env);
-    for i in 0..members.len() {
-        if Some(i) != member_index {
-            if let Some(member) = members.get(i) {
-                new_members.push_back(member);
-            }
-        }
+    if group.is_completed || group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
     }
-    env.storage().persistent().set(&members_key, &new_members);
 
-    // Decrement member count
-    group.member_count = group.member_count.saturating_sub(1);
+    if member == creator {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
 
-    // Get user's contribution
-    let contribution_key = DataKey::GroupMemberContribution(group_id, user.clone());
-    let user_contribution: i128 = env
+    let refund = detach_member(env, &mut group, &member)?;
+    group.current_amount = group
+        .current_amount
+        .checked_sub(refund)
+        .ok_or(SavingsError::Underflow)?;
+
+    // Fill the freed slot from the waitlist
+    promote_from_waitlist(env, &mut group)?;
+
+    env.storage().persistent().set(&group_key, &group);
+
+    assets::credit(
+        env,
+        &member,
+        &assets::get_group_asset(env, group_id),
+        refund,
+    )?;
+
+    env.events().publish(
+        (soroban_sdk::symbol_short!("grp_kick"), member, group_id),
+        refund,
+    );
+
+    Ok(refund)
+}
+
+/// Pays out a completed group's pooled funds and marks the group settled.
+///
+/// If the group has a `payout_beneficiary`, the whole pot is credited to that
+/// address. Otherwise `current_amount` is split pro-rata to each member's
+/// recorded contribution (equally if no member has a recorded contribution),
+/// with any rounding remainder going to the first member in the list.
+/// Interest accrued on the pool at the group rate is always split pro-rata
+/// between members. Payouts are credited to Flexi balances. Anyone may
+/// trigger distribution once the group is completed.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `group_id` - The group ID
+///
+/// # Returns
+/// `Ok(i128)` - The pooled amount distributed, excluding interest
+/// `Err(SavingsError)` if:
+/// - Group doesn't exist
+/// - Group is not completed yet (`GroupCycleIncomplete`)
+/// - Group was already settled (`PlanCompleted`)
+pub fn distribute_group_save(env: &Env, group_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
         .storage()
         .persistent()
-        .get(&contribution_key)
-        .unwrap_or(0i128);
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
 
-    // Update group's current_amount
-    group.current_amount = group.current_amount.saturating_sub(user_contribution);
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
 
-    // Save updated group
+    if !group.is_completed {
+        return Err(SavingsError::GroupCycleIncomplete);
+    }
+
+    accrue_interest(env, &mut group);
+    let pot = group.current_amount;
+    let interest = group.accrued_interest;
+
+    // Effects first: mark settled before crediting anyone
+    group.current_amount = 0;
+    group.accrued_interest = 0;
+    group.status = GroupStatus::Settled;
     env.storage().persistent().set(&group_key, &group);
 
-    // Remove user's contribution entry
-    env.storage().persistent().remove(&contribution_key);
+    let members = get_group_members(env, group_id);
+    close_member_plans(env, group_id, &members);
 
-    // Remove group from user's list of groups
-    remove_group_from_user_list(env, &user, group_id)?;
+    pay_out_pot(env, &group, &members, pot)?;
+    if interest > 0 {
+        pay_pro_rata(env, group_id, &members, interest)?;
+    }
 
-    // Delete user's SavingsPlan for this group
-    let plan_key = DataKey::SavingsPlan(user.clone(), group_id);
-    env.storage().persistent().remove(&plan_key);
+    env.events()
+        .publish((soroban_sdk::symbol_short!("grp_dist"), group_id), pot);
 
-    // Emit event for leaving group
-    env.events().publish(
-        (soroban_sdk::symbol_short!("grp_leave"), user, group_id),
-        user_contribution,
-    );
+    Ok(pot)
+}
 
-    Ok(())
+/// Cancels an incomplete group and refunds every member.
+///
+/// Each member's exact recorded contribution is credited back to their
+/// Flexi balance. Any remainder in the pool (contributions forfeited by
+/// members who left) is shared pro-rata between the remaining members.
+/// For rotating groups only the contributions of the open round are
+/// refunded, since earlier pots have already been paid out.
+/// Cancelled groups reject further joins and contributions.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+///
+/// # Returns
+/// `Ok(i128)` - The total amount refunded
+/// `Err(SavingsError)` if:
+/// - Group doesn't exist
+/// - Caller is not the creator
+/// - Group is completed, settled, or already cancelled
+pub fn cancel_group_save(env: &Env, creator: Address, group_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if group.is_completed || group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let pot = group.current_amount;
+    group.current_amount = 0;
+    group.status = GroupStatus::Cancelled;
+    env.storage().persistent().set(&group_key, &group);
+    delist_public_group(env, group_id);
+
+    let members = get_group_members(env, group_id);
+    close_member_plans(env, group_id, &members);
+
+    refund_members(env, &group, &members, pot)?;
+
+    env.events()
+        .publish((soroban_sdk::symbol_short!("grp_cncl"), group_id), pot);
+
+    Ok(pot)
+}
+
+/// Dissolves a group in an emergency, refunding every member.
+///
+/// Intended for abuse or stuck state; works on completed groups and while
+/// the contract is paused. Members are refunded exactly as on cancellation
+/// and the group is marked `Cancelled`. The caller is responsible for
+/// checking that `admin` is the protocol admin and has authorized.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `admin` - The protocol admin, recorded in the audit event
+/// * `group_id` - The group ID
+///
+/// # Returns
+/// `Ok(i128)` - The total amount refunded
+/// `Err(SavingsError)` if:
+/// - Group doesn't exist (`PlanNotFound`)
+/// - Group was already settled, cancelled or merged (`PlanCompleted`)
+pub fn force_dissolve_group(
+    env: &Env,
+    admin: Address,
+    group_id: u64,
+) -> Result<i128, SavingsError> {
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let pot = group.current_amount;
+    group.current_amount = 0;
+    group.accrued_interest = 0;
+    group.status = GroupStatus::Cancelled;
+    env.storage().persistent().set(&group_key, &group);
+    delist_public_group(env, group_id);
+
+    let members = get_group_members(env, group_id);
+    close_member_plans(env, group_id, &members);
+
+    refund_members(env, &group, &members, pot)?;
+
+    env.events().publish(
+        (soroban_sdk::symbol_short!("grp_fdis"), admin, group_id),
+        pot,
+    );
+
+    Ok(pot)
+}
+
+/// Closes a group whose deadline (`end_time`) has passed.
+///
+/// Completed groups are distributed as usual. For incomplete groups the
+/// group's `expiry_policy` decides what happens to the funds raised:
+/// - `ExpiryPolicy::Refund` - members get their contributions back and the
+///   group is cancelled
+/// - `ExpiryPolicy::Distribute` - the pot is paid out as if the group had
+///   completed and the group is settled
+///
+/// Anyone may finalize an expired group.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `group_id` - The group ID
+///
+/// # Returns
+/// `Ok(i128)` - The amount paid out or refunded
+/// `Err(SavingsError)` if:
+/// - Group doesn't exist
+/// - Deadline has not passed yet (`TooEarly`)
+/// - Group is already settled or cancelled (`PlanCompleted`)
+pub fn finalize_expired_group(env: &Env, group_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    if env.ledger().timestamp() <= group.end_time {
+        return Err(SavingsError::TooEarly);
+    }
+
+    let distribute = group.mode == GroupMode::Pooled
+        && (group.is_completed || group.expiry_policy == ExpiryPolicy::Distribute);
+
+    // Interest is only earned by groups that pay out
+    accrue_interest(env, &mut group);
+    let pot = group.current_amount;
+    let interest = group.accrued_interest;
+    group.current_amount = 0;
+    group.accrued_interest = 0;
+    group.status = if distribute {
+        GroupStatus::Settled
+    } else {
+        GroupStatus::Cancelled
+    };
+    env.storage().persistent().set(&group_key, &group);
+    delist_public_group(env, group_id);
+
+    let members = get_group_members(env, group_id);
+    close_member_plans(env, group_id, &members);
+
+    if distribute {
+        pay_out_pot(env, &group, &members, pot)?;
+        if interest > 0 {
+            pay_pro_rata(env, group_id, &members, interest)?;
+        }
+    } else {
+        refund_members(env, &group, &members, pot)?;
+    }
+
+    env.events()
+        .publish((soroban_sdk::symbol_short!("grp_exp"), group_id), pot);
+
+    Ok(pot)
+}
+
+/// Re-opens a completed group with a higher target.
+///
+/// A group that reached its target but has not been distributed yet can
+/// keep saving together: the target is raised and `is_completed` is reset so
+/// members may contribute again until the new target or the deadline.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `new_target` - The new target amount (must exceed the amount raised)
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if:
+/// - Group doesn't exist (`PlanNotFound`)
+/// - Caller is not the creator (`Unauthorized`)
+/// - Group was already settled or cancelled (`PlanCompleted`)
+/// - Group hasn't reached its target yet or is rotating (`InvalidGroupConfig`)
+/// - Group is past its deadline (`TooLate`)
+/// - `new_target` doesn't exceed the amount raised (`InvalidAmount`)
+pub fn extend_group_target(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    new_target: i128,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if !group.is_completed || group.mode != GroupMode::Pooled {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    if env.ledger().timestamp() > group.end_time {
+        return Err(SavingsError::TooLate);
+    }
+    if new_target <= group.current_amount || new_target <= group.target_amount {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let previous_target = group.target_amount;
+    group.target_amount = new_target;
+    group.is_completed = false;
+    env.storage().persistent().set(&group_key, &group);
+
+    if group.is_public {
+        list_public_group(env, group_id);
+    }
+
+    env.events().publish(
+        (soroban_sdk::symbol_short!("grp_ext"), group_id),
+        (previous_target, new_target),
+    );
+
+    Ok(())
+}
+
+/// Folds one group's members and funds into another group.
+///
+/// Every member of the source group becomes a member of the target group
+/// (members of both keep a single membership) and their recorded
+/// contribution is added to their contribution in the target. The source
+/// pool and any interest it accrued move to the target, and the source is
+/// marked `Merged`. The caller must be the creator of both groups.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - Creator of both groups (must authorize)
+/// * `source_id` - The group being folded in
+/// * `target_id` - The group receiving members and funds
+///
+/// # Returns
+/// `Ok(i128)` - The pooled amount moved into the target group
+/// `Err(SavingsError)` if:
+/// - Either group doesn't exist (`PlanNotFound`)
+/// - The caller doesn't own both groups (`Unauthorized`)
+/// - Either group is no longer active or is completed (`PlanCompleted`)
+/// - The groups are the same, rotating, or have different contribution
+///   terms (`InvalidGroupConfig`)
+/// - The target cannot take the extra members (`GroupFull`)
+/// - A merged contribution exceeds the target's per-member cap
+///   (`ContributionLimitExceeded`)
+pub fn merge_groups(
+    env: &Env,
+    creator: Address,
+    source_id: u64,
+    target_id: u64,
+) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    if source_id == target_id {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    let mut source = get_group_save(env, source_id).ok_or(SavingsError::PlanNotFound)?;
+    let mut target = get_group_save(env, target_id).ok_or(SavingsError::PlanNotFound)?;
+
+    if source.creator != creator || target.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    for group in [&source, &target] {
+        if group.is_completed || group.status != GroupStatus::Active {
+            return Err(SavingsError::PlanCompleted);
+        }
+        if group.mode != GroupMode::Pooled {
+            return Err(SavingsError::InvalidGroupConfig);
+        }
+    }
+
+    if source.contribution_type != target.contribution_type
+        || source.contribution_amount != target.contribution_amount
+        || assets::get_group_asset(env, source_id) != assets::get_group_asset(env, target_id)
+    {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    // Settle interest on both pools before they change
+    accrue_interest(env, &mut source);
+    accrue_interest(env, &mut target);
+
+    let members = get_group_members(env, source_id);
+    for i in 0..members.len() {
+        let member = members.get(i).unwrap();
+        let contribution = detach_member(env, &mut source, &member)?;
+
+        if !is_member(env, target_id, &member) {
+            if is_full(env, &target) {
+                return Err(SavingsError::GroupFull);
+            }
+            push_member(env, &mut target, &member);
+            add_group_to_user_list(env, &member, target_id)?;
+            cycles::record_join(env, &target, &member);
+        }
+
+        let contribution_key = DataKey::GroupMemberContribution(target_id, member.clone());
+        let merged: i128 = env
+            .storage()
+            .persistent()
+            .get(&contribution_key)
+            .unwrap_or(0i128)
+            .checked_add(contribution)
+            .ok_or(SavingsError::Overflow)?;
+        if let Some(max_contribution) = target.max_contribution_per_member {
+            if merged > max_contribution {
+                return Err(SavingsError::ContributionLimitExceeded);
+            }
+        }
+        env.storage().persistent().set(&contribution_key, &merged);
+
+        let plan_key = DataKey::SavingsPlan(member.clone(), target_id);
+        let mut plan = env
+            .storage()
+            .persistent()
+            .get::<DataKey, crate::storage_types::SavingsPlan>(&plan_key)
+            .unwrap_or(crate::storage_types::SavingsPlan {
+                plan_id: target_id,
+                plan_type: crate::storage_types::PlanType::Group(
+                    target_id,
+                    target.is_public,
+                    target.contribution_type,
+                    target.target_amount,
+                ),
+                balance: 0,
+                start_time: env.ledger().timestamp(),
+                last_deposit: 0,
+                last_withdraw: 0,
+                interest_rate: 500,
+                is_completed: false,
+                is_withdrawn: false,
+            });
+        plan.balance += contribution;
+        env.storage().persistent().set(&plan_key, &plan);
+    }
+
+    let moved = source.current_amount;
+    target.current_amount = target
+        .current_amount
+        .checked_add(moved)
+        .ok_or(SavingsError::Overflow)?;
+    target.accrued_interest = target
+        .accrued_interest
+        .checked_add(source.accrued_interest)
+        .ok_or(SavingsError::Overflow)?;
+    if target.current_amount >= target.target_amount {
+        target.is_completed = true;
+        delist_public_group(env, target_id);
+    }
+
+    source.current_amount = 0;
+    source.accrued_interest = 0;
+    source.status = GroupStatus::Merged;
+    delist_public_group(env, source_id);
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupSave(source_id), &source);
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupSave(target_id), &target);
+
+    env.events().publish(
+        (
+            soroban_sdk::symbol_short!("grp_merge"),
+            source_id,
+            target_id,
+        ),
+        moved,
+    );
+
+    Ok(moved)
+}
+
+/// Sets what happens to an incomplete group's funds once its deadline passes.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `policy` - The new expiry policy
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if the group doesn't exist or the caller is not the creator
+pub fn set_group_expiry_policy(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    policy: ExpiryPolicy,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    group.expiry_policy = policy;
+    env.storage().persistent().set(&group_key, &group);
+
+    Ok(())
+}
+
+/// Sets (or clears) the address that receives a group's entire pot on
+/// distribution, instead of a pro-rata split between members.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `beneficiary` - The payout recipient, or `None` for pro-rata payout
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if the group doesn't exist, the caller is not the
+/// creator, the group is settled, or the beneficiary has no account
+pub fn set_group_payout_beneficiary(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    beneficiary: Option<Address>,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    if let Some(ref recipient) = beneficiary {
+        if !users::user_exists(env, recipient) {
+            return Err(SavingsError::UserNotFound);
+        }
+    }
+
+    group.payout_beneficiary = beneficiary;
+    env.storage().persistent().set(&group_key, &group);
+
+    Ok(())
+}
+
+/// Starts a two-step transfer of group ownership.
+///
+/// The nominated owner must be a member of the group and becomes the creator
+/// only after calling `accept_group_ownership`. Nominating again replaces
+/// any pending nomination.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `current_creator` - The current group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `new_owner` - The member nominated as the new creator
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if:
+/// - Group doesn't exist
+/// - Caller is not the creator
+/// - Group is settled or cancelled
+/// - New owner is not a member, or is already the creator
+pub fn transfer_group_ownership(
+    env: &Env,
+    current_creator: Address,
+    group_id: u64,
+    new_owner: Address,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    current_creator.require_auth();
+
+    let group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&DataKey::GroupSave(group_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.creator != current_creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    if new_owner == current_creator {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    if !is_member(env, group_id, &new_owner) {
+        return Err(SavingsError::NotGroupMember);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupPendingOwner(group_id), &new_owner);
+
+    env.events().publish(
+        (
+            soroban_sdk::symbol_short!("grp_xfer"),
+            current_creator,
+            group_id,
+        ),
+        new_owner,
+    );
+
+    Ok(())
+}
+
+/// Completes a pending ownership transfer, making the caller the creator.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `new_owner` - The nominated owner (must authorize)
+/// * `group_id` - The group ID
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if:
+/// - Group doesn't exist
+/// - Caller is not the pending owner
+/// - Caller is no longer a member
+/// - Group is settled or cancelled
+pub fn accept_group_ownership(
+    env: &Env,
+    new_owner: Address,
+    group_id: u64,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    new_owner.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    let pending_key = DataKey::GroupPendingOwner(group_id);
+    let pending: Option<Address> = env.storage().persistent().get(&pending_key);
+    if pending != Some(new_owner.clone()) {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    if !is_member(env, group_id, &new_owner) {
+        return Err(SavingsError::NotGroupMember);
+    }
+
+    let previous = group.creator.clone();
+    group.creator = new_owner.clone();
+    env.storage().persistent().set(&group_key, &group);
+    env.storage().persistent().remove(&pending_key);
+
+    env.events().publish(
+        (soroban_sdk::symbol_short!("grp_owner"), new_owner, group_id),
+        previous,
+    );
+
+    Ok(())
+}
+
+/// VIEW FUNCTION - Gets the nominated owner of a pending ownership transfer
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `group_id` - The group ID
+///
+/// # Returns
+/// The pending owner, if a transfer is in progress
+pub fn get_group_pending_owner(env: &Env, group_id: u64) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GroupPendingOwner(group_id))
+}
+
+/// Sets what happens to a member's contribution when they leave the group.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `policy` - The new leave policy
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if the group doesn't exist or the caller is not the creator
+pub fn set_group_leave_policy(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    policy: LeavePolicy,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    group.leave_policy = policy;
+    env.storage().persistent().set(&group_key, &group);
+
+    Ok(())
+}
+
+/// Sets the percentage of members that must vote for a group change to pass.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `quorum` - Percentage of members required (1-100)
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if the group doesn't exist, the caller is not the
+/// creator, or the quorum is out of range
+pub fn set_group_change_quorum(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    quorum: u32,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if quorum == 0 || quorum > 100 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    group.change_quorum = quorum;
+    env.storage().persistent().set(&group_key, &group);
+
+    Ok(())
+}
+
+/// Sets the per-contribution minimum and per-member cap of a group.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+/// * `min_contribution` - Smallest amount accepted per contribution, if any
+/// * `max_contribution_per_member` - Cap on each member's total, if any
+///
+/// # Returns
+/// `Ok(())` on success
+/// `Err(SavingsError)` if the group doesn't exist, the caller is not the
+/// creator, or the limits are not positive or the minimum exceeds the cap
+pub fn set_group_contribution_limits(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    min_contribution: Option<i128>,
+    max_contribution_per_member: Option<i128>,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if min_contribution.is_some_and(|min| min <= 0)
+        || max_contribution_per_member.is_some_and(|max| max <= 0)
+    {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if let (Some(min), Some(max)) = (min_contribution, max_contribution_per_member) {
+        if min > max {
+            return Err(SavingsError::InvalidGroupConfig);
+        }
+    }
+
+    group.min_contribution = min_contribution;
+    group.max_contribution_per_member = max_contribution_per_member;
+    env.storage().persistent().set(&group_key, &group);
+
+    Ok(())
+}
+
+/// Helper function to remove a member from a group.
+///
+/// Removes the user from the member list, decrements `member_count`, and
+/// cleans up the member's contribution entry, group list entry, and
+/// SavingsPlan. The caller is responsible for adjusting `current_amount`
+/// and persisting the group.
+///
+/// # Returns
+/// `Ok(i128)` - The member's recorded contribution before removal
+/// `Err(SavingsError::NotGroupMember)` if the user is not a member
+fn detach_member(env: &Env, group: &mut GroupSave, user: &Address) -> Result<i128, SavingsError> {
+    let group_id = group.id;
+
+    // Members cannot drop out of a rotation that may already have paid them
+    if group.mode == GroupMode::Rotating {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    // Settle interest on the balance held so far before it changes
+    accrue_interest(env, group);
+
+    // Remove user from members list and decrement member count
+    pop_member(env, group, user)?;
+
+    // Get and remove user's contribution entry
+    let contribution_key = DataKey::GroupMemberContribution(group_id, user.clone());
+    let user_contribution: i128 = env
+        .storage()
+        .persistent()
+        .get(&contribution_key)
+        .unwrap_or(0i128);
+    env.storage().persistent().remove(&contribution_key);
+    env.storage()
+        .persistent()
+        .remove(&DataKey::GroupMemberCycle(group_id, user.clone()));
+
+    // Remove group from user's list of groups
+    remove_group_from_user_list(env, user, group_id)?;
+
+    // Delete user's SavingsPlan for this group
+    let plan_key = DataKey::SavingsPlan(user.clone(), group_id);
+    env.storage().persistent().remove(&plan_key);
+
+    Ok(user_contribution)
+}
+
+/// Helper function to credit a payout and emit a payout event.
+pub(crate) fn pay_member(
+    env: &Env,
+    group_id: u64,
+    member: &Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    assets::credit(env, member, &assets::get_group_asset(env, group_id), amount)?;
+    env.events().publish(
+        (
+            soroban_sdk::symbol_short!("grp_pay"),
+            member.clone(),
+            group_id,
+        ),
+        amount,
+    );
+    Ok(())
+}
+
+/// Helper function paying a pot out to the group's beneficiary, or pro-rata
+/// to members when no beneficiary is set.
+///
+/// In the pro-rata case, late penalties for missed cycles are deducted from
+/// each member's share and split between the members who are not behind,
+/// pro-rata to their shares. If every member is behind, no penalty applies.
+/// Under a penalty routing policy, penalties in the savings token are routed
+/// by `penalty_pool::route_penalty` instead, whoever is behind.
+fn pay_out_pot(
+    env: &Env,
+    group: &GroupSave,
+    members: &Vec<Address>,
+    pot: i128,
+) -> Result<(), SavingsError> {
+    if let Some(beneficiary) = group.payout_beneficiary.clone() {
+        return pay_member(env, group.id, &beneficiary, pot);
+    }
+
+    let mut shares = pro_rata_shares(env, group.id, members, pot)?;
+
+    let mut penalties = Vec::new(env);
+    let mut collected: i128 = 0;
+    let mut on_time_total: i128 = 0;
+    let mut last_on_time = None;
+    for i in 0..members.len() {
+        let share = shares.get(i).unwrap();
+        let penalty = cycles::late_penalty(env, group, &members.get(i).unwrap()).min(share);
+        penalties.push_back(penalty);
+        collected += penalty;
+        if penalty == 0 {
+            on_time_total += share;
+            last_on_time = Some(i);
+        }
+    }
+
+    let asset = assets::get_group_asset(env, group.id);
+    let routed = penalty_pool::get_penalty_routing(env).is_some()
+        && asset.is_none_or(|asset| assets::is_savings_token(env, &asset));
+    if collected > 0 && routed {
+        for i in 0..members.len() {
+            let penalty = penalties.get(i).unwrap();
+            if penalty > 0 {
+                let member = members.get(i).unwrap();
+                penalty_pool::route_penalty(env, &member, penalty)?;
+                env.events().publish(
+                    (soroban_sdk::symbol_short!("grp_pen"), member, group.id),
+                    penalty,
+                );
+                shares.set(i, shares.get(i).unwrap() - penalty);
+            }
+        }
+    } else if let (true, Some(last_on_time)) = (collected > 0, last_on_time) {
+        let mut redistributed: i128 = 0;
+        for i in 0..members.len() {
+            let share = shares.get(i).unwrap();
+            let penalty = penalties.get(i).unwrap();
+            let adjusted = if penalty > 0 {
+                env.events().publish(
+                    (
+                        soroban_sdk::symbol_short!("grp_pen"),
+                        members.get(i).unwrap(),
+                        group.id,
+                    ),
+                    penalty,
+                );
+                share - penalty
+            } else if i == last_on_time {
+                // Last on-time member absorbs the rounding remainder
+                share + (collected - redistributed)
+            } else {
+                let bonus = if on_time_total > 0 {
+                    collected.checked_mul(share).ok_or(SavingsError::Overflow)? / on_time_total
+                } else {
+                    0
+                };
+                redistributed += bonus;
+                share + bonus
+            };
+            shares.set(i, adjusted);
+        }
+    }
+
+    for i in 0..members.len() {
+        pay_member(
+            env,
+            group.id,
+            &members.get(i).unwrap(),
+            shares.get(i).unwrap(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Helper function accruing interest on a pooled group's `current_amount` at
+/// the admin-configured group rate since `last_accrual`. The caller persists
+/// the group.
+pub(crate) fn accrue_interest(env: &Env, group: &mut GroupSave) {
+    let now = env.ledger().timestamp();
+    if group.mode == GroupMode::Pooled && group.status == GroupStatus::Active {
+        let elapsed = now.saturating_sub(group.last_accrual);
+        let interest = rates::calculate_flexi_interest(
+            group.current_amount,
+            rates::get_group_rate(env),
+            elapsed,
+        );
+        group.accrued_interest = group.accrued_interest.saturating_add(interest);
+    }
+    group.last_accrual = now;
+}
+
+/// Helper function returning a pot to the members who paid into it.
+///
+/// Each member gets their exact recorded contribution back and any remainder
+/// (forfeited contributions) is shared pro-rata. For rotating groups only the
+/// open round is refunded, since earlier pots have already been paid out.
+fn refund_members(
+    env: &Env,
+    group: &GroupSave,
+    members: &Vec<Address>,
+    pot: i128,
+) -> Result<(), SavingsError> {
+    if group.mode == GroupMode::Rotating {
+        // Earlier rounds were already paid out; only the open round is refunded
+        let contributors = rotation::current_round_contributors(env, group.id);
+        for i in 0..contributors.len() {
+            let contributor = contributors.get(i).unwrap();
+            pay_member(env, group.id, &contributor, group.contribution_amount)?;
+        }
+    } else {
+        let mut contributed: i128 = 0;
+        for i in 0..members.len() {
+            contributed = contributed
+                .checked_add(get_member_contribution(
+                    env,
+                    group.id,
+                    &members.get(i).unwrap(),
+                ))
+                .ok_or(SavingsError::Overflow)?;
+        }
+
+        // Approved withdrawals may have left less than was paid in; share
+        // what is left pro-rata instead of refunding in full
+        if contributed > pot {
+            return pay_pro_rata(env, group.id, members, pot);
+        }
+
+        for i in 0..members.len() {
+            let member = members.get(i).unwrap();
+            let contribution = get_member_contribution(env, group.id, &member);
+            pay_member(env, group.id, &member, contribution)?;
+        }
+
+        let remainder = pot - contributed;
+        if remainder > 0 {
+            pay_pro_rata(env, group.id, members, remainder)?;
+        }
+    }
+    Ok(())
+}
+
+/// Helper function splitting `amount` between members pro-rata to their
+/// payout weights. Index 0 is paid last so it absorbs the rounding remainder.
+fn pay_pro_rata(
+    env: &Env,
+    group_id: u64,
+    members: &Vec<Address>,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    let shares = pro_rata_shares(env, group_id, members, amount)?;
+    for i in (0..members.len()).rev() {
+        pay_member(
+            env,
+            group_id,
+            &members.get(i).unwrap(),
+            shares.get(i).unwrap(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Helper function computing each member's pro-rata share of `amount`.
+/// Index 0 absorbs the rounding remainder.
+fn pro_rata_shares(
+    env: &Env,
+    group_id: u64,
+    members: &Vec<Address>,
+    amount: i128,
+) -> Result<Vec<i128>, SavingsError> {
+    let (weights, total_weight) = payout_weights(env, group_id, members);
+    let mut shares = Vec::new(env);
+    let mut paid: i128 = 0;
+    for i in 0..members.len() {
+        let share = if i == 0 {
+            0
+        } else {
+            amount
+                .checked_mul(weights.get(i).unwrap())
+                .ok_or(SavingsError::Overflow)?
+                / total_weight
+        };
+        paid += share;
+        shares.push_back(share);
+    }
+    if !members.is_empty() {
+        shares.set(0, amount - paid);
+    }
+    Ok(shares)
+}
+
+/// Helper function marking every member's SavingsPlan for a group withdrawn.
+pub(crate) fn close_member_plans(env: &Env, group_id: u64, members: &Vec<Address>) {
+    for i in 0..members.len() {
+        let plan_key = DataKey::SavingsPlan(members.get(i).unwrap(), group_id);
+        if let Some(mut plan) = env
+            .storage()
+            .persistent()
+            .get::<DataKey, crate::storage_types::SavingsPlan>(&plan_key)
+        {
+            plan.is_withdrawn = true;
+            plan.last_withdraw = env.ledger().timestamp();
+            env.storage().persistent().set(&plan_key, &plan);
+        }
+    }
+}
+
+/// Helper function computing each member's payout weight and their sum.
+///
+/// Weights are the recorded contributions, or 1 for every member when no
+/// member has contributed (equal split).
+fn payout_weights(env: &Env, group_id: u64, members: &Vec<Address>) -> (Vec<i128>, i128) {
+    let mut weights = Vec::new(env);
+    let mut total: i128 = 0;
+    for i in 0..members.len() {
+        let contribution = get_member_contribution(env, group_id, &members.get(i).unwrap());
+        weights.push_back(contribution);
+        total += contribution;
+    }
+
+    if total == 0 {
+        weights = Vec::new(env);
+        for _ in 0..members.len() {
+            weights.push_back(1);
+        }
+        total = members.len() as i128;
+    }
+
+    (weights, total)
 }
@@ -0,0 +1,1036 @@
+use crate::{
+    ExpiryPolicy, GroupChange, GroupCreationFee, GroupStatus, LeavePolicy, NesteraContract,
+    NesteraContractClient, SavingsError,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env, String, Vec,
+};
+
+fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &admin_pk, &None);
+
+    (env, client, admin)
+}
+
+fn create_group(env: &Env, client: &NesteraContractClient<'static>, creator: &Address) -> u64 {
+    client.create_group_save(
+        creator,
+        &String::from_str(env, "Test Group"),
+        &String::from_str(env, "Description"),
+        &String::from_str(env, "savings"),
+        &5000,
+        &0,
+        &100,
+        &true,
+        &1,
+        &500,
+    )
+}
+
+fn new_user(env: &Env, client: &NesteraContractClient<'static>) -> Address {
+    let user = Address::generate(env);
+    client.initialize_user(&user);
+    user
+}
+
+#[test]
+fn test_leave_group_refunds_to_flexi() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+    client.contribute_to_group_save(&member, &group_id, &300);
+
+    let refund = client.leave_group_save(&member, &group_id);
+    assert_eq!(refund, 300);
+    assert_eq!(client.get_flexi_balance(&member), 300);
+
+    let group = client.get_group_save(&group_id).unwrap();
+    assert_eq!(group.member_count, 1);
+    assert_eq!(group.current_amount, 0);
+
+    let res = client.try_contribute_to_group_save(&member, &group_id, &100);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::NotGroupMember));
+}
+
+#[test]
+fn test_leave_group_forfeit_keeps_pool() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.set_group_leave_policy(&creator, &group_id, &LeavePolicy::Forfeit);
+    client.join_group_save(&member, &group_id);
+    client.contribute_to_group_save(&member, &group_id, &300);
+
+    assert_eq!(client.leave_group_save(&member, &group_id), 0);
+    assert_eq!(client.get_flexi_balance(&member), 0);
+
+    let group = client.get_group_save(&group_id).unwrap();
+    assert_eq!(group.member_count, 1);
+    assert_eq!(group.current_amount, 300);
+}
+
+#[test]
+fn test_leave_group_rejects_creator_and_non_members() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let outsider = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+
+    let res = client.try_leave_group_save(&creator, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    let res = client.try_leave_group_save(&outsider, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::NotGroupMember));
+
+    let res = client.try_set_group_leave_policy(&outsider, &group_id, &LeavePolicy::Forfeit);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+}
+
+#[test]
+fn test_creator_removes_member_with_refund() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    // Removal refunds even when voluntary leaves forfeit
+    client.set_group_leave_policy(&creator, &group_id, &LeavePolicy::Forfeit);
+    client.join_group_save(&member, &group_id);
+    client.contribute_to_group_save(&member, &group_id, &250);
+
+    assert_eq!(
+        client.remove_group_member(&creator, &group_id, &member),
+        250
+    );
+    assert_eq!(client.get_flexi_balance(&member), 250);
+
+    let group = client.get_group_save(&group_id).unwrap();
+    assert_eq!(group.member_count, 1);
+    assert_eq!(group.current_amount, 0);
+
+    let res = client.try_contribute_to_group_save(&member, &group_id, &100);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::NotGroupMember));
+}
+
+#[test]
+fn test_only_creator_can_remove_members() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member1 = new_user(&env, &client);
+    let member2 = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member1, &group_id);
+    client.join_group_save(&member2, &group_id);
+
+    let res = client.try_remove_group_member(&member1, &group_id, &member2);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+
+    let res = client.try_remove_group_member(&creator, &group_id, &creator);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+}
+
+#[test]
+fn test_distribute_pro_rata_on_completion() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+
+    // Not completed yet
+    client.contribute_to_group_save(&creator, &group_id, &1000);
+    let res = client.try_distribute_group_save(&group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::GroupCycleIncomplete));
+
+    client.contribute_to_group_save(&member, &group_id, &4000);
+    assert_eq!(client.distribute_group_save(&group_id), 5000);
+
+    assert_eq!(client.get_flexi_balance(&creator), 1000);
+    assert_eq!(client.get_flexi_balance(&member), 4000);
+
+    let group = client.get_group_save(&group_id).unwrap();
+    assert_eq!(group.status, GroupStatus::Settled);
+    assert_eq!(group.current_amount, 0);
+
+    // No double distribution, no further contributions
+    let res = client.try_distribute_group_save(&group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+    let res = client.try_contribute_to_group_save(&member, &group_id, &10);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_distribute_rounding_remainder_goes_to_first_member() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member1 = new_user(&env, &client);
+    let member2 = new_user(&env, &client);
+    let leaver = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.set_group_leave_policy(&creator, &group_id, &LeavePolicy::Forfeit);
+    client.join_group_save(&member1, &group_id);
+    client.join_group_save(&member2, &group_id);
+    client.join_group_save(&leaver, &group_id);
+
+    // A forfeited contribution leaves 1 unit that cannot split evenly
+    client.contribute_to_group_save(&leaver, &group_id, &1);
+    client.leave_group_save(&leaver, &group_id);
+    client.contribute_to_group_save(&creator, &group_id, &1666);
+    client.contribute_to_group_save(&member1, &group_id, &1667);
+    client.contribute_to_group_save(&member2, &group_id, &1667);
+
+    assert_eq!(client.distribute_group_save(&group_id), 5001);
+    // member shares: floor(5001 * 1667 / 5000) = 1667
+    assert_eq!(client.get_flexi_balance(&member1), 1667);
+    assert_eq!(client.get_flexi_balance(&member2), 1667);
+    assert_eq!(client.get_flexi_balance(&creator), 1667);
+}
+
+#[test]
+fn test_distribute_to_designated_beneficiary() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let beneficiary = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+
+    let res = client.try_set_group_payout_beneficiary(&member, &group_id, &Some(member.clone()));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+    client.set_group_payout_beneficiary(&creator, &group_id, &Some(beneficiary.clone()));
+
+    client.contribute_to_group_save(&creator, &group_id, &2500);
+    client.contribute_to_group_save(&member, &group_id, &2500);
+    client.distribute_group_save(&group_id);
+
+    assert_eq!(client.get_flexi_balance(&beneficiary), 5000);
+    assert_eq!(client.get_flexi_balance(&creator), 0);
+    assert_eq!(client.get_flexi_balance(&member), 0);
+}
+
+#[test]
+fn test_cancel_group_refunds_exact_contributions() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member1 = new_user(&env, &client);
+    let member2 = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member1, &group_id);
+    client.join_group_save(&member2, &group_id);
+    client.contribute_to_group_save(&creator, &group_id, &100);
+    client.contribute_to_group_save(&member1, &group_id, &700);
+    client.contribute_to_group_save(&member2, &group_id, &333);
+
+    assert_eq!(client.cancel_group_save(&creator, &group_id), 1133);
+    assert_eq!(client.get_flexi_balance(&creator), 100);
+    assert_eq!(client.get_flexi_balance(&member1), 700);
+    assert_eq!(client.get_flexi_balance(&member2), 333);
+
+    let group = client.get_group_save(&group_id).unwrap();
+    assert_eq!(group.status, GroupStatus::Cancelled);
+    assert_eq!(group.current_amount, 0);
+
+    let late = new_user(&env, &client);
+    let res = client.try_join_group_save(&late, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+    let res = client.try_contribute_to_group_save(&member1, &group_id, &10);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+    let res = client.try_cancel_group_save(&creator, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_cancel_group_rejects_non_creator_and_completed() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+
+    let res = client.try_cancel_group_save(&member, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+
+    client.contribute_to_group_save(&member, &group_id, &5000);
+    let res = client.try_cancel_group_save(&creator, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_rotating_group_pays_each_member_once() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member1 = new_user(&env, &client);
+    let member2 = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member1, &group_id);
+    client.join_group_save(&member2, &group_id);
+    client.start_rotation(&creator, &group_id);
+
+    let order = [creator.clone(), member1.clone(), member2.clone()];
+    for (round, recipient) in order.iter().enumerate() {
+        client.contribute_to_group_save(&creator, &group_id, &100);
+        client.contribute_to_group_save(&member1, &group_id, &100);
+
+        // Round is not complete until everyone has paid
+        let res = client.try_payout_rotation(&group_id);
+        assert_eq!(res.unwrap_err(), Ok(SavingsError::GroupCycleIncomplete));
+
+        client.contribute_to_group_save(&member2, &group_id, &100);
+        assert_eq!(client.payout_rotation(&group_id), 300);
+
+        let state = client.get_group_rotation(&group_id).unwrap();
+        assert_eq!(state.current_round, round as u32 + 1);
+        assert_eq!(state.paid_out.get(round as u32).unwrap(), recipient.clone());
+    }
+
+    assert_eq!(client.get_flexi_balance(&creator), 300);
+    assert_eq!(client.get_flexi_balance(&member1), 300);
+    assert_eq!(client.get_flexi_balance(&member2), 300);
+
+    let group = client.get_group_save(&group_id).unwrap();
+    assert_eq!(group.status, GroupStatus::Settled);
+    let res = client.try_payout_rotation(&group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_rotating_group_enforces_round_rules() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let late = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+    client.start_rotation(&creator, &group_id);
+
+    // Fixed amount, once per round
+    let res = client.try_contribute_to_group_save(&member, &group_id, &50);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidAmount));
+    client.contribute_to_group_save(&member, &group_id, &100);
+    let res = client.try_contribute_to_group_save(&member, &group_id, &100);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    // Membership is frozen during the rotation
+    let res = client.try_join_group_save(&late, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+    let res = client.try_leave_group_save(&member, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    // Cancelling refunds the open round only
+    client.cancel_group_save(&creator, &group_id);
+    assert_eq!(client.get_flexi_balance(&member), 100);
+}
+
+#[test]
+fn test_expired_group_rejects_contributions_and_refunds() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+    client.contribute_to_group_save(&member, &group_id, &400);
+
+    let res = client.try_finalize_expired_group(&group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooEarly));
+
+    // Deadline (end_time = 500) passes
+    env.ledger().with_mut(|li| li.timestamp = 501);
+    let res = client.try_contribute_to_group_save(&member, &group_id, &100);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooLate));
+
+    assert_eq!(client.finalize_expired_group(&group_id), 400);
+    assert_eq!(client.get_flexi_balance(&member), 400);
+    let group = client.get_group_save(&group_id).unwrap();
+    assert_eq!(group.status, GroupStatus::Cancelled);
+
+    let res = client.try_finalize_expired_group(&group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_expired_group_distribute_policy() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let beneficiary = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+    client.set_group_expiry_policy(&creator, &group_id, &ExpiryPolicy::Distribute);
+    client.set_group_payout_beneficiary(&creator, &group_id, &Some(beneficiary.clone()));
+    client.contribute_to_group_save(&creator, &group_id, &300);
+    client.contribute_to_group_save(&member, &group_id, &200);
+
+    env.ledger().with_mut(|li| li.timestamp = 501);
+    assert_eq!(client.finalize_expired_group(&group_id), 500);
+    assert_eq!(client.get_flexi_balance(&beneficiary), 500);
+
+    let group = client.get_group_save(&group_id).unwrap();
+    assert_eq!(group.status, GroupStatus::Settled);
+}
+
+#[test]
+fn test_transfer_group_ownership_two_step() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let other = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+    client.join_group_save(&other, &group_id);
+
+    client.transfer_group_ownership(&creator, &group_id, &member);
+    assert_eq!(
+        client.get_group_pending_owner(&group_id),
+        Some(member.clone())
+    );
+    // Nothing changes until the nominee accepts
+    assert_eq!(client.get_group_save(&group_id).unwrap().creator, creator);
+
+    let res = client.try_accept_group_ownership(&other, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+
+    client.accept_group_ownership(&member, &group_id);
+    assert_eq!(client.get_group_save(&group_id).unwrap().creator, member);
+    assert_eq!(client.get_group_pending_owner(&group_id), None);
+
+    // Creator permissions follow the new owner
+    let res = client.try_remove_group_member(&creator, &group_id, &other);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+    client.remove_group_member(&member, &group_id, &other);
+
+    // The previous creator is now a regular member and may leave
+    client.leave_group_save(&creator, &group_id);
+}
+
+#[test]
+fn test_transfer_group_ownership_requires_member() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let outsider = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+
+    let res = client.try_transfer_group_ownership(&creator, &group_id, &outsider);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::NotGroupMember));
+
+    let res = client.try_transfer_group_ownership(&outsider, &group_id, &creator);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+}
+
+#[test]
+fn test_paginated_group_members() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let group_id = create_group(&env, &client, &creator);
+
+    let mut joined = Vec::new(&env);
+    joined.push_back(creator.clone());
+    for _ in 0..4 {
+        let member = new_user(&env, &client);
+        client.join_group_save(&member, &group_id);
+        joined.push_back(member);
+    }
+
+    let page1 = client.get_group_members(&group_id, &0, &2);
+    let page2 = client.get_group_members(&group_id, &2, &2);
+    let page3 = client.get_group_members(&group_id, &4, &2);
+    assert_eq!(page1.len(), 2);
+    assert_eq!(page2.len(), 2);
+    assert_eq!(page3.len(), 1);
+    assert_eq!(page1.get(0).unwrap(), creator);
+    assert!(client.get_group_members(&group_id, &5, &2).is_empty());
+
+    // Removing a member keeps the list dense
+    let leaver = joined.get(1).unwrap();
+    client.leave_group_save(&leaver, &group_id);
+    let all = client.get_group_members(&group_id, &0, &10);
+    assert_eq!(all.len(), 4);
+    assert!(!all.contains(&leaver));
+    for member in joined.iter().filter(|m| *m != leaver) {
+        assert!(all.contains(&member));
+    }
+}
+
+#[test]
+fn test_get_user_groups_tracks_membership() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+
+    let group1 = create_group(&env, &client, &creator);
+    let group2 = create_group(&env, &client, &creator);
+    let group3 = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group1);
+    client.join_group_save(&member, &group2);
+    client.join_group_save(&member, &group3);
+
+    let first = client.get_user_groups(&member, &0, &2);
+    assert_eq!(first.len(), 2);
+    assert_eq!(first.get(0).unwrap(), group1);
+    assert_eq!(first.get(1).unwrap(), group2);
+    let rest = client.get_user_groups(&member, &2, &2);
+    assert_eq!(rest.len(), 1);
+    assert!(client.get_user_groups(&member, &9, &2).is_empty());
+
+    // Leaving and being removed both drop the group from the index
+    client.leave_group_save(&member, &group1);
+    client.remove_group_member(&creator, &group3, &member);
+    let remaining = client.get_user_groups(&member, &0, &10);
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining.get(0).unwrap(), group2);
+}
+
+#[test]
+fn test_list_public_groups() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+
+    let open = create_group(&env, &client, &creator);
+    let cancelled = create_group(&env, &client, &creator);
+    let private = client.create_group_save(
+        &creator,
+        &String::from_str(&env, "Private Group"),
+        &String::from_str(&env, "Description"),
+        &String::from_str(&env, "savings"),
+        &5000,
+        &0,
+        &100,
+        &false,
+        &1,
+        &500,
+    );
+
+    let listed = client.list_public_groups(&0, &10, &false);
+    assert_eq!(listed.len(), 2);
+
+    // Cancelled groups drop out of the index
+    client.cancel_group_save(&creator, &cancelled);
+    let listed = client.list_public_groups(&0, &10, &false);
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed.get(0).unwrap().id, open);
+    assert!(listed.iter().all(|g| g.id != private));
+
+    // Expired groups are only hidden when asking for open groups
+    env.ledger().with_mut(|li| li.timestamp = 501);
+    assert_eq!(client.list_public_groups(&0, &10, &false).len(), 1);
+    assert!(client.list_public_groups(&0, &10, &true).is_empty());
+}
+
+#[test]
+fn test_group_contribution_limits() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+
+    let res = client.try_set_group_contribution_limits(&member, &group_id, &Some(50), &None);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+    let res = client.try_set_group_contribution_limits(&creator, &group_id, &Some(500), &Some(100));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    client.set_group_contribution_limits(&creator, &group_id, &Some(50), &Some(1000));
+
+    let res = client.try_contribute_to_group_save(&member, &group_id, &49);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::ContributionTooSmall));
+
+    client.contribute_to_group_save(&member, &group_id, &600);
+    let res = client.try_contribute_to_group_save(&member, &group_id, &401);
+    assert_eq!(
+        res.unwrap_err(),
+        Ok(SavingsError::ContributionLimitExceeded)
+    );
+    client.contribute_to_group_save(&member, &group_id, &400);
+}
+
+#[test]
+fn test_group_contribution_history() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+
+    env.ledger().with_mut(|li| li.timestamp = 10);
+    client.contribute_to_group_save(&creator, &group_id, &100);
+    env.ledger().with_mut(|li| li.timestamp = 20);
+    client.contribute_to_group_save(&member, &group_id, &250);
+    client.contribute_to_group_save(&creator, &group_id, &50);
+
+    let history = client.get_group_history(&group_id, &0, &10);
+    assert_eq!(history.len(), 3);
+    let second = history.get(1).unwrap();
+    assert_eq!(second.contributor, member);
+    assert_eq!(second.amount, 250);
+    assert_eq!(second.timestamp, 20);
+    assert_eq!(second.running_total, 350);
+
+    let page = client.get_group_history(&group_id, &2, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().running_total, 400);
+}
+
+#[test]
+fn test_group_interest_split_pro_rata_on_distribution() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let year: u64 = 365 * 24 * 60 * 60;
+
+    // 10% a year
+    client.set_group_rate(&1000);
+
+    let group_id = client.create_group_save(
+        &creator,
+        &String::from_str(&env, "Interest Group"),
+        &String::from_str(&env, "Description"),
+        &String::from_str(&env, "savings"),
+        &5000,
+        &0,
+        &100,
+        &true,
+        &0,
+        &(2 * year),
+    );
+    client.join_group_save(&member, &group_id);
+
+    client.contribute_to_group_save(&creator, &group_id, &3000);
+    client.contribute_to_group_save(&member, &group_id, &1000);
+
+    // A year on 4000 earns 400
+    env.ledger().with_mut(|li| li.timestamp = year);
+    client.contribute_to_group_save(&member, &group_id, &1000);
+    assert_eq!(
+        client.get_group_save(&group_id).unwrap().accrued_interest,
+        400
+    );
+
+    client.distribute_group_save(&group_id);
+    assert_eq!(client.get_flexi_balance(&creator), 3000 + 240);
+    assert_eq!(client.get_flexi_balance(&member), 2000 + 160);
+}
+
+#[test]
+fn test_member_cycle_status_tracks_missed_payments() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let week: u64 = 7 * 24 * 60 * 60;
+
+    let group_id = client.create_group_save(
+        &creator,
+        &String::from_str(&env, "Weekly Circle"),
+        &String::from_str(&env, "Description"),
+        &String::from_str(&env, "savings"),
+        &100_000,
+        &0,
+        &100,
+        &true,
+        &0,
+        &(10 * week),
+    );
+    client.set_group_cycle_duration(&creator, &group_id, &week);
+
+    // Cycle 0: creator pays in two parts
+    client.contribute_to_group_save(&creator, &group_id, &60);
+    client.contribute_to_group_save(&creator, &group_id, &40);
+
+    // Cycle 1: member joins and pays; creator skips
+    env.ledger().with_mut(|li| li.timestamp = week + 1);
+    client.join_group_save(&member, &group_id);
+    client.contribute_to_group_save(&member, &group_id, &100);
+
+    // Cycle 2: nobody has paid yet
+    env.ledger().with_mut(|li| li.timestamp = 2 * week + 1);
+    let creator_status = client.get_member_cycle_status(&group_id, &creator);
+    assert_eq!(creator_status.current_cycle, 2);
+    assert_eq!(creator_status.paid_cycles, 1);
+    assert_eq!(creator_status.missed_cycles, 1);
+    assert!(!creator_status.paid_current);
+
+    let member_status = client.get_member_cycle_status(&group_id, &member);
+    assert_eq!(member_status.paid_cycles, 1);
+    assert_eq!(member_status.missed_cycles, 0);
+
+    client.contribute_to_group_save(&member, &group_id, &100);
+    assert!(
+        client
+            .get_member_cycle_status(&group_id, &member)
+            .paid_current
+    );
+}
+
+#[test]
+fn test_merge_groups_folds_members_and_funds() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let shared = new_user(&env, &client);
+    let newcomer = new_user(&env, &client);
+
+    let source = create_group(&env, &client, &creator);
+    let target = create_group(&env, &client, &creator);
+    client.join_group_save(&shared, &source);
+    client.join_group_save(&shared, &target);
+    client.join_group_save(&newcomer, &source);
+
+    client.contribute_to_group_save(&shared, &source, &300);
+    client.contribute_to_group_save(&newcomer, &source, &200);
+    client.contribute_to_group_save(&shared, &target, &100);
+
+    assert_eq!(client.merge_groups(&creator, &source, &target), 500);
+
+    let merged = client.get_group_save(&target).unwrap();
+    assert_eq!(merged.current_amount, 600);
+    // Creator and shared member are counted once
+    assert_eq!(merged.member_count, 3);
+    assert_eq!(client.get_group_members(&target, &0, &10).len(), 3);
+
+    let old = client.get_group_save(&source).unwrap();
+    assert_eq!(old.status, GroupStatus::Merged);
+    assert_eq!(old.member_count, 0);
+    assert_eq!(old.current_amount, 0);
+
+    let newcomer_groups = client.get_user_groups(&newcomer, &0, &10);
+    assert_eq!(newcomer_groups.len(), 1);
+    assert_eq!(newcomer_groups.get(0).unwrap(), target);
+
+    // Exact refunds on the target include the merged contributions
+    client.cancel_group_save(&creator, &target);
+    assert_eq!(client.get_flexi_balance(&shared), 400);
+    assert_eq!(client.get_flexi_balance(&newcomer), 200);
+}
+
+#[test]
+fn test_merge_groups_validation() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let other = new_user(&env, &client);
+
+    let source = create_group(&env, &client, &creator);
+    let target = create_group(&env, &client, &creator);
+    let foreign = create_group(&env, &client, &other);
+
+    let res = client.try_merge_groups(&creator, &source, &foreign);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+    let res = client.try_merge_groups(&creator, &source, &source);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    client.cancel_group_save(&creator, &target);
+    let res = client.try_merge_groups(&creator, &source, &target);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_waitlist_promotes_on_leave() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let first = new_user(&env, &client);
+    let second = new_user(&env, &client);
+
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+
+    // Waitlists only open once the group is full
+    let res = client.try_join_group_waitlist(&first, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    let proposal_id = client.propose_group_change(&creator, &group_id, &GroupChange::MaxMembers(2));
+    client.vote_group_change(&member, &proposal_id);
+
+    assert_eq!(client.join_group_waitlist(&first, &group_id), 0);
+    assert_eq!(client.join_group_waitlist(&second, &group_id), 1);
+    let res = client.try_join_group_waitlist(&first, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    client.leave_group_save(&member, &group_id);
+
+    let members = client.get_group_members(&group_id, &0, &10);
+    assert_eq!(members.len(), 2);
+    assert!(members.contains(&first));
+    let waitlist = client.get_group_waitlist(&group_id);
+    assert_eq!(waitlist.len(), 1);
+    assert_eq!(waitlist.get(0).unwrap(), second);
+
+    // The promoted member can contribute right away
+    client.contribute_to_group_save(&first, &group_id, &100);
+}
+
+#[test]
+fn test_extend_completed_group_target() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+
+    // Only completed groups can be extended
+    let res = client.try_extend_group_target(&creator, &group_id, &8000);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    client.contribute_to_group_save(&member, &group_id, &5000);
+    assert!(client.get_group_save(&group_id).unwrap().is_completed);
+    assert!(client.list_public_groups(&0, &10, &false).is_empty());
+
+    let res = client.try_extend_group_target(&creator, &group_id, &5000);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidAmount));
+    let res = client.try_extend_group_target(&member, &group_id, &8000);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::Unauthorized));
+
+    client.extend_group_target(&creator, &group_id, &8000);
+    let group = client.get_group_save(&group_id).unwrap();
+    assert!(!group.is_completed);
+    assert_eq!(group.target_amount, 8000);
+    assert_eq!(client.list_public_groups(&0, &10, &false).len(), 1);
+
+    client.contribute_to_group_save(&creator, &group_id, &3000);
+    assert!(client.get_group_save(&group_id).unwrap().is_completed);
+
+    // Settled groups stay closed
+    client.distribute_group_save(&group_id);
+    let res = client.try_extend_group_target(&creator, &group_id, &9000);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_contribute_for_credits_beneficiary() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let friend = new_user(&env, &client);
+    let outsider = new_user(&env, &client);
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+
+    // The payer does not need to be a member
+    client.contribute_for(&friend, &group_id, &member, &300);
+    assert_eq!(
+        client.get_group_save(&group_id).unwrap().current_amount,
+        300
+    );
+
+    let res = client.try_contribute_for(&friend, &group_id, &outsider, &300);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::NotGroupMember));
+
+    // The contribution is refunded to the beneficiary, not the payer
+    client.cancel_group_save(&creator, &group_id);
+    assert_eq!(client.get_flexi_balance(&member), 300);
+    assert_eq!(client.get_flexi_balance(&friend), 0);
+}
+
+#[test]
+fn test_group_contributions_collect_savings_tokens_from_the_payer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(issuer).address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+
+    let creator = new_user(&env, &client);
+    let friend = new_user(&env, &client);
+    let minter = StellarAssetClient::new(&env, &token);
+    minter.mint(&creator, &1_000);
+    minter.mint(&friend, &1_000);
+    let group_id = create_group(&env, &client, &creator);
+
+    client.contribute_to_group_save(&creator, &group_id, &400);
+    client.contribute_for(&friend, &group_id, &creator, &250);
+
+    let balances = TokenClient::new(&env, &token);
+    assert_eq!(balances.balance(&creator), 600);
+    assert_eq!(balances.balance(&friend), 750);
+    assert_eq!(balances.balance(&contract_id), 650);
+    assert_eq!(
+        client.get_group_save(&group_id).unwrap().current_amount,
+        650
+    );
+}
+
+#[test]
+fn test_group_creation_fee_routed_to_treasury() {
+    let (env, client, admin) = setup();
+    let creator = new_user(&env, &client);
+    let treasury = Address::generate(&env);
+
+    // 1% of the 5000 target
+    client.set_group_creation_fee(&GroupCreationFee::Bps(100));
+    client.set_fee_recipient(&treasury);
+    let config = client.get_fee_config();
+    assert_eq!(config.group_creation_fee, GroupCreationFee::Bps(100));
+    assert_eq!(config.fee_recipient, Some(treasury.clone()));
+
+    let res = client.try_create_group_save(
+        &creator,
+        &String::from_str(&env, "Test Group"),
+        &String::from_str(&env, "Description"),
+        &String::from_str(&env, "savings"),
+        &5000,
+        &0,
+        &100,
+        &true,
+        &1,
+        &500,
+    );
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientBalance));
+
+    client.deposit_flexi(&creator, &80);
+    create_group(&env, &client, &creator);
+    assert_eq!(client.get_flexi_balance(&creator), 30);
+    assert_eq!(client.get_treasury_balance(), 50);
+    assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+
+    // Group creation fees are withdrawn with the rest of the treasury
+    client.withdraw_treasury(&admin, &treasury, &50);
+    assert_eq!(client.get_treasury_balance(), 0);
+
+    let res = client.try_set_group_creation_fee(&GroupCreationFee::Flat(0));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidAmount));
+}
+
+#[test]
+fn test_late_penalties_deducted_at_distribution() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let week: u64 = 7 * 24 * 60 * 60;
+
+    let group_id = client.create_group_save(
+        &creator,
+        &String::from_str(&env, "Weekly Circle"),
+        &String::from_str(&env, "Description"),
+        &String::from_str(&env, "savings"),
+        &1000,
+        &0,
+        &100,
+        &true,
+        &0,
+        &(10 * week),
+    );
+    client.join_group_save(&member, &group_id);
+    client.set_group_cycle_duration(&creator, &group_id, &week);
+    // 200 due per cycle, 50% penalty per missed cycle
+    client.set_group_cycle_requirements(&creator, &group_id, &Some(200), &5000);
+
+    // Cycle 0: both pay
+    client.contribute_to_group_save(&creator, &group_id, &200);
+    client.contribute_to_group_save(&member, &group_id, &200);
+
+    // Cycle 1: only the creator pays
+    env.ledger().with_mut(|li| li.timestamp = week);
+    client.contribute_to_group_save(&creator, &group_id, &200);
+
+    // Cycle 2: both pay and the target is reached
+    env.ledger().with_mut(|li| li.timestamp = 2 * week);
+    client.contribute_to_group_save(&creator, &group_id, &200);
+    let status = client.get_member_cycle_status(&group_id, &member);
+    assert_eq!(status.missed_cycles, 1);
+    assert_eq!(status.late_penalty, 100);
+    client.contribute_to_group_save(&member, &group_id, &200);
+
+    // Shares of 600/400 move 100 from the late member to the on-time one
+    client.distribute_group_save(&group_id);
+    assert_eq!(client.get_flexi_balance(&creator), 700);
+    assert_eq!(client.get_flexi_balance(&member), 300);
+}
+
+#[test]
+fn test_admin_force_dissolves_group() {
+    let (env, client, admin) = setup();
+    let creator = new_user(&env, &client);
+    let member = new_user(&env, &client);
+    let group_id = create_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+    client.contribute_to_group_save(&member, &group_id, &5000);
+
+    // Works on completed groups and while paused
+    client.pause(&admin);
+    assert_eq!(client.force_dissolve_group(&group_id), 5000);
+    client.unpause(&admin);
+
+    assert_eq!(client.get_flexi_balance(&member), 5000);
+    let group = client.get_group_save(&group_id).unwrap();
+    assert_eq!(group.status, GroupStatus::Cancelled);
+    assert_eq!(group.current_amount, 0);
+
+    let res = client.try_force_dissolve_group(&group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_group_invites_expire_and_can_be_revoked() {
+    let (env, client, _admin) = setup();
+    let creator = new_user(&env, &client);
+    let invitee = new_user(&env, &client);
+    let late = new_user(&env, &client);
+    let group_id = client.create_group_save(
+        &creator,
+        &String::from_str(&env, "Private Group"),
+        &String::from_str(&env, "Description"),
+        &String::from_str(&env, "savings"),
+        &5000,
+        &0,
+        &100,
+        &false,
+        &1,
+        &500,
+    );
+
+    // Without an invite there is nothing to accept
+    let res = client.try_accept_group_invite(&invitee, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanNotFound));
+
+    assert_eq!(
+        client.invite_to_group(&creator, &group_id, &invitee, &100),
+        100
+    );
+    client.accept_group_invite(&invitee, &group_id);
+    assert_eq!(client.get_group_save(&group_id).unwrap().member_count, 2);
+    assert!(client.get_group_invite(&group_id, &invitee).is_none());
+
+    // Expired invites are rejected
+    client.invite_to_group(&creator, &group_id, &late, &100);
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    let res = client.try_accept_group_invite(&late, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooLate));
+
+    // Revoked invites cannot be accepted
+    client.invite_to_group(&creator, &group_id, &late, &100);
+    client.revoke_invite(&creator, &group_id, &late);
+    let res = client.try_accept_group_invite(&late, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanNotFound));
+    let res = client.try_revoke_invite(&creator, &group_id, &late);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanNotFound));
+}
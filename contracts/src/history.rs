@@ -0,0 +1,77 @@
+use crate::group::MAX_PAGE_SIZE;
+use crate::storage_types::{DataKey, GroupContributionRecord};
+use soroban_sdk::{Address, Env, Vec};
+
+/// Number of contributions kept per group; older entries are overwritten
+pub const GROUP_HISTORY_CAPACITY: u32 = 200;
+
+/// Appends a contribution to the group's log.
+///
+/// The log is a ring buffer of `GROUP_HISTORY_CAPACITY` slots, so storage per
+/// group stays bounded and the oldest record is overwritten once it is full.
+pub(crate) fn record_contribution(
+    env: &Env,
+    group_id: u64,
+    contributor: &Address,
+    amount: i128,
+    running_total: i128,
+) {
+    let count = get_history_count(env, group_id);
+    let record = GroupContributionRecord {
+        contributor: contributor.clone(),
+        amount,
+        timestamp: env.ledger().timestamp(),
+        running_total,
+    };
+
+    let slot = (count % GROUP_HISTORY_CAPACITY as u64) as u32;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupHistory(group_id, slot), &record);
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupHistoryCount(group_id), &(count + 1));
+}
+
+/// Returns a page of a group's retained contribution log, oldest first.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `group_id` - The group ID
+/// * `start` - Position in the retained log (0 = oldest retained record)
+/// * `limit` - Maximum number of records to return (capped at `MAX_PAGE_SIZE`)
+pub fn get_group_history(
+    env: &Env,
+    group_id: u64,
+    start: u32,
+    limit: u32,
+) -> Vec<GroupContributionRecord> {
+    let count = get_history_count(env, group_id);
+    let retained = count.min(GROUP_HISTORY_CAPACITY as u64);
+    let oldest = count - retained;
+
+    let start = (start as u64).min(retained);
+    let end = start
+        .saturating_add(limit.min(MAX_PAGE_SIZE) as u64)
+        .min(retained);
+
+    let mut records = Vec::new(env);
+    for position in start..end {
+        let slot = ((oldest + position) % GROUP_HISTORY_CAPACITY as u64) as u32;
+        if let Some(record) = env
+            .storage()
+            .persistent()
+            .get(&DataKey::GroupHistory(group_id, slot))
+        {
+            records.push_back(record);
+        }
+    }
+    records
+}
+
+fn get_history_count(env: &Env, group_id: u64) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GroupHistoryCount(group_id))
+        .unwrap_or(0)
+}
@@ -0,0 +1,655 @@
+//! Lazy interest accrual for Flexi and Goal savings plans.
+//!
+//! Rather than updating balances every ledger, a plan's interest is settled
+//! whenever the plan is loaded for a deposit, withdrawal or other change. The
+//! interest earned since the last settlement is the plan balance times the
+//! growth of its rate index (rate in basis points multiplied by elapsed
+//! seconds) over that period.
+//!
+//! Plans with a streak of weekly contributions earn a rate boost from the
+//! admin's streak boost tiers for as long as the streak lasts.
+//!
+//! Settled interest is kept apart from the plan's principal until the user
+//! claims it with `claim_interest`. Unclaimed interest keeps earning, but is
+//! not part of `User.total_balance` until claimed.
+//!
+//! The admin may have a plan type's interest compound daily, weekly or
+//! monthly. Compounding uses fixed-point growth factors scaled by `SCALE`;
+//! every multiplication and division rounds down, so rounding never pays out
+//! more than was earned.
+//!
+//! Lock plans are not accrued here: their interest is paid in full when they
+//! are settled at maturity.
+
+use crate::assets;
+use crate::ensure_not_paused;
+use crate::errors::SavingsError;
+use crate::fees;
+use crate::plans;
+use crate::storage_types::{
+    CompoundingFrequency, ContributionStreak, DataKey, DataKeyExt, PlanAccrual, PlanKind, PlanType,
+    RateChange, RewardsToken, SavingsPlan, StreakBoostTier,
+};
+use soroban_sdk::{symbol_short, token::TokenClient, Address, Env, Vec};
+
+const DAY_SECONDS: u64 = 24 * 60 * 60;
+const YEAR_SECONDS: i128 = 365 * DAY_SECONDS as i128;
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Length of a contribution streak period
+pub const STREAK_PERIOD: u64 = 7 * DAY_SECONDS;
+
+/// Fixed-point scale of growth factors: `SCALE` represents 1.0
+pub const SCALE: i128 = 1_000_000_000_000;
+
+/// Settles the interest `plan` has earned since it was last accrued into the
+/// plan's unclaimed interest.
+///
+/// # Returns
+/// `Ok(PlanAccrual)` - The plan's updated accrual record
+pub fn accrue(env: &Env, user: &Address, plan: &SavingsPlan) -> Result<PlanAccrual, SavingsError> {
+    let mut accrual = get_accrual(env, user, plan);
+    if !accrues(plan) {
+        return Ok(accrual);
+    }
+
+    let interest = pending_interest(env, user, plan)?;
+    accrual.updated_at = env.ledger().timestamp();
+    accrual.unclaimed = accrual
+        .unclaimed
+        .checked_add(interest)
+        .ok_or(SavingsError::Overflow)?;
+    accrual.total_accrued = accrual
+        .total_accrued
+        .checked_add(interest)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage().persistent().set(
+        &DataKeyExt::PlanAccrual(user.clone(), plan.plan_id),
+        &accrual,
+    );
+
+    Ok(accrual)
+}
+
+/// Pays out a plan's earned interest, leaving its principal untouched. The
+/// performance fee for the plan's kind is taken out of the interest first.
+///
+/// The interest is credited to the user's Flexi balance, or with `restake`
+/// added to the plan's principal. Interest left on a closed plan can still be
+/// claimed, but only to Flexi.
+///
+/// # Returns
+/// `Ok(i128)` - The interest claimed
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `PlanCompleted` - If restaking into a closed plan
+pub fn claim_interest(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    restake: bool,
+) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    let plan_key = DataKey::SavingsPlan(user.clone(), plan_id);
+    let mut plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&plan_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+    let mut accrual = accrue(env, &user, &plan)?;
+    if accrual.unclaimed == 0 {
+        return Ok(0);
+    }
+    let asset = assets::get_plan_asset(env, &user, plan_id);
+    let fee = fees::collect_performance_fee(
+        env,
+        &user,
+        plan.plan_type.kind(),
+        &asset,
+        accrual.unclaimed,
+    )?;
+    let interest = accrual.unclaimed - fee;
+
+    if restake {
+        if plan.is_withdrawn {
+            return Err(SavingsError::PlanCompleted);
+        }
+        plan.balance = plan
+            .balance
+            .checked_add(interest)
+            .ok_or(SavingsError::Overflow)?;
+        if let PlanType::Goal(_, target, _) = plan.plan_type {
+            plan.is_completed = plan.balance >= target;
+        }
+        env.storage().persistent().set(&plan_key, &plan);
+    } else {
+        assets::credit(env, &user, &asset, interest)?;
+    }
+
+    accrual.unclaimed = 0;
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::PlanAccrual(user.clone(), plan_id), &accrual);
+
+    env.events().publish(
+        (symbol_short!("int_clm"), user, plan_id),
+        (interest, restake),
+    );
+
+    Ok(interest)
+}
+
+/// Sets the token that interest can be claimed in instead of the savings
+/// asset, and how many token units one unit of interest is worth, scaled by
+/// `SCALE`.
+///
+/// The contract pays rewards from its own balance of the token.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `exchange_rate` is not positive
+pub fn set_rewards_token(
+    env: &Env,
+    token: Address,
+    exchange_rate: i128,
+) -> Result<(), SavingsError> {
+    if exchange_rate <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage().instance().set(
+        &DataKeyExt::RewardsToken,
+        &RewardsToken {
+            token: token.clone(),
+            exchange_rate,
+        },
+    );
+    env.events()
+        .publish((symbol_short!("set_rwd"), token), exchange_rate);
+    Ok(())
+}
+
+/// Returns the rewards token settings, if configured.
+pub fn get_rewards_token(env: &Env) -> Option<RewardsToken> {
+    env.storage().instance().get(&DataKeyExt::RewardsToken)
+}
+
+/// Pays out a plan's earned interest in the rewards token, converted at the
+/// configured exchange rate and rounded down. The interest is used up in
+/// full and the savings balances are unchanged.
+///
+/// # Returns
+/// `Ok(i128)` - The amount of rewards token paid
+///
+/// # Errors
+/// * `MissingParameter` - If no rewards token is configured
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InsufficientBalance` - If the contract holds too little of the token
+pub fn claim_interest_as_rewards(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    let rewards = get_rewards_token(env).ok_or(SavingsError::MissingParameter)?;
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    let mut accrual = accrue(env, &user, &plan)?;
+    let fee = fees::collect_performance_fee(
+        env,
+        &user,
+        plan.plan_type.kind(),
+        &assets::get_plan_asset(env, &user, plan_id),
+        accrual.unclaimed,
+    )?;
+    let payout = mul_scaled(accrual.unclaimed - fee, rewards.exchange_rate)?;
+    if payout == 0 {
+        return Ok(0);
+    }
+
+    let token = TokenClient::new(env, &rewards.token);
+    let contract = env.current_contract_address();
+    if token.balance(&contract) < payout {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    let interest = accrual.unclaimed;
+    accrual.unclaimed = 0;
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::PlanAccrual(user.clone(), plan_id), &accrual);
+    token.transfer(&contract, &user, &payout);
+
+    env.events().publish(
+        (symbol_short!("int_rwd"), user, plan_id),
+        (interest, payout),
+    );
+
+    Ok(payout)
+}
+
+/// Interest `plan` has earned since it was last accrued, without settling it.
+///
+/// Both the principal and the unclaimed interest earn.
+pub fn pending_interest(
+    env: &Env,
+    user: &Address,
+    plan: &SavingsPlan,
+) -> Result<i128, SavingsError> {
+    interest_until(env, user, plan, env.ledger().timestamp())
+}
+
+/// Projects what a user's savings plan will be worth at `at`, including
+/// interest not yet earned, assuming no further deposits or withdrawals.
+///
+/// Flexi and Goal plans include unclaimed interest and follow the scheduled
+/// rate changes and compounding of the accrual engine; lock plans follow
+/// their settlement at maturity, including the matured lock policy.
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is a group plan
+/// * `InvalidTimestamp` - If `at` is in the past
+pub fn project_balance(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    at: u64,
+) -> Result<i128, SavingsError> {
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    if at < env.ledger().timestamp() {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    let interest = match plan.plan_type {
+        PlanType::Group(..) => return Err(SavingsError::InvalidPlanConfig),
+        PlanType::Lock(_) if plan.is_withdrawn => 0,
+        PlanType::Lock(until) => {
+            plans::lock_interest(&plan, until, at, &plans::get_lock_maturity_policy(env))?
+        }
+        PlanType::Flexi | PlanType::Goal(..) => interest_until(env, &user, &plan, at)?
+            .checked_add(get_accrual(env, &user, &plan).unclaimed)
+            .ok_or(SavingsError::Overflow)?,
+    };
+    plan.balance
+        .checked_add(interest)
+        .ok_or(SavingsError::Overflow)
+}
+
+/// Interest `plan` earns from when it was last accrued until `until`.
+fn interest_until(
+    env: &Env,
+    user: &Address,
+    plan: &SavingsPlan,
+    until: u64,
+) -> Result<i128, SavingsError> {
+    let accrual = get_accrual(env, user, plan);
+    let base = plan
+        .balance
+        .checked_add(accrual.unclaimed)
+        .ok_or(SavingsError::Overflow)?;
+    if !accrues(plan) || base <= 0 {
+        return Ok(0);
+    }
+
+    let period = match get_compounding(env, plan.plan_type.kind()) {
+        CompoundingFrequency::None => {
+            return simple_interest(
+                base,
+                rate_index(env, user, plan, accrual.updated_at, until)?,
+            );
+        }
+        CompoundingFrequency::Daily => DAY_SECONDS,
+        CompoundingFrequency::Weekly => 7 * DAY_SECONDS,
+        CompoundingFrequency::Monthly => 30 * DAY_SECONDS,
+    };
+
+    // Each stretch at one rate compounds over its whole periods; what is
+    // left of a period at a rate change or at `until` earns simple interest
+    let mut value = base;
+    for (rate, from, to) in rate_segments(env, user, plan, accrual.updated_at, until).iter() {
+        let elapsed = to - from;
+        let period_rate = (rate as i128)
+            .checked_mul(period as i128)
+            .and_then(|index| index.checked_mul(SCALE))
+            .ok_or(SavingsError::Overflow)?
+            / (BPS_DENOMINATOR * YEAR_SECONDS);
+        value = mul_scaled(value, pow_scaled(SCALE + period_rate, elapsed / period)?)?;
+        let partial = (rate as i128)
+            .checked_mul((elapsed % period) as i128)
+            .ok_or(SavingsError::Overflow)?;
+        value = value
+            .checked_add(simple_interest(value, partial)?)
+            .ok_or(SavingsError::Overflow)?;
+    }
+
+    Ok(value - base)
+}
+
+/// Sets how often interest compounds for Flexi or Goal plans.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If `kind` is not accrued by this module
+pub fn set_compounding(
+    env: &Env,
+    kind: PlanKind,
+    frequency: CompoundingFrequency,
+) -> Result<(), SavingsError> {
+    if !matches!(kind, PlanKind::Flexi | PlanKind::Goal) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::Compounding(kind), &frequency);
+    env.events()
+        .publish((symbol_short!("set_cmpd"), kind), frequency);
+    Ok(())
+}
+
+/// Returns how often interest compounds for a plan type. Interest is simple
+/// unless configured otherwise.
+pub fn get_compounding(env: &Env, kind: PlanKind) -> CompoundingFrequency {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::Compounding(kind))
+        .unwrap_or(CompoundingFrequency::None)
+}
+
+/// Interest a user's savings plan has earned and that can be claimed now,
+/// whether or not it has been settled yet.
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+pub fn get_pending_interest(env: &Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    let unclaimed = get_accrual(env, &user, &plan).unclaimed;
+    pending_interest(env, &user, &plan)?
+        .checked_add(unclaimed)
+        .ok_or(SavingsError::Overflow)
+}
+
+/// Starts a plan's accrual from now, for plans carrying a balance that has
+/// already been accrued elsewhere.
+pub(crate) fn start_accrual(env: &Env, user: &Address, plan_id: u64) {
+    env.storage().persistent().set(
+        &DataKeyExt::PlanAccrual(user.clone(), plan_id),
+        &PlanAccrual {
+            updated_at: env.ledger().timestamp(),
+            unclaimed: 0,
+            total_accrued: 0,
+        },
+    );
+}
+
+/// Returns the accrual record of a plan. Plans that have never been accrued
+/// accrue from their start time.
+pub fn get_accrual(env: &Env, user: &Address, plan: &SavingsPlan) -> PlanAccrual {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::PlanAccrual(user.clone(), plan.plan_id))
+        .unwrap_or(PlanAccrual {
+            updated_at: plan.start_time,
+            unclaimed: 0,
+            total_accrued: 0,
+        })
+}
+
+/// Replaces the contribution streak boost tiers.
+///
+/// Tiers must be ordered by strictly increasing `min_streak`. An empty table
+/// turns streak boosts off.
+///
+/// The caller is responsible for checking admin authorization.
+pub fn set_streak_boost_tiers(env: &Env, tiers: Vec<StreakBoostTier>) -> Result<(), SavingsError> {
+    for i in 1..tiers.len() {
+        let prev = tiers.get(i - 1).unwrap();
+        let tier = tiers.get(i).unwrap();
+        if tier.min_streak <= prev.min_streak {
+            return Err(SavingsError::InvalidInterestRate);
+        }
+    }
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::StreakBoostTiers, &tiers);
+    Ok(())
+}
+
+pub fn get_streak_boost_tiers(env: &Env) -> Vec<StreakBoostTier> {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::StreakBoostTiers)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Counts a deposit towards the plan's contribution streak.
+///
+/// Time since the plan started is split into `STREAK_PERIOD` weeks. A deposit
+/// in the week after the last counted one extends the streak, further
+/// deposits in the same week don't count, and a skipped week restarts it.
+///
+/// Call after the plan's interest has been accrued, so a new boost only
+/// applies from the deposit on.
+pub(crate) fn record_contribution(env: &Env, user: &Address, plan: &SavingsPlan) {
+    if !accrues(plan) {
+        return;
+    }
+    let period = env.ledger().timestamp().saturating_sub(plan.start_time) / STREAK_PERIOD;
+    let key = DataKeyExt::ContributionStreak(user.clone(), plan.plan_id);
+    let mut streak = get_contribution_streak(env, user.clone(), plan.plan_id);
+    if streak.count > 0 && period == streak.last_period {
+        return;
+    }
+    streak.count = if streak.count > 0 && period == streak.last_period + 1 {
+        streak.count + 1
+    } else {
+        1
+    };
+    streak.last_period = period;
+    env.storage().persistent().set(&key, &streak);
+}
+
+/// Returns a plan's contribution streak.
+pub fn get_contribution_streak(env: &Env, user: Address, plan_id: u64) -> ContributionStreak {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::ContributionStreak(user, plan_id))
+        .unwrap_or(ContributionStreak {
+            count: 0,
+            last_period: 0,
+        })
+}
+
+/// The rate boost a plan's streak earns and when it lapses unless the streak
+/// is extended.
+fn streak_boost(env: &Env, user: &Address, plan: &SavingsPlan) -> (u32, u64) {
+    let streak = get_contribution_streak(env, user.clone(), plan.plan_id);
+    let mut boost = 0;
+    for tier in get_streak_boost_tiers(env).iter() {
+        if streak.count < tier.min_streak {
+            break;
+        }
+        boost = tier.boost_bps;
+    }
+    let lapses_at = plan
+        .start_time
+        .saturating_add((streak.last_period + 2).saturating_mul(STREAK_PERIOD));
+    (boost, lapses_at)
+}
+
+/// Schedules a new rate for all Flexi or Goal plans from `effective_at` on.
+/// Until the first scheduled rate takes effect, plans earn the rate stored
+/// with them. A change already scheduled for the same time is replaced.
+///
+/// Interest is accrued per stretch of time at each rate, so a change never
+/// alters interest earned before it takes effect.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If `kind` is not accrued by this module
+/// * `InvalidInterestRate` - If `rate_bps` exceeds 10_000
+/// * `InvalidTimestamp` - If `effective_at` is in the past
+pub fn set_interest_rate(
+    env: &Env,
+    kind: PlanKind,
+    rate_bps: u32,
+    effective_at: u64,
+) -> Result<(), SavingsError> {
+    if !matches!(kind, PlanKind::Flexi | PlanKind::Goal) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if rate_bps > BPS_DENOMINATOR as u32 {
+        return Err(SavingsError::InvalidInterestRate);
+    }
+    if effective_at < env.ledger().timestamp() {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    let mut schedule = get_rate_schedule(env, kind);
+    let change = RateChange {
+        rate_bps,
+        effective_at,
+    };
+    let position = schedule
+        .iter()
+        .position(|existing| existing.effective_at >= effective_at)
+        .map(|i| i as u32);
+    match position {
+        Some(i) if schedule.get(i).unwrap().effective_at == effective_at => schedule.set(i, change),
+        Some(i) => schedule.insert(i, change),
+        None => schedule.push_back(change),
+    }
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::RateSchedule(kind), &schedule);
+
+    env.events()
+        .publish((symbol_short!("set_rate"), kind), (rate_bps, effective_at));
+    Ok(())
+}
+
+/// Returns the scheduled rate changes for a plan type, earliest first.
+pub fn get_rate_schedule(env: &Env, kind: PlanKind) -> Vec<RateChange> {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::RateSchedule(kind))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Splits `from..to` at the plan type's rate changes into
+/// `(rate_bps, from, to)` stretches.
+fn rate_segments(
+    env: &Env,
+    user: &Address,
+    plan: &SavingsPlan,
+    from: u64,
+    to: u64,
+) -> Vec<(u32, u64, u64)> {
+    let mut segments = Vec::new(env);
+    let mut rate = plan.interest_rate;
+    let mut cursor = from;
+    for change in get_rate_schedule(env, plan.plan_type.kind()).iter() {
+        if change.effective_at >= to {
+            break;
+        }
+        if change.effective_at > cursor {
+            segments.push_back((rate, cursor, change.effective_at));
+            cursor = change.effective_at;
+        }
+        rate = change.rate_bps;
+    }
+    if to > cursor {
+        segments.push_back((rate, cursor, to));
+    }
+
+    // A streak boost holds until the streak lapses
+    let (boost, boost_until) = streak_boost(env, user, plan);
+    if boost == 0 {
+        return segments;
+    }
+    let mut boosted = Vec::new(env);
+    for (rate, start, end) in segments.iter() {
+        if end <= boost_until {
+            boosted.push_back((rate + boost, start, end));
+        } else if start >= boost_until {
+            boosted.push_back((rate, start, end));
+        } else {
+            boosted.push_back((rate + boost, start, boost_until));
+            boosted.push_back((rate, boost_until, end));
+        }
+    }
+    boosted
+}
+
+/// Growth of the plan's rate index (basis points × seconds) between `from`
+/// and `to`.
+fn rate_index(
+    env: &Env,
+    user: &Address,
+    plan: &SavingsPlan,
+    from: u64,
+    to: u64,
+) -> Result<i128, SavingsError> {
+    let mut index: i128 = 0;
+    for (rate, start, end) in rate_segments(env, user, plan, from, to).iter() {
+        index = (rate as i128)
+            .checked_mul((end - start) as i128)
+            .and_then(|segment| index.checked_add(segment))
+            .ok_or(SavingsError::Overflow)?;
+    }
+    Ok(index)
+}
+
+/// Simple interest on `balance` for a rate index growth, rounded down.
+fn simple_interest(balance: i128, rate_index: i128) -> Result<i128, SavingsError> {
+    balance
+        .checked_mul(rate_index)
+        .map(|scaled| scaled / (BPS_DENOMINATOR * YEAR_SECONDS))
+        .ok_or(SavingsError::Overflow)
+}
+
+/// Multiplies `value` by the fixed-point factor `factor`, rounding down.
+pub fn mul_scaled(value: i128, factor: i128) -> Result<i128, SavingsError> {
+    value
+        .checked_mul(factor)
+        .map(|product| product / SCALE)
+        .ok_or(SavingsError::Overflow)
+}
+
+/// Raises the fixed-point factor `base` to the power `exp` by squaring,
+/// rounding down after every multiplication.
+pub fn pow_scaled(mut base: i128, mut exp: u64) -> Result<i128, SavingsError> {
+    let mut result = SCALE;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_scaled(result, base)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = mul_scaled(base, base)?;
+        }
+    }
+    Ok(result)
+}
+
+fn accrues(plan: &SavingsPlan) -> bool {
+    !plan.is_withdrawn && matches!(plan.plan_type, PlanType::Flexi | PlanType::Goal(..))
+}
@@ -0,0 +1,252 @@
+use crate::interest;
+use crate::{
+    CompoundingFrequency, NesteraContract, NesteraContractClient, PlanKind, PlanType, SavingsError,
+    StreakBoostTier,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, BytesN, Env,
+};
+
+const YEAR: u64 = 365 * 24 * 60 * 60;
+
+fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &admin_pk, &None);
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+
+    (env, client, user)
+}
+
+#[test]
+fn test_flexi_plan_accrues_interest_lazily() {
+    let (env, client, user) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &10_000);
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR / 2);
+    assert_eq!(client.get_pending_interest(&user, &plan_id), 250);
+
+    // Settling on deposit keeps the interest apart from the principal
+    client.deposit_to_plan(&user, &plan_id, &9_750);
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        19_750
+    );
+    assert_eq!(client.get_pending_interest(&user, &plan_id), 250);
+
+    // Unclaimed interest earns as well
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    assert_eq!(client.get_pending_interest(&user, &plan_id), 750);
+    client.check_balance_invariant(&user);
+}
+
+#[test]
+fn test_claim_interest_to_flexi_or_restake() {
+    let (env, client, user) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &10_000);
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+
+    assert_eq!(client.claim_interest(&user, &plan_id, &false), 500);
+    assert_eq!(client.get_flexi_balance(&user), 500);
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        10_000
+    );
+    assert_eq!(client.claim_interest(&user, &plan_id, &false), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 2 * YEAR);
+    assert_eq!(client.claim_interest(&user, &plan_id, &true), 500);
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        10_500
+    );
+    assert_eq!(client.check_balance_invariant(&user), 11_000);
+
+    // Interest earned before closing can still be claimed to Flexi
+    env.ledger().with_mut(|li| li.timestamp = 3 * YEAR);
+    client.close_plan(&user, &plan_id);
+    let res = client.try_claim_interest(&user, &plan_id, &true);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+    assert_eq!(client.claim_interest(&user, &plan_id, &false), 525);
+}
+
+#[test]
+fn test_performance_fee_is_taken_from_interest_only() {
+    let (env, client, user) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &10_000);
+    let res = client.try_set_performance_fee(&PlanKind::Flexi, &10_001);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidAmount));
+    client.set_performance_fee(&PlanKind::Flexi, &1_000);
+    assert_eq!(client.get_performance_fee(&PlanKind::Lock), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    assert_eq!(client.claim_interest(&user, &plan_id, &false), 450);
+    assert_eq!(client.get_flexi_balance(&user), 450);
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        10_000
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 2 * YEAR);
+    assert_eq!(client.claim_interest(&user, &plan_id, &true), 450);
+    assert_eq!(client.get_performance_fees(), 100);
+    assert_eq!(client.get_treasury_balance(), 100);
+    assert_eq!(client.check_balance_invariant(&user), 10_900);
+}
+
+#[test]
+fn test_pow_scaled_matches_repeated_multiplication() {
+    let base = interest::SCALE + interest::SCALE / 100;
+    let mut expected = interest::SCALE;
+    for _ in 0..13 {
+        expected = interest::mul_scaled(expected, base).unwrap();
+    }
+    // Squaring rounds at different steps, so allow a few units of error
+    let got = interest::pow_scaled(base, 13).unwrap();
+    assert!((got - expected).abs() <= 13);
+    assert_eq!(interest::pow_scaled(base, 0).unwrap(), interest::SCALE);
+    assert_eq!(
+        interest::pow_scaled(i128::MAX / 2, 2),
+        Err(SavingsError::Overflow)
+    );
+}
+
+#[test]
+fn test_monthly_compounding_beats_simple_interest() {
+    let (env, client, user) = setup();
+    let res = client.try_set_compounding(&PlanKind::Lock, &CompoundingFrequency::Daily);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+
+    client.set_compounding(&PlanKind::Flexi, &CompoundingFrequency::Monthly);
+    assert_eq!(
+        client.get_compounding(&PlanKind::Flexi),
+        CompoundingFrequency::Monthly
+    );
+    assert_eq!(
+        client.get_compounding(&PlanKind::Goal),
+        CompoundingFrequency::None
+    );
+
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &1_000_000);
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    // 12 months of 5% compounding plus 5 days of simple interest
+    let pending = client.get_pending_interest(&user, &plan_id);
+    assert!(pending > 50_000 && pending < 51_300);
+
+    assert_eq!(client.claim_interest(&user, &plan_id, &false), pending);
+}
+
+#[test]
+fn test_scheduled_rate_change_only_affects_later_accrual() {
+    let (env, client, user) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &10_000);
+
+    client.set_interest_rate(&PlanKind::Flexi, &1_000, &(YEAR / 2));
+    client.set_interest_rate(&PlanKind::Flexi, &2_000, &YEAR);
+    // Rescheduling the same time replaces the change
+    client.set_interest_rate(&PlanKind::Flexi, &0, &YEAR);
+    assert_eq!(client.get_rate_schedule(&PlanKind::Flexi).len(), 2);
+
+    // 5% for the first half year, then 10%
+    env.ledger().with_mut(|li| li.timestamp = YEAR / 4);
+    assert_eq!(client.claim_interest(&user, &plan_id, &false), 125);
+    env.ledger().with_mut(|li| li.timestamp = 3 * YEAR / 4);
+    assert_eq!(client.get_pending_interest(&user, &plan_id), 375);
+
+    env.ledger().with_mut(|li| li.timestamp = 2 * YEAR);
+    assert_eq!(client.get_pending_interest(&user, &plan_id), 625);
+
+    let res = client.try_set_interest_rate(&PlanKind::Flexi, &100, &YEAR);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidTimestamp));
+    let res = client.try_set_interest_rate(&PlanKind::Goal, &10_001, &(3 * YEAR));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidInterestRate));
+}
+
+#[test]
+fn test_project_balance_uses_engine_math() {
+    let (env, client, user) = setup();
+    let flexi_id = client.create_savings_plan(&user, &PlanType::Flexi, &10_000);
+    let lock_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &10_000);
+    client.set_interest_rate(&PlanKind::Flexi, &1_000, &(YEAR / 2));
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR / 4);
+    client.claim_interest(&user, &flexi_id, &true);
+    let projected = client.project_balance(&user, &flexi_id, &YEAR);
+    assert_eq!(client.project_balance(&user, &lock_id, &YEAR), 10_500);
+    // Past maturity a lock earns nothing more under the default policy
+    assert_eq!(client.project_balance(&user, &lock_id, &(2 * YEAR)), 10_500);
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    let value = client.get_savings_plan(&user, &flexi_id).unwrap().balance
+        + client.get_pending_interest(&user, &flexi_id);
+    assert_eq!(projected, value);
+    assert_eq!(client.withdraw_lock(&user, &lock_id), 10_500);
+
+    let res = client.try_project_balance(&user, &flexi_id, &(YEAR / 2));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidTimestamp));
+}
+
+#[test]
+fn test_contribution_streak_boosts_rate_until_it_lapses() {
+    let (env, client, user) = setup();
+    let week = interest::STREAK_PERIOD;
+    client.set_streak_boost_tiers(&vec![
+        &env,
+        StreakBoostTier {
+            min_streak: 3,
+            boost_bps: 500,
+        },
+    ]);
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &10_000);
+
+    for i in 1..=3 {
+        env.ledger().with_mut(|li| li.timestamp = i * week);
+        client.deposit_to_plan(&user, &plan_id, &1);
+    }
+    // A second deposit in the same week doesn't count
+    client.deposit_to_plan(&user, &plan_id, &1);
+    assert_eq!(client.get_contribution_streak(&user, &plan_id).count, 3);
+    client.claim_interest(&user, &plan_id, &false);
+
+    // 10% while the streak holds (through week 4), then back to 5%
+    env.ledger().with_mut(|li| li.timestamp = 5 * week);
+    assert_eq!(client.get_pending_interest(&user, &plan_id), 38);
+    env.ledger().with_mut(|li| li.timestamp = 6 * week);
+    assert_eq!(client.get_pending_interest(&user, &plan_id), 47);
+
+    // Skipping a week restarts the streak
+    client.deposit_to_plan(&user, &plan_id, &1);
+    assert_eq!(client.get_contribution_streak(&user, &plan_id).count, 1);
+}
+
+#[test]
+fn test_claim_interest_as_rewards_token() {
+    let (env, client, user) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &10_000);
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+
+    let res = client.try_claim_interest_as_rewards(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::MissingParameter));
+
+    // One unit of interest is worth 2.5 reward tokens
+    let issuer = Address::generate(&env);
+    let rewards = env.register_stellar_asset_contract_v2(issuer).address();
+    client.set_rewards_token(&rewards, &(5 * interest::SCALE / 2));
+    let res = client.try_claim_interest_as_rewards(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientBalance));
+
+    StellarAssetClient::new(&env, &rewards).mint(&client.address, &5_000);
+    assert_eq!(client.claim_interest_as_rewards(&user, &plan_id), 1_250);
+    assert_eq!(TokenClient::new(&env, &rewards).balance(&user), 1_250);
+    assert_eq!(client.get_pending_interest(&user, &plan_id), 0);
+    assert_eq!(client.get_flexi_balance(&user), 0);
+}
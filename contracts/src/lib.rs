@@ -1,26 +1,56 @@
 #![no_std]
 #![allow(non_snake_case)]
 use soroban_sdk::{
-    contract, contractimpl, panic_with_error, symbol_short, xdr::ToXdr, Address, Bytes, BytesN,
-    Env, String, Symbol, Vec,
+    contract, contractimpl, panic_with_error, symbol_short, Address, Bytes, BytesN, Env, Map,
+    String, Symbol, Vec,
 };
 
+mod approvals;
+mod assets;
 mod autosave;
+mod config;
+mod cycles;
 mod errors;
+mod fees;
 mod flexi;
 mod goal;
 mod group;
+mod history;
+mod interest;
 mod lock;
+mod mint;
+mod normalize;
+mod oracle;
+mod penalty_pool;
+mod plans;
 mod storage_types;
 mod users;
 
 mod rates;
+mod rotation;
+mod strategies;
+mod swap;
+mod timelock;
+mod upgrade;
 mod views;
+mod voting;
+mod withdrawal_queue;
 
 pub use crate::errors::SavingsError;
 pub use crate::storage_types::{
-    AutoSave, DataKey, GoalSave, GoalSaveView, GroupSave, GroupSaveView, LockSave, LockSaveView,
-    MintPayload, PlanType, SavingsPlan, User,
+    AccountFreeze, AutoSave, BurnPayload, CompoundingFrequency, ContributionStreak, ControlDataKey,
+    DataKey, DataKeyExt, ExpiryPolicy, FeeConfig, FeeStats, FeeTier, FlexiBucket, GoalBonusConfig,
+    GoalCategory, GoalMilestone, GoalProgress, GoalSave, GoalSaveView, GroupAction,
+    GroupApprovalPolicy, GroupChange, GroupChangeProposal, GroupContributionRecord,
+    GroupCreationFee, GroupInvite, GroupMode, GroupProposal, GroupSave, GroupSaveView, GroupStatus,
+    GroupWithdrawalRequest, IdleFundsPolicy, LeavePolicy, LockMaturityPolicy, LockRateTier,
+    LockSave, LockSaveView, MemberCycleStatus, MintDataKey, MintLimits, MintOutcome, MintPayload,
+    MintWindow, Minter, OracleAsset, OracleConfig, OracleFallback, PauseScope, PenaltyPool,
+    PenaltyPoolConfig, PenaltyRouting, PenaltyShare, PlanAutoSave, PlanFreeze, PlanKind, PlanType,
+    PriceData, ProtocolConfig, QueuedAction, RateChange, RequiredContribution, RewardsToken,
+    RotationState, RoundupGoal, SavingsPlan, SignatureScheme, SignerSignature, StreakBoostTier,
+    TimelockAction, User, WithdrawalQueue, WithdrawalRequest, YieldConfig, YieldState,
+    YieldStrategy,
 };
 
 /// Custom error codes for the contract administration
@@ -31,6 +61,14 @@ pub enum ContractError {
     NotInitialized = 2,
     InvalidSignature = 3,
     SignatureExpired = 4,
+    PayloadAlreadyUsed = 5,
+    PayloadRevoked = 6,
+    InvalidBatch = 7,
+    SupplyCapExceeded = 8,
+    InsufficientSignatures = 9,
+    InvalidTargetPlan = 10,
+    MintLimitExceeded = 11,
+    MinterCeilingExceeded = 12,
 }
 
 impl From<ContractError> for soroban_sdk::Error {
@@ -55,6 +93,35 @@ pub(crate) fn ensure_not_paused(env: &Env) -> Result<(), SavingsError> {
     }
 }
 
+/// Fails if the admin has frozen `user`'s account.
+pub(crate) fn ensure_not_frozen(env: &Env, user: &Address) -> Result<(), SavingsError> {
+    if env
+        .storage()
+        .persistent()
+        .has(&ControlDataKey::FrozenAccount(user.clone()))
+    {
+        Err(SavingsError::FrozenAccount)
+    } else {
+        Ok(())
+    }
+}
+
+/// Fails with `ContractPaused` if the contract is paused, or with
+/// `ProtocolPaused` if just `scope` is.
+pub(crate) fn ensure_not_paused_for(env: &Env, scope: PauseScope) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    let is_paused: bool = env
+        .storage()
+        .persistent()
+        .get(&ControlDataKey::PausedScope(scope))
+        .unwrap_or(false);
+    if is_paused {
+        Err(SavingsError::ProtocolPaused)
+    } else {
+        Ok(())
+    }
+}
+
 #[contractimpl]
 impl NesteraContract {
     /// Initialize a new user in the system
@@ -64,7 +131,14 @@ impl NesteraContract {
         users::get_user(&env, &user).unwrap_or_else(|e| panic_with_error!(&env, e))
     }
 
-    pub fn initialize(env: Env, admin: Address, admin_public_key: BytesN<32>) {
+    /// Initializes the contract. With a `token`, deposits and withdrawals move
+    /// that SEP-41 token; without one only internal balances are kept.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        admin_public_key: BytesN<32>,
+        token: Option<Address>,
+    ) {
         if env.storage().instance().has(&DataKey::Initialized) {
             panic_with_error!(&env, ContractError::AlreadyInitialized);
         }
@@ -73,38 +147,227 @@ impl NesteraContract {
         env.storage()
             .instance()
             .set(&DataKey::AdminPublicKey, &admin_public_key);
+        if let Some(token) = token {
+            env.storage()
+                .instance()
+                .set(&DataKeyExt::SavingsToken, &token);
+            normalize::record_decimals(&env, &token);
+        }
         env.storage().instance().set(&DataKey::Initialized, &true);
         env.storage().persistent().set(&DataKey::Paused, &false);
+        upgrade::init_schema_version(&env);
         env.events()
             .publish((symbol_short!("init"),), admin_public_key);
     }
 
-    pub fn verify_signature(env: Env, payload: MintPayload, signature: BytesN<64>) -> bool {
-        if !env.storage().instance().has(&DataKey::Initialized) {
-            panic_with_error!(&env, ContractError::NotInitialized);
-        }
-        let current_timestamp = env.ledger().timestamp();
-        let expiry_time = payload.timestamp + payload.expiry_duration;
-        if current_timestamp > expiry_time {
-            panic_with_error!(&env, ContractError::SignatureExpired);
-        }
-        let admin_public_key: BytesN<32> = env
-            .storage()
-            .instance()
-            .get(&DataKey::AdminPublicKey)
-            .expect("Admin PK not found");
-        let payload_bytes: Bytes = payload.to_xdr(&env);
-        env.crypto()
-            .ed25519_verify(&admin_public_key, &payload_bytes, &signature);
+    pub fn verify_signature(
+        env: Env,
+        payload: MintPayload,
+        signatures: Vec<SignerSignature>,
+    ) -> bool {
+        mint::verify(&env, &payload, &signatures).unwrap_or_else(|e| panic_with_error!(&env, e));
         true
     }
 
-    pub fn mint(env: Env, payload: MintPayload, signature: BytesN<64>) -> i128 {
-        Self::verify_signature(env.clone(), payload.clone(), signature);
-        let amount = payload.amount;
+    pub fn mint(env: Env, payload: MintPayload, signatures: Vec<SignerSignature>) -> i128 {
+        ensure_not_paused_for(&env, PauseScope::Mint)
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        mint::mint(&env, &payload, &signatures).unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    pub fn add_signer(env: Env, public_key: BytesN<32>) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        mint::add_signer(&env, public_key)
+    }
+
+    pub fn add_secp256k1_signer(
+        env: Env,
+        public_key: BytesN<65>,
+    ) -> Result<BytesN<32>, SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        mint::add_secp256k1_signer(&env, public_key)
+    }
+
+    pub fn remove_signer(env: Env, public_key: BytesN<32>) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        mint::remove_signer(&env, public_key)
+    }
+
+    pub fn add_minter(
+        env: Env,
+        public_key: BytesN<32>,
+        ceiling: i128,
+        expires_at: u64,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        mint::add_minter(&env, public_key, ceiling, expires_at)
+    }
+
+    pub fn remove_minter(env: Env, public_key: BytesN<32>) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        mint::remove_minter(&env, public_key)
+    }
+
+    pub fn get_minter(env: Env, public_key: BytesN<32>) -> Option<Minter> {
+        mint::get_minter(&env, public_key)
+    }
+
+    pub fn get_mint_authorizers(env: Env, payload_hash: BytesN<32>) -> Vec<BytesN<32>> {
+        mint::get_mint_authorizers(&env, payload_hash)
+    }
+
+    pub fn set_threshold(env: Env, threshold: u32) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        mint::set_threshold(&env, threshold)
+    }
+
+    pub fn rotate_admin_key(env: Env, new_key: BytesN<32>) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        mint::rotate_admin_key(&env, new_key)
+    }
+
+    pub fn confirm_rotation(env: Env, signature: BytesN<64>) -> Result<(), SavingsError> {
+        mint::confirm_rotation(&env, signature)
+    }
+
+    pub fn get_pending_admin_key(env: Env) -> Option<BytesN<32>> {
+        mint::get_pending_admin_key(&env)
+    }
+
+    /// Returns the bytes a staged admin key signs for `confirm_rotation`.
+    pub fn get_rotation_message(env: Env, new_key: BytesN<32>) -> Bytes {
+        mint::rotation_message(&env, &new_key)
+    }
+
+    pub fn get_signers(env: Env) -> Vec<BytesN<32>> {
+        mint::get_signers(&env)
+    }
+
+    pub fn get_threshold(env: Env) -> u32 {
+        mint::get_threshold(&env)
+    }
+
+    pub fn burn(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
+        mint::burn(&env, user, amount)
+    }
+
+    pub fn admin_burn(env: Env, payload: BurnPayload, signatures: Vec<SignerSignature>) -> i128 {
+        mint::admin_burn(&env, &payload, &signatures).unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    pub fn get_burn_payload_hash(env: Env, payload: BurnPayload) -> BytesN<32> {
+        mint::burn_payload_hash(&env, &payload)
+    }
+
+    /// Returns the bytes the signers sign for a mint payload.
+    pub fn get_mint_message(env: Env, payload: MintPayload) -> Bytes {
+        mint::mint_message(&env, &payload)
+    }
+
+    /// Returns the bytes the signers sign for a burn payload.
+    pub fn get_burn_message(env: Env, payload: BurnPayload) -> Bytes {
+        mint::burn_message(&env, &payload)
+    }
+
+    pub fn get_minted_balance(env: Env, user: Address) -> i128 {
+        mint::get_minted_balance(&env, user)
+    }
+
+    pub fn total_supply(env: Env) -> i128 {
+        mint::total_supply(&env)
+    }
+
+    pub fn get_total_minted(env: Env) -> i128 {
+        mint::get_total_minted(&env)
+    }
+
+    pub fn get_total_burned(env: Env) -> i128 {
+        mint::get_total_burned(&env)
+    }
+
+    pub fn set_supply_cap(env: Env, cap: i128) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        mint::set_supply_cap(&env, cap)
+    }
+
+    pub fn get_supply_cap(env: Env) -> Option<i128> {
+        mint::get_supply_cap(&env)
+    }
+
+    pub fn set_mint_limits(
+        env: Env,
+        max_single: i128,
+        max_per_window: i128,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        mint::set_mint_limits(&env, max_single, max_per_window)
+    }
+
+    pub fn get_mint_limits(env: Env) -> Option<MintLimits> {
+        mint::get_mint_limits(&env)
+    }
+
+    pub fn get_mint_window(env: Env, user: Address) -> Option<MintWindow> {
+        mint::get_mint_window(&env, user)
+    }
+
+    pub fn mint_batch(
+        env: Env,
+        payloads: Vec<MintPayload>,
+        signatures: Vec<Vec<SignerSignature>>,
+    ) -> Vec<MintOutcome> {
+        ensure_not_paused_for(&env, PauseScope::Mint)
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        mint::mint_batch(&env, payloads, signatures).unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Invalidates a signed payload that has not been redeemed yet, e.g. after
+    /// the fiat payment behind it was reversed.
+    pub fn revoke_payload(
+        env: Env,
+        admin: Address,
+        payload_hash: BytesN<32>,
+    ) -> Result<(), SavingsError> {
+        admin.require_auth();
+        let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        if stored_admin != Some(admin.clone()) {
+            return Err(SavingsError::Unauthorized);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::RevokedPayload(payload_hash.clone()), &true);
         env.events()
-            .publish((symbol_short!("mint"), payload.user), amount);
-        amount
+            .publish((symbol_short!("revoke"), admin), payload_hash);
+        Ok(())
+    }
+
+    pub fn is_payload_revoked(env: Env, payload_hash: BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .has(&MintDataKey::RevokedPayload(payload_hash))
+    }
+
+    /// Returns the hash `revoke_payload` identifies a payload by.
+    pub fn get_payload_hash(env: Env, payload: MintPayload) -> BytesN<32> {
+        mint::payload_hash(&env, &payload)
+    }
+
+    pub fn is_payload_used(env: Env, payload: MintPayload) -> bool {
+        env.storage()
+            .persistent()
+            .has(&MintDataKey::ConsumedPayload(mint::payload_hash(
+                &env, &payload,
+            )))
     }
 
     pub fn is_initialized(env: Env) -> bool {
@@ -117,41 +380,32 @@ impl NesteraContract {
         plan_type: PlanType,
         initial_deposit: i128,
     ) -> u64 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        ensure_not_paused_for(&env, PauseScope::Deposits)
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        ensure_not_frozen(&env, &user).unwrap_or_else(|e| panic_with_error!(&env, e));
         if !Self::is_initialized(env.clone()) {
             panic_with_error!(&env, ContractError::NotInitialized);
         }
-        let mut user_data = Self::get_user(env.clone(), user.clone()).unwrap_or(User {
-            total_balance: 0,
-            savings_count: 0,
-        });
-        user_data.savings_count += 1;
-        user_data.total_balance += initial_deposit;
-        let plan_id = user_data.savings_count as u64;
-        let new_plan = SavingsPlan {
-            plan_id,
-            plan_type,
-            balance: initial_deposit,
-            start_time: env.ledger().timestamp(),
-            last_deposit: env.ledger().timestamp(),
-            last_withdraw: 0,
-            interest_rate: 500,
-            is_completed: false,
-            is_withdrawn: false,
-        };
-        env.storage()
-            .persistent()
-            .set(&DataKey::User(user.clone()), &user_data);
-        env.storage()
-            .persistent()
-            .set(&DataKey::SavingsPlan(user.clone(), plan_id), &new_plan);
-        env.events().publish(
-            (Symbol::new(&env, "create_plan"), user, plan_id),
-            initial_deposit,
-        );
+        user.require_auth();
+        plans::ensure_valid_plan_type(&env, &plan_type)
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        let fee = fees::collect_transfer_fee(&env, &user, initial_deposit, true)
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        let plan_id = plans::store_new_plan(&env, &user, plan_type, initial_deposit - fee);
+        assets::pull(&env, &user, initial_deposit).unwrap_or_else(|e| panic_with_error!(&env, e));
         plan_id
     }
 
+    pub fn create_lock_ladder(
+        env: Env,
+        user: Address,
+        total_amount: i128,
+        tranches: u32,
+        interval: u64,
+    ) -> Result<Vec<u64>, SavingsError> {
+        plans::create_lock_ladder(&env, user, total_amount, tranches, interval)
+    }
+
     // --- User & Flexi Logic ---
 
     pub fn get_user(env: Env, user: Address) -> Result<User, SavingsError> {
@@ -163,17 +417,190 @@ impl NesteraContract {
         users::initialize_user(&env, user)
     }
 
+    pub fn initialize_referred_user(
+        env: Env,
+        user: Address,
+        referrer: Address,
+    ) -> Result<(), SavingsError> {
+        ensure_not_paused(&env)?;
+        users::initialize_referred_user(&env, user, referrer)
+    }
+
+    pub fn get_referrer(env: Env, user: Address) -> Option<Address> {
+        users::get_referrer(&env, &user)
+    }
+
+    pub fn get_savings_token(env: Env) -> Option<Address> {
+        assets::get_savings_token(&env)
+    }
+
+    pub fn add_supported_asset(env: Env, asset: Address) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        assets::add_supported_asset(&env, asset)
+    }
+
+    pub fn sweep_token(
+        env: Env,
+        token: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        assets::sweep_token(&env, token, to, amount)
+    }
+
+    pub fn get_asset_decimals(env: Env, asset: Address) -> u32 {
+        normalize::get_asset_decimals(&env, &asset)
+    }
+
+    pub fn remove_supported_asset(env: Env, asset: Address) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        assets::remove_supported_asset(&env, asset)
+    }
+
+    pub fn get_supported_assets(env: Env) -> Vec<Address> {
+        assets::get_supported_assets(&env)
+    }
+
+    pub fn create_asset_plan(
+        env: Env,
+        user: Address,
+        plan_type: PlanType,
+        asset: Address,
+        initial_deposit: i128,
+    ) -> Result<u64, SavingsError> {
+        assets::create_asset_plan(&env, user, plan_type, asset, initial_deposit)
+    }
+
+    pub fn deposit_asset(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        assets::deposit_asset(&env, user, plan_id, asset, amount)
+    }
+
+    pub fn set_group_asset(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        asset: Address,
+    ) -> Result<(), SavingsError> {
+        assets::set_group_asset(&env, creator, group_id, asset)
+    }
+
+    pub fn withdraw_asset(
+        env: Env,
+        user: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        assets::withdraw_asset(&env, user, asset, amount)
+    }
+
+    pub fn set_native_asset(env: Env, sac: Address) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        assets::set_native_asset(&env, sac)
+    }
+
+    pub fn get_native_asset(env: Env) -> Option<Address> {
+        assets::get_native_asset(&env)
+    }
+
+    pub fn deposit_native(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
+        assets::deposit_native(&env, user, amount)
+    }
+
+    pub fn set_swap_router(env: Env, router: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        swap::set_swap_router(&env, router)
+    }
+
+    pub fn get_swap_router(env: Env) -> Option<Address> {
+        swap::get_swap_router(&env)
+    }
+
+    pub fn deposit_with_swap(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        in_token: Address,
+        in_amount: i128,
+        min_out: i128,
+    ) -> Result<i128, SavingsError> {
+        swap::deposit_with_swap(&env, user, plan_id, in_token, in_amount, min_out)
+    }
+
+    pub fn set_price_oracle(
+        env: Env,
+        oracle: Address,
+        max_age: u64,
+        fallback: OracleFallback,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        oracle::set_price_oracle(&env, oracle, max_age, fallback)
+    }
+
+    pub fn get_price_oracle(env: Env) -> Option<OracleConfig> {
+        oracle::get_price_oracle(&env)
+    }
+
+    pub fn set_goal_usd_target(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        usd_target: i128,
+    ) -> Result<(), SavingsError> {
+        oracle::set_goal_usd_target(&env, user, plan_id, usd_target)
+    }
+
+    pub fn get_goal_usd_target(env: Env, user: Address, plan_id: u64) -> Option<i128> {
+        oracle::get_goal_usd_target(&env, user, plan_id)
+    }
+
+    pub fn get_plan_usd_value(env: Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+        oracle::get_plan_usd_value(&env, user, plan_id)
+    }
+
+    pub fn refresh_usd_goal(env: Env, user: Address, plan_id: u64) -> Result<bool, SavingsError> {
+        oracle::refresh_usd_goal(&env, user, plan_id)
+    }
+
+    pub fn get_user_balances(env: Env, user: Address) -> Result<Map<Address, i128>, SavingsError> {
+        assets::get_user_balances(&env, user)
+    }
+
+    pub fn get_asset_balance(env: Env, user: Address, asset: Address) -> i128 {
+        assets::get_asset_balance(&env, user, asset)
+    }
+
+    pub fn get_plan_asset(env: Env, user: Address, plan_id: u64) -> Option<Address> {
+        assets::get_plan_asset(&env, &user, plan_id)
+    }
+
+    pub fn get_group_asset(env: Env, group_id: u64) -> Option<Address> {
+        assets::get_group_asset(&env, group_id)
+    }
+
     pub fn user_exists(env: Env, user: Address) -> bool {
         users::user_exists(&env, &user)
     }
 
     pub fn deposit_flexi(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
+        ensure_not_paused_for(&env, PauseScope::Deposits)?;
         flexi::flexi_deposit(env, user, amount)
     }
 
     pub fn withdraw_flexi(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
+        ensure_not_paused_for(&env, PauseScope::Withdrawals)?;
         flexi::flexi_withdraw(env, user, amount)
     }
 
@@ -181,28 +608,330 @@ impl NesteraContract {
         flexi::get_flexi_balance(&env, user).unwrap_or(0)
     }
 
-    // --- Lock Save Logic ---
-
-    pub fn create_lock_save(env: Env, user: Address, amount: i128, duration: u64) -> u64 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        user.require_auth();
-        lock::create_lock_save(&env, user, amount, duration)
-            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    pub fn set_flexi_cooldown(env: Env, user: Address, seconds: u64) -> Result<(), SavingsError> {
+        flexi::set_flexi_cooldown(&env, user, seconds)
     }
 
-    pub fn withdraw_lock_save(env: Env, user: Address, lock_id: u64) -> i128 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        user.require_auth();
-        lock::withdraw_lock_save(&env, user, lock_id).unwrap_or_else(|e| panic_with_error!(&env, e))
+    pub fn get_flexi_cooldown_remaining(env: Env, user: Address) -> u64 {
+        flexi::get_flexi_cooldown_remaining(&env, user)
     }
 
-    pub fn check_matured_lock(env: Env, lock_id: u64) -> bool {
-        lock::check_matured_lock(&env, lock_id)
+    pub fn create_bucket(env: Env, user: Address, name: Symbol) -> Result<(), SavingsError> {
+        flexi::create_bucket(&env, user, name)
     }
 
-    pub fn get_user_lock_saves(env: Env, user: Address) -> Vec<u64> {
-        lock::get_user_lock_saves(&env, &user)
-    }
+    pub fn move_between_buckets(
+        env: Env,
+        user: Address,
+        from: Option<Symbol>,
+        to: Option<Symbol>,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        flexi::move_between_buckets(&env, user, from, to, amount)
+    }
+
+    pub fn get_buckets(env: Env, user: Address) -> Vec<FlexiBucket> {
+        flexi::get_buckets(&env, user)
+    }
+
+    // --- Lock Save Logic ---
+
+    pub fn create_lock_save(
+        env: Env,
+        user: Address,
+        amount: i128,
+        duration: u64,
+        rollover: bool,
+    ) -> u64 {
+        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        lock::create_lock_save(&env, user, amount, duration, rollover)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    pub fn withdraw_lock_save(env: Env, user: Address, lock_id: u64) -> i128 {
+        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        lock::withdraw_lock_save(&env, user, lock_id).unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    pub fn process_rollover(env: Env, lock_id: u64) -> Result<i128, SavingsError> {
+        lock::process_rollover(&env, lock_id)
+    }
+
+    pub fn check_matured_lock(env: Env, lock_id: u64) -> bool {
+        lock::check_matured_lock(&env, lock_id)
+    }
+
+    pub fn get_user_lock_saves(env: Env, user: Address) -> Vec<u64> {
+        lock::get_user_lock_saves(&env, &user)
+    }
+
+    pub fn get_upcoming_maturities(
+        env: Env,
+        user: Address,
+        window: u64,
+    ) -> Result<Vec<u64>, SavingsError> {
+        views::get_upcoming_maturities(&env, user, window)
+    }
+
+    pub fn sweep_matured_lock(env: Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+        plans::sweep_matured_lock(&env, user, plan_id)
+    }
+
+    pub fn deposit_goal(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        amount: i128,
+    ) -> Result<bool, SavingsError> {
+        plans::deposit_goal(&env, user, plan_id, amount)
+    }
+
+    pub fn create_goal_plan(
+        env: Env,
+        user: Address,
+        category_id: u32,
+        target_amount: i128,
+        contribution_type: u32,
+        initial_deposit: i128,
+        target_date: Option<u64>,
+    ) -> Result<u64, SavingsError> {
+        plans::create_goal_plan(
+            &env,
+            user,
+            category_id,
+            target_amount,
+            contribution_type,
+            initial_deposit,
+            target_date,
+        )
+    }
+
+    pub fn get_required_contribution(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+    ) -> Result<RequiredContribution, SavingsError> {
+        plans::get_required_contribution(&env, user, plan_id)
+    }
+
+    pub fn withdraw_goal(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        amount: i128,
+    ) -> Result<i128, SavingsError> {
+        plans::withdraw_goal(&env, user, plan_id, amount)
+    }
+
+    pub fn abandon_goal(env: Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+        plans::abandon_goal(&env, user, plan_id)
+    }
+
+    pub fn get_goal_progress(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+    ) -> Result<GoalProgress, SavingsError> {
+        plans::get_goal_progress(&env, user, plan_id)
+    }
+
+    pub fn set_roundup_goal(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        rounding_unit: i128,
+    ) -> Result<(), SavingsError> {
+        plans::set_roundup_goal(&env, user, plan_id, rounding_unit)
+    }
+
+    pub fn get_roundup_goal(env: Env, user: Address) -> Option<RoundupGoal> {
+        plans::get_roundup_goal(&env, user)
+    }
+
+    pub fn update_goal_target(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        new_target: i128,
+    ) -> Result<bool, SavingsError> {
+        plans::update_goal_target(&env, user, plan_id, new_target)
+    }
+
+    pub fn set_goal_milestones(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        percents: Vec<u32>,
+    ) -> Result<(), SavingsError> {
+        plans::set_goal_milestones(&env, user, plan_id, percents)
+    }
+
+    pub fn get_goal_milestones(env: Env, user: Address, plan_id: u64) -> Vec<GoalMilestone> {
+        plans::get_goal_milestones(&env, user, plan_id)
+    }
+
+    pub fn claim_goal_bonus(env: Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+        plans::claim_goal_bonus(&env, user, plan_id)
+    }
+
+    pub fn convert_flexi_to_lock(
+        env: Env,
+        user: Address,
+        amount: i128,
+        lock_duration: u64,
+    ) -> Result<u64, SavingsError> {
+        plans::convert_flexi_to_lock(&env, user, amount, lock_duration)
+    }
+
+    pub fn deposit_to_plan(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        plans::deposit_to_plan(&env, user, plan_id, amount)
+    }
+
+    pub fn withdraw_from_plan(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        amount: i128,
+    ) -> Result<i128, SavingsError> {
+        plans::withdraw_from_plan(&env, user, plan_id, amount)
+    }
+
+    pub fn close_plan(env: Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+        plans::close_plan(&env, user, plan_id)
+    }
+
+    pub fn split_plan(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        amount: i128,
+    ) -> Result<u64, SavingsError> {
+        plans::split_plan(&env, user, plan_id, amount)
+    }
+
+    pub fn merge_plans(
+        env: Env,
+        user: Address,
+        source_id: u64,
+        target_id: u64,
+    ) -> Result<(), SavingsError> {
+        plans::merge_plans(&env, user, source_id, target_id)
+    }
+
+    pub fn freeze_plan(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        unfreeze_delay: u64,
+    ) -> Result<(), SavingsError> {
+        plans::freeze_plan(&env, user, plan_id, unfreeze_delay)
+    }
+
+    pub fn unfreeze_plan(env: Env, user: Address, plan_id: u64) -> Result<u64, SavingsError> {
+        plans::unfreeze_plan(&env, user, plan_id)
+    }
+
+    pub fn get_plan_freeze(env: Env, user: Address, plan_id: u64) -> Option<PlanFreeze> {
+        plans::get_plan_freeze(&env, user, plan_id)
+    }
+
+    pub fn set_plan_label(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        label: String,
+    ) -> Result<(), SavingsError> {
+        plans::set_plan_label(&env, user, plan_id, label)
+    }
+
+    pub fn get_plan_label(env: Env, user: Address, plan_id: u64) -> Option<String> {
+        plans::get_plan_label(&env, user, plan_id)
+    }
+
+    pub fn archive_plans(env: Env, user: Address) -> u32 {
+        plans::archive_plans(&env, user)
+    }
+
+    pub fn get_user_savings_plans(env: Env, user: Address) -> Vec<SavingsPlan> {
+        plans::get_user_savings_plans(&env, user)
+    }
+
+    pub fn get_archived_plans(env: Env, user: Address, start: u32, limit: u32) -> Vec<SavingsPlan> {
+        plans::get_archived_plans(&env, user, start, limit)
+    }
+
+    pub fn get_pending_interest(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+    ) -> Result<i128, SavingsError> {
+        interest::get_pending_interest(&env, user, plan_id)
+    }
+
+    pub fn claim_interest(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        restake: bool,
+    ) -> Result<i128, SavingsError> {
+        interest::claim_interest(&env, user, plan_id, restake)
+    }
+
+    pub fn project_balance(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        at_timestamp: u64,
+    ) -> Result<i128, SavingsError> {
+        interest::project_balance(&env, user, plan_id, at_timestamp)
+    }
+
+    pub fn claim_interest_as_rewards(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+    ) -> Result<i128, SavingsError> {
+        interest::claim_interest_as_rewards(&env, user, plan_id)
+    }
+
+    pub fn check_balance_invariant(env: Env, user: Address) -> Result<i128, SavingsError> {
+        plans::check_balance_invariant(&env, user)
+    }
+
+    pub fn withdraw_lock(env: Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+        plans::withdraw_lock(&env, user, plan_id)
+    }
+
+    pub fn early_withdraw_lock(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+    ) -> Result<i128, SavingsError> {
+        plans::early_withdraw_lock(&env, user, plan_id)
+    }
+
+    pub fn deposit_lock(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        plans::deposit_lock(&env, user, plan_id, amount)
+    }
+
+    pub fn extend_lock(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        new_until: u64,
+    ) -> Result<u32, SavingsError> {
+        plans::extend_lock(&env, user, plan_id, new_until)
+    }
 
     // ========== Goal Save Functions ==========
 
@@ -218,149 +947,862 @@ impl NesteraContract {
             .unwrap_or_else(|e| panic_with_error!(&env, e))
     }
 
-    pub fn deposit_to_goal_save(env: Env, user: Address, goal_id: u64, amount: i128) {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        goal::deposit_to_goal_save(&env, user, goal_id, amount)
-            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    pub fn deposit_to_goal_save(env: Env, user: Address, goal_id: u64, amount: i128) {
+        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        goal::deposit_to_goal_save(&env, user, goal_id, amount)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    pub fn withdraw_completed_goal_save(env: Env, user: Address, goal_id: u64) -> i128 {
+        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        goal::withdraw_completed_goal_save(&env, user, goal_id)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    pub fn break_goal_save(env: Env, user: Address, goal_id: u64) -> i128 {
+        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        goal::break_goal_save(&env, user, goal_id).unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    pub fn get_goal_save_detail(env: Env, goal_id: u64) -> GoalSave {
+        goal::get_goal_save(&env, goal_id)
+            .unwrap_or_else(|| panic_with_error!(&env, SavingsError::PlanNotFound))
+    }
+
+    pub fn get_user_goal_saves(env: Env, user: Address) -> Vec<u64> {
+        goal::get_user_goal_saves(&env, &user)
+    }
+
+    // --- Group Save Logic ---
+
+    pub fn create_group_save(
+        env: Env,
+        creator: Address,
+        title: String,
+        description: String,
+        category: String,
+        target_amount: i128,
+        contribution_type: u32,
+        contribution_amount: i128,
+        is_public: bool,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<u64, SavingsError> {
+        ensure_not_paused(&env)?;
+        group::create_group_save(
+            &env,
+            creator,
+            title,
+            description,
+            category,
+            target_amount,
+            contribution_type,
+            contribution_amount,
+            is_public,
+            start_time,
+            end_time,
+        )
+    }
+
+    pub fn join_group_save(env: Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
+        ensure_not_paused(&env)?;
+        group::join_group_save(&env, user, group_id)
+    }
+
+    pub fn contribute_to_group_save(
+        env: Env,
+        user: Address,
+        group_id: u64,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        ensure_not_paused(&env)?;
+        group::contribute_to_group_save(&env, user, group_id, amount)
+    }
+
+    pub fn contribute_for(
+        env: Env,
+        payer: Address,
+        group_id: u64,
+        beneficiary: Address,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        group::contribute_for(&env, payer, group_id, beneficiary, amount)
+    }
+
+    pub fn break_group_save(env: Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
+        ensure_not_paused(&env)?;
+        group::break_group_save(&env, user, group_id)
+    }
+
+    pub fn leave_group_save(env: Env, user: Address, group_id: u64) -> Result<i128, SavingsError> {
+        group::leave_group_save(&env, user, group_id)
+    }
+
+    pub fn remove_group_member(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        member: Address,
+    ) -> Result<i128, SavingsError> {
+        group::remove_group_member(&env, creator, group_id, member)
+    }
+
+    pub fn distribute_group_save(env: Env, group_id: u64) -> Result<i128, SavingsError> {
+        group::distribute_group_save(&env, group_id)
+    }
+
+    pub fn cancel_group_save(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+    ) -> Result<i128, SavingsError> {
+        group::cancel_group_save(&env, creator, group_id)
+    }
+
+    pub fn finalize_expired_group(env: Env, group_id: u64) -> Result<i128, SavingsError> {
+        group::finalize_expired_group(&env, group_id)
+    }
+
+    pub fn set_group_expiry_policy(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        policy: ExpiryPolicy,
+    ) -> Result<(), SavingsError> {
+        group::set_group_expiry_policy(&env, creator, group_id, policy)
+    }
+
+    pub fn set_group_payout_beneficiary(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        beneficiary: Option<Address>,
+    ) -> Result<(), SavingsError> {
+        group::set_group_payout_beneficiary(&env, creator, group_id, beneficiary)
+    }
+
+    pub fn transfer_group_ownership(
+        env: Env,
+        current_creator: Address,
+        group_id: u64,
+        new_owner: Address,
+    ) -> Result<(), SavingsError> {
+        group::transfer_group_ownership(&env, current_creator, group_id, new_owner)
+    }
+
+    pub fn accept_group_ownership(
+        env: Env,
+        new_owner: Address,
+        group_id: u64,
+    ) -> Result<(), SavingsError> {
+        group::accept_group_ownership(&env, new_owner, group_id)
+    }
+
+    pub fn get_group_pending_owner(env: Env, group_id: u64) -> Option<Address> {
+        group::get_group_pending_owner(&env, group_id)
+    }
+
+    pub fn set_group_leave_policy(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        policy: LeavePolicy,
+    ) -> Result<(), SavingsError> {
+        group::set_group_leave_policy(&env, creator, group_id, policy)
+    }
+
+    pub fn set_group_contribution_limits(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        min_contribution: Option<i128>,
+        max_contribution_per_member: Option<i128>,
+    ) -> Result<(), SavingsError> {
+        group::set_group_contribution_limits(
+            &env,
+            creator,
+            group_id,
+            min_contribution,
+            max_contribution_per_member,
+        )
+    }
+
+    pub fn set_group_cycle_duration(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        cycle_duration: u64,
+    ) -> Result<(), SavingsError> {
+        cycles::set_group_cycle_duration(&env, creator, group_id, cycle_duration)
+    }
+
+    pub fn set_group_cycle_requirements(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        required_amount_per_cycle: Option<i128>,
+        late_penalty_bps: u32,
+    ) -> Result<(), SavingsError> {
+        cycles::set_group_cycle_requirements(
+            &env,
+            creator,
+            group_id,
+            required_amount_per_cycle,
+            late_penalty_bps,
+        )
+    }
+
+    pub fn get_member_cycle_status(
+        env: Env,
+        group_id: u64,
+        user: Address,
+    ) -> Result<MemberCycleStatus, SavingsError> {
+        cycles::get_member_cycle_status(&env, group_id, &user)
+    }
+
+    pub fn invite_to_group(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        invitee: Address,
+        ttl: u64,
+    ) -> Result<u64, SavingsError> {
+        group::invite_to_group(&env, creator, group_id, invitee, ttl)
+    }
+
+    pub fn accept_group_invite(
+        env: Env,
+        invitee: Address,
+        group_id: u64,
+    ) -> Result<(), SavingsError> {
+        group::accept_group_invite(&env, invitee, group_id)
+    }
+
+    pub fn revoke_invite(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        invitee: Address,
+    ) -> Result<(), SavingsError> {
+        group::revoke_invite(&env, creator, group_id, invitee)
+    }
+
+    pub fn get_group_invite(env: Env, group_id: u64, invitee: Address) -> Option<GroupInvite> {
+        group::get_group_invite(&env, group_id, invitee)
+    }
+
+    pub fn join_group_waitlist(
+        env: Env,
+        user: Address,
+        group_id: u64,
+    ) -> Result<u32, SavingsError> {
+        group::join_group_waitlist(&env, user, group_id)
+    }
+
+    pub fn leave_group_waitlist(
+        env: Env,
+        user: Address,
+        group_id: u64,
+    ) -> Result<(), SavingsError> {
+        group::leave_group_waitlist(&env, user, group_id)
+    }
+
+    pub fn get_group_waitlist(env: Env, group_id: u64) -> Vec<Address> {
+        group::get_group_waitlist(&env, group_id)
+    }
+
+    pub fn extend_group_target(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        new_target: i128,
+    ) -> Result<(), SavingsError> {
+        group::extend_group_target(&env, creator, group_id, new_target)
+    }
+
+    pub fn merge_groups(
+        env: Env,
+        creator: Address,
+        source_id: u64,
+        target_id: u64,
+    ) -> Result<i128, SavingsError> {
+        group::merge_groups(&env, creator, source_id, target_id)
+    }
+
+    pub fn get_group_save(env: Env, group_id: u64) -> Option<GroupSave> {
+        group::get_group_save(&env, group_id)
+    }
+
+    pub fn get_group_members(env: Env, group_id: u64, start: u32, limit: u32) -> Vec<Address> {
+        group::get_group_members_page(&env, group_id, start, limit)
+    }
+
+    pub fn get_user_groups(env: Env, user: Address, start: u32, limit: u32) -> Vec<u64> {
+        group::get_user_groups_page(&env, &user, start, limit)
+    }
+
+    pub fn get_group_history(
+        env: Env,
+        group_id: u64,
+        start: u32,
+        limit: u32,
+    ) -> Vec<GroupContributionRecord> {
+        history::get_group_history(&env, group_id, start, limit)
+    }
+
+    pub fn list_public_groups(env: Env, start: u32, limit: u32, only_open: bool) -> Vec<GroupSave> {
+        group::list_public_groups(&env, start, limit, only_open)
+    }
+
+    // --- Rotating Groups ---
+
+    pub fn start_rotation(env: Env, creator: Address, group_id: u64) -> Result<(), SavingsError> {
+        rotation::start_rotation(&env, creator, group_id)
+    }
+
+    pub fn payout_rotation(env: Env, group_id: u64) -> Result<i128, SavingsError> {
+        rotation::payout_rotation(&env, group_id)
+    }
+
+    pub fn get_group_rotation(env: Env, group_id: u64) -> Option<RotationState> {
+        rotation::get_rotation(&env, group_id)
+    }
+
+    // --- Group Approvals ---
+
+    pub fn set_group_approval_policy(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        approvers: Vec<Address>,
+        threshold: u32,
+        proposal_ttl: u64,
+    ) -> Result<(), SavingsError> {
+        approvals::set_group_approval_policy(
+            &env,
+            creator,
+            group_id,
+            approvers,
+            threshold,
+            proposal_ttl,
+        )
+    }
+
+    pub fn get_group_approval_policy(env: Env, group_id: u64) -> Option<GroupApprovalPolicy> {
+        approvals::get_group_approval_policy(&env, group_id)
+    }
+
+    pub fn propose_group_action(
+        env: Env,
+        proposer: Address,
+        group_id: u64,
+        action: GroupAction,
+        recipient: Address,
+        amount: i128,
+    ) -> Result<u64, SavingsError> {
+        approvals::propose_group_action(&env, proposer, group_id, action, recipient, amount)
+    }
+
+    pub fn approve_group_action(
+        env: Env,
+        approver: Address,
+        proposal_id: u64,
+    ) -> Result<(), SavingsError> {
+        approvals::approve_group_action(&env, approver, proposal_id)
+    }
+
+    pub fn execute_group_action(
+        env: Env,
+        executor: Address,
+        proposal_id: u64,
+    ) -> Result<(), SavingsError> {
+        approvals::execute_group_action(&env, executor, proposal_id)
+    }
+
+    pub fn get_group_proposal(env: Env, proposal_id: u64) -> Option<GroupProposal> {
+        approvals::get_group_proposal(&env, proposal_id)
+    }
+
+    pub fn set_group_withdrawal_threshold(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        threshold: u32,
+    ) -> Result<(), SavingsError> {
+        approvals::set_group_withdrawal_threshold(&env, creator, group_id, threshold)
+    }
+
+    pub fn request_group_withdrawal(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        recipient: Address,
+        amount: i128,
+    ) -> Result<u64, SavingsError> {
+        approvals::request_group_withdrawal(&env, creator, group_id, recipient, amount)
+    }
+
+    pub fn approve_group_withdrawal(
+        env: Env,
+        member: Address,
+        request_id: u64,
+    ) -> Result<bool, SavingsError> {
+        approvals::approve_group_withdrawal(&env, member, request_id)
+    }
+
+    pub fn cancel_group_withdrawal(
+        env: Env,
+        creator: Address,
+        request_id: u64,
+    ) -> Result<(), SavingsError> {
+        approvals::cancel_group_withdrawal(&env, creator, request_id)
+    }
+
+    pub fn get_group_withdrawal(env: Env, request_id: u64) -> Option<GroupWithdrawalRequest> {
+        approvals::get_group_withdrawal(&env, request_id)
+    }
+
+    // --- Group Voting ---
+
+    pub fn set_group_change_quorum(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        quorum: u32,
+    ) -> Result<(), SavingsError> {
+        group::set_group_change_quorum(&env, creator, group_id, quorum)
+    }
+
+    pub fn propose_group_change(
+        env: Env,
+        proposer: Address,
+        group_id: u64,
+        change: GroupChange,
+    ) -> Result<u64, SavingsError> {
+        voting::propose_group_change(&env, proposer, group_id, change)
+    }
+
+    pub fn vote_group_change(
+        env: Env,
+        voter: Address,
+        proposal_id: u64,
+    ) -> Result<bool, SavingsError> {
+        voting::vote_group_change(&env, voter, proposal_id)
+    }
+
+    pub fn get_group_change(env: Env, proposal_id: u64) -> Option<GroupChangeProposal> {
+        voting::get_group_change(&env, proposal_id)
+    }
+
+    // --- Admin Control Functions ---
+
+    pub fn set_admin(
+        env: Env,
+        current_admin: Address,
+        new_admin: Address,
+    ) -> Result<(), SavingsError> {
+        current_admin.require_auth();
+        let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        if let Some(admin) = stored_admin {
+            if admin != current_admin {
+                return Err(SavingsError::Unauthorized);
+            }
+        }
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.events()
+            .publish((symbol_short!("set_admin"),), new_admin);
+        Ok(())
+    }
+
+    pub fn set_flexi_rate(env: Env, rate: i128) -> Result<(), SavingsError> {
+        let admin = env.storage().instance().get(&DataKey::Admin).unwrap();
+        let admin_address: Address = admin; // Type casting for clarity, though get returns generic
+        admin_address.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        rates::set_flexi_rate(&env, rate)
+    }
+
+    pub fn set_goal_rate(env: Env, rate: i128) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        rates::set_goal_rate(&env, rate)
+    }
+
+    pub fn set_group_rate(env: Env, rate: i128) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        rates::set_group_rate(&env, rate)
+    }
+
+    pub fn set_lock_rate(env: Env, duration_days: u64, rate: i128) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        rates::set_lock_rate(&env, duration_days, rate)
+    }
+
+    pub fn set_lock_rate_tiers(env: Env, tiers: Vec<LockRateTier>) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        rates::set_lock_rate_tiers(&env, tiers)
+    }
+
+    pub fn set_compounding(
+        env: Env,
+        kind: PlanKind,
+        frequency: CompoundingFrequency,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        interest::set_compounding(&env, kind, frequency)
+    }
+
+    pub fn get_compounding(env: Env, kind: PlanKind) -> CompoundingFrequency {
+        interest::get_compounding(&env, kind)
+    }
+
+    pub fn set_streak_boost_tiers(
+        env: Env,
+        tiers: Vec<StreakBoostTier>,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        interest::set_streak_boost_tiers(&env, tiers)
+    }
+
+    pub fn get_streak_boost_tiers(env: Env) -> Vec<StreakBoostTier> {
+        interest::get_streak_boost_tiers(&env)
+    }
+
+    pub fn get_contribution_streak(env: Env, user: Address, plan_id: u64) -> ContributionStreak {
+        interest::get_contribution_streak(&env, user, plan_id)
+    }
+
+    pub fn set_rewards_token(
+        env: Env,
+        token: Address,
+        exchange_rate: i128,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        interest::set_rewards_token(&env, token, exchange_rate)
+    }
+
+    pub fn get_rewards_token(env: Env) -> Option<RewardsToken> {
+        interest::get_rewards_token(&env)
+    }
+
+    pub fn set_penalty_pool(
+        env: Env,
+        share_bps: u32,
+        epoch_length: u64,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        penalty_pool::set_penalty_pool(&env, share_bps, epoch_length)
+    }
+
+    pub fn set_penalty_routing(env: Env, routing: PenaltyRouting) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        penalty_pool::set_penalty_routing(&env, routing)
+    }
+
+    pub fn get_penalty_routing(env: Env) -> Option<PenaltyRouting> {
+        penalty_pool::get_penalty_routing(&env)
+    }
+
+    pub fn get_penalty_pool_config(env: Env) -> Option<PenaltyPoolConfig> {
+        penalty_pool::get_penalty_pool_config(&env)
+    }
+
+    pub fn get_penalty_pool(env: Env) -> PenaltyPool {
+        penalty_pool::get_penalty_pool(&env)
+    }
+
+    pub fn distribute_penalty_pool(env: Env) -> Result<i128, SavingsError> {
+        penalty_pool::distribute_penalty_pool(&env)
+    }
+
+    pub fn claim_penalty_share(env: Env, user: Address) -> Result<i128, SavingsError> {
+        penalty_pool::claim_penalty_share(&env, user)
+    }
+
+    pub fn get_penalty_share(env: Env, user: Address) -> PenaltyShare {
+        penalty_pool::get_penalty_share(&env, user)
+    }
+
+    pub fn set_interest_rate(
+        env: Env,
+        plan_kind: PlanKind,
+        rate_bps: u32,
+        effective_at: u64,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        interest::set_interest_rate(&env, plan_kind, rate_bps, effective_at)
+    }
+
+    pub fn get_rate_schedule(env: Env, kind: PlanKind) -> Vec<RateChange> {
+        interest::get_rate_schedule(&env, kind)
+    }
+
+    pub fn set_yield_config(env: Env, asset: Address, buffer_bps: u32) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        strategies::set_yield_config(&env, asset, buffer_bps)
+    }
+
+    pub fn register_strategy(
+        env: Env,
+        address: Address,
+        weight_bps: u32,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        strategies::register_strategy(&env, address, weight_bps)
+    }
+
+    pub fn set_strategy_weight(
+        env: Env,
+        address: Address,
+        weight_bps: u32,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        strategies::set_strategy_weight(&env, address, weight_bps)
+    }
+
+    pub fn remove_strategy(env: Env, address: Address) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        strategies::remove_strategy(&env, address)
+    }
+
+    pub fn rebalance(env: Env) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        strategies::rebalance(&env)
+    }
+
+    pub fn harvest(env: Env) -> Result<i128, SavingsError> {
+        strategies::harvest(&env)
+    }
+
+    pub fn recall_liquidity(env: Env, amount: i128) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        strategies::recall_liquidity(&env, amount)
+    }
+
+    pub fn process_withdrawal_queue(env: Env) -> Result<u32, SavingsError> {
+        withdrawal_queue::process_withdrawal_queue(&env)
+    }
+
+    pub fn get_withdrawal_queue(env: Env) -> WithdrawalQueue {
+        withdrawal_queue::get_withdrawal_queue(&env)
+    }
+
+    pub fn get_withdrawal_request(env: Env, id: u64) -> Option<WithdrawalRequest> {
+        withdrawal_queue::get_withdrawal_request(&env, id)
+    }
+
+    pub fn get_queue_position(env: Env, id: u64) -> Option<u64> {
+        withdrawal_queue::get_queue_position(&env, id)
+    }
+
+    pub fn get_yield_config(env: Env) -> Option<YieldConfig> {
+        strategies::get_yield_config(&env)
+    }
+
+    pub fn get_strategies(env: Env) -> Vec<YieldStrategy> {
+        strategies::get_strategies(&env)
+    }
+
+    pub fn get_yield_state(env: Env) -> YieldState {
+        strategies::get_yield_state(&env)
+    }
+
+    pub fn set_lock_maturity_policy(
+        env: Env,
+        policy: LockMaturityPolicy,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        plans::set_lock_maturity_policy(&env, policy)
+    }
+
+    pub fn set_goal_bonus_config(env: Env, config: GoalBonusConfig) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        plans::set_goal_bonus_config(&env, config)
+    }
+
+    pub fn fund_goal_bonus_pool(env: Env, amount: i128) -> Result<i128, SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        plans::fund_goal_bonus_pool(&env, &admin, amount)
+    }
+
+    pub fn add_goal_category(env: Env, name: Symbol) -> Result<u32, SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        plans::add_goal_category(&env, name)
+    }
+
+    pub fn set_early_break_fee_bps(env: Env, bps: u32) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        fees::set_early_break_fee_bps(&env, bps)
+    }
+
+    pub fn set_fee_recipient(env: Env, recipient: Address) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        fees::set_fee_recipient(&env, recipient);
+        Ok(())
+    }
+
+    pub fn set_transfer_fees(
+        env: Env,
+        deposit_bps: u32,
+        withdrawal_bps: u32,
+    ) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        fees::set_transfer_fees(&env, deposit_bps, withdrawal_bps)
+    }
+
+    pub fn set_performance_fee(env: Env, kind: PlanKind, bps: u32) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        fees::set_performance_fee(&env, kind, bps)
     }
 
-    pub fn withdraw_completed_goal_save(env: Env, user: Address, goal_id: u64) -> i128 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        goal::withdraw_completed_goal_save(&env, user, goal_id)
-            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    pub fn get_performance_fee(env: Env, kind: PlanKind) -> u32 {
+        fees::get_performance_fee(&env, kind)
     }
 
-    pub fn break_goal_save(env: Env, user: Address, goal_id: u64) -> i128 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        goal::break_goal_save(&env, user, goal_id).unwrap_or_else(|e| panic_with_error!(&env, e))
+    pub fn set_referral_share(env: Env, bps: u32) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        fees::set_referral_share(&env, bps)
     }
 
-    pub fn get_goal_save_detail(env: Env, goal_id: u64) -> GoalSave {
-        goal::get_goal_save(&env, goal_id)
-            .unwrap_or_else(|| panic_with_error!(&env, SavingsError::PlanNotFound))
+    pub fn get_referral_share(env: Env) -> u32 {
+        fees::get_referral_share(&env)
     }
 
-    pub fn get_user_goal_saves(env: Env, user: Address) -> Vec<u64> {
-        goal::get_user_goal_saves(&env, &user)
+    pub fn get_performance_fees(env: Env) -> i128 {
+        fees::get_performance_fees(&env)
     }
 
-    // --- Group Save Logic ---
-
-    pub fn create_group_save(
-        env: Env,
-        creator: Address,
-        title: String,
-        description: String,
-        category: String,
-        target_amount: i128,
-        contribution_type: u32,
-        contribution_amount: i128,
-        is_public: bool,
-        start_time: u64,
-        end_time: u64,
-    ) -> Result<u64, SavingsError> {
-        ensure_not_paused(&env)?;
-        group::create_group_save(
-            &env,
-            creator,
-            title,
-            description,
-            category,
-            target_amount,
-            contribution_type,
-            contribution_amount,
-            is_public,
-            start_time,
-            end_time,
-        )
+    /// Lifetime fees charged by type and by asset, kept up to date as fees
+    /// are charged.
+    pub fn get_fee_stats(env: Env) -> FeeStats {
+        fees::get_fee_stats(&env)
     }
 
-    pub fn join_group_save(env: Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
-        group::join_group_save(&env, user, group_id)
+    pub fn get_treasury_balance(env: Env) -> i128 {
+        fees::get_treasury_balance(&env)
     }
 
-    pub fn contribute_to_group_save(
-        env: Env,
-        user: Address,
-        group_id: u64,
-        amount: i128,
-    ) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
-        group::contribute_to_group_save(&env, user, group_id, amount)
+    pub fn set_treasurer(env: Env, treasurer: Address) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        fees::set_treasurer(&env, treasurer);
+        Ok(())
     }
 
-    pub fn break_group_save(env: Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
-        group::break_group_save(&env, user, group_id)
+    pub fn set_fee_tiers(env: Env, tiers: Vec<FeeTier>) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        fees::set_fee_tiers(&env, tiers)
     }
 
-    // --- Admin Control Functions ---
+    pub fn get_fee_tiers(env: Env) -> Vec<FeeTier> {
+        fees::get_fee_tiers(&env)
+    }
 
-    pub fn set_admin(
-        env: Env,
-        current_admin: Address,
-        new_admin: Address,
-    ) -> Result<(), SavingsError> {
-        current_admin.require_auth();
-        let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
-        if let Some(admin) = stored_admin {
-            if admin != current_admin {
-                return Err(SavingsError::Unauthorized);
-            }
-        }
-        env.storage().instance().set(&DataKey::Admin, &new_admin);
-        env.events()
-            .publish((symbol_short!("set_admin"),), new_admin);
-        Ok(())
+    pub fn get_user_volume(env: Env, user: Address) -> i128 {
+        fees::get_user_volume(&env, &user)
     }
 
-    pub fn set_flexi_rate(env: Env, rate: i128) -> Result<(), SavingsError> {
-        let admin = env.storage().instance().get(&DataKey::Admin).unwrap();
-        let admin_address: Address = admin; // Type casting for clarity, though get returns generic
-        admin_address.require_auth();
-        rates::set_flexi_rate(&env, rate)
+    pub fn get_user_fee_tier(env: Env, user: Address) -> Option<FeeTier> {
+        fees::get_user_fee_tier(&env, &user)
     }
 
-    pub fn set_goal_rate(env: Env, rate: i128) -> Result<(), SavingsError> {
+    pub fn add_fee_exemption(env: Env, user: Address) {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
-        rates::set_goal_rate(&env, rate)
+        fees::add_fee_exemption(&env, user);
     }
 
-    pub fn set_group_rate(env: Env, rate: i128) -> Result<(), SavingsError> {
+    pub fn remove_fee_exemption(env: Env, user: Address) -> Result<(), SavingsError> {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
-        rates::set_group_rate(&env, rate)
+        fees::remove_fee_exemption(&env, user)
     }
 
-    pub fn set_lock_rate(env: Env, duration_days: u64, rate: i128) -> Result<(), SavingsError> {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
-        rates::set_lock_rate(&env, duration_days, rate)
+    pub fn is_fee_exempt(env: Env, user: Address) -> bool {
+        fees::is_fee_exempt(&env, &user)
     }
 
-    pub fn set_early_break_fee_bps(env: Env, bps: u32) -> Result<(), SavingsError> {
+    pub fn get_treasurer(env: Env) -> Address {
+        fees::get_treasurer(&env)
+    }
+
+    /// Moves collected fees to a protocol wallet. Only the treasurer may do
+    /// so directly; while a timelock delay is set, withdrawals are queued as
+    /// `WithdrawTreasury` actions instead.
+    pub fn withdraw_treasury(
+        env: Env,
+        treasurer: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        treasurer.require_auth();
+        if treasurer != fees::get_treasurer(&env) {
+            return Err(SavingsError::Unauthorized);
+        }
+        timelock::ensure_no_timelock(&env)?;
+        fees::withdraw_treasury(&env, to, amount)
+    }
+
+    pub fn set_group_creation_fee(env: Env, fee: GroupCreationFee) -> Result<(), SavingsError> {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
-        if bps > 10_000 {
-            return Err(SavingsError::InvalidAmount);
-        }
-        env.storage()
-            .instance()
-            .set(&DataKey::EarlyBreakFeeBps, &bps);
-        env.events().publish((symbol_short!("set_brk"),), bps);
-        Ok(())
+        timelock::ensure_no_timelock(&env)?;
+        fees::set_group_creation_fee(&env, fee)
     }
 
-    pub fn set_fee_recipient(env: Env, recipient: Address) -> Result<(), SavingsError> {
+    pub fn force_dissolve_group(env: Env, group_id: u64) -> Result<i128, SavingsError> {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
-        env.storage()
-            .instance()
-            .set(&DataKey::FeeRecipient, &recipient);
-        env.events().publish((symbol_short!("set_fee"),), recipient);
-        Ok(())
+        group::force_dissolve_group(&env, admin, group_id)
     }
 
     pub fn pause(env: Env, admin: Address) -> Result<(), SavingsError> {
@@ -391,6 +1833,146 @@ impl NesteraContract {
         Ok(())
     }
 
+    /// Pauses only one kind of operation, leaving the rest of the contract
+    /// running.
+    pub fn pause_only(env: Env, admin: Address, scope: PauseScope) -> Result<(), SavingsError> {
+        admin.require_auth();
+        let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        if stored_admin != Some(admin.clone()) {
+            return Err(SavingsError::Unauthorized);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&ControlDataKey::PausedScope(scope), &true);
+        env.events()
+            .publish((symbol_short!("pause_sc"), admin), scope);
+        Ok(())
+    }
+
+    pub fn unpause_only(env: Env, admin: Address, scope: PauseScope) -> Result<(), SavingsError> {
+        admin.require_auth();
+        let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        if stored_admin != Some(admin.clone()) {
+            return Err(SavingsError::Unauthorized);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&ControlDataKey::PausedScope(scope), &false);
+        env.events()
+            .publish((symbol_short!("unpaus_sc"), admin), scope);
+        Ok(())
+    }
+
+    /// Blocks deposits and withdrawals by `user`, e.g. on a compliance request
+    /// or when the account is compromised. `reason` is a code of the
+    /// operator's choosing.
+    pub fn freeze_account(
+        env: Env,
+        admin: Address,
+        user: Address,
+        reason: u32,
+    ) -> Result<(), SavingsError> {
+        admin.require_auth();
+        let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        if stored_admin != Some(admin.clone()) {
+            return Err(SavingsError::Unauthorized);
+        }
+
+        let freeze = AccountFreeze {
+            reason,
+            frozen_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&ControlDataKey::FrozenAccount(user.clone()), &freeze);
+        env.events()
+            .publish((symbol_short!("acct_frz"), user), reason);
+        Ok(())
+    }
+
+    pub fn unfreeze_account(env: Env, admin: Address, user: Address) -> Result<(), SavingsError> {
+        admin.require_auth();
+        let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        if stored_admin != Some(admin.clone()) {
+            return Err(SavingsError::Unauthorized);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&ControlDataKey::FrozenAccount(user.clone()));
+        env.events().publish((symbol_short!("acct_unfz"), user), ());
+        Ok(())
+    }
+
+    pub fn get_account_freeze(env: Env, user: Address) -> Option<AccountFreeze> {
+        env.storage()
+            .persistent()
+            .get(&ControlDataKey::FrozenAccount(user))
+    }
+
+    pub fn is_account_frozen(env: Env, user: Address) -> bool {
+        ensure_not_frozen(&env, &user).is_err()
+    }
+
+    /// Whether operations of `scope` are blocked, by a global or scoped pause.
+    pub fn is_paused_for(env: Env, scope: PauseScope) -> bool {
+        ensure_not_paused_for(&env, scope).is_err()
+    }
+
+    /// Replaces the contract's code, keeping its address and storage. Call
+    /// `migrate` afterwards if the new code changed the schema version.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        upgrade::upgrade(&env, new_wasm_hash);
+        Ok(())
+    }
+
+    /// Sets the timelock delay for sensitive admin actions. Once non-zero,
+    /// it can only be changed by queueing `SetTimelockDelay`.
+    pub fn set_timelock_delay(env: Env, delay: u64) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::set_timelock_delay(&env, delay)
+    }
+
+    pub fn get_timelock_delay(env: Env) -> u64 {
+        timelock::get_timelock_delay(&env)
+    }
+
+    pub fn queue_action(env: Env, action: TimelockAction) -> u64 {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::queue_action(&env, action)
+    }
+
+    pub fn execute_action(env: Env, id: u64) -> Result<(), SavingsError> {
+        timelock::execute_action(&env, id)
+    }
+
+    pub fn cancel_action(env: Env, id: u64) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::cancel_action(&env, id)
+    }
+
+    pub fn get_queued_action(env: Env, id: u64) -> Option<QueuedAction> {
+        timelock::get_queued_action(&env, id)
+    }
+
+    pub fn migrate(env: Env, version: u32) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        upgrade::migrate(&env, version)
+    }
+
+    pub fn get_schema_version(env: Env) -> u32 {
+        upgrade::get_schema_version(&env)
+    }
+
     // --- Remaining views and utilities ---
     pub fn get_savings_plan(env: Env, user: Address, plan_id: u64) -> Option<SavingsPlan> {
         env.storage()
@@ -421,17 +2003,49 @@ impl NesteraContract {
         rates::get_lock_rate(&env, duration_days)
     }
 
+    pub fn get_lock_rate_tiers(env: Env) -> Vec<LockRateTier> {
+        rates::get_lock_rate_tiers(&env)
+    }
+
+    pub fn get_lock_maturity_policy(env: Env) -> LockMaturityPolicy {
+        plans::get_lock_maturity_policy(&env)
+    }
+
+    pub fn get_goal_bonus_config(env: Env) -> GoalBonusConfig {
+        plans::get_goal_bonus_config(&env)
+    }
+
+    pub fn get_goal_bonus_pool(env: Env) -> i128 {
+        plans::get_goal_bonus_pool(&env)
+    }
+
+    pub fn list_goal_categories(env: Env) -> Vec<GoalCategory> {
+        plans::list_goal_categories(&env)
+    }
+
     pub fn get_early_break_fee_bps(env: Env) -> u32 {
-        env.storage()
-            .instance()
-            .get(&DataKey::EarlyBreakFeeBps)
-            .unwrap_or(0)
+        config::get_config(&env).early_break_fee_bps
+    }
+
+    pub fn get_config(env: Env) -> ProtocolConfig {
+        config::get_config(&env)
+    }
+
+    pub fn update_config(env: Env, config: ProtocolConfig) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        timelock::ensure_no_timelock(&env)?;
+        config::update_config(&env, config)
     }
 
     pub fn get_fee_recipient(env: Env) -> Option<Address> {
         env.storage().instance().get(&DataKey::FeeRecipient)
     }
 
+    pub fn get_fee_config(env: Env) -> FeeConfig {
+        fees::get_fee_config(&env)
+    }
+
     pub fn get_protocol_fee_balance(env: Env, recipient: Address) -> i128 {
         env.storage()
             .persistent()
@@ -467,6 +2081,32 @@ impl NesteraContract {
         autosave::get_autosave(&env, schedule_id)
     }
 
+    pub fn setup_autosave(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+        amount: i128,
+        interval: u64,
+    ) -> Result<(), SavingsError> {
+        autosave::setup_autosave(&env, user, plan_id, amount, interval)
+    }
+
+    pub fn execute_plan_autosave(
+        env: Env,
+        user: Address,
+        plan_id: u64,
+    ) -> Result<bool, SavingsError> {
+        autosave::execute_plan_autosave(&env, user, plan_id)
+    }
+
+    pub fn cancel_plan_autosave(env: Env, user: Address, plan_id: u64) -> Result<(), SavingsError> {
+        autosave::cancel_plan_autosave(&env, user, plan_id)
+    }
+
+    pub fn get_plan_autosave(env: Env, user: Address, plan_id: u64) -> Option<PlanAutoSave> {
+        autosave::get_plan_autosave(&env, user, plan_id)
+    }
+
     /// Gets all AutoSave schedule IDs for a user
     pub fn get_user_autosaves(env: Env, user: Address) -> Vec<u64> {
         autosave::get_user_autosaves(&env, &user)
@@ -476,7 +2116,35 @@ impl NesteraContract {
 #[cfg(test)]
 mod admin_tests;
 #[cfg(test)]
+mod approvals_test;
+#[cfg(test)]
+mod assets_test;
+#[cfg(test)]
+mod autosave_test;
+#[cfg(test)]
+mod flexi_test;
+#[cfg(test)]
+mod group_tests;
+#[cfg(test)]
+mod interest_test;
+#[cfg(test)]
+mod lock_test;
+#[cfg(test)]
+mod normalize_test;
+#[cfg(test)]
+mod oracle_test;
+#[cfg(test)]
+mod penalty_pool_test;
+#[cfg(test)]
+mod plans_test;
+#[cfg(test)]
 mod rates_test;
 #[cfg(test)]
 #[cfg(test)]
+mod strategies_test;
+#[cfg(test)]
+mod swap_test;
+#[cfg(test)]
 mod test;
+#[cfg(test)]
+mod voting_test;
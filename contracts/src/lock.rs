@@ -1,7 +1,10 @@
-use crate::ensure_not_paused;
+use crate::assets;
+use crate::config;
 use crate::errors::SavingsError;
-use crate::storage_types::{DataKey, LockSave, User};
+use crate::flexi;
+use crate::storage_types::{DataKey, LockSave, PauseScope, User};
 use crate::users;
+use crate::{ensure_not_frozen, ensure_not_paused, ensure_not_paused_for};
 use soroban_sdk::{symbol_short, Address, Env, Vec};
 
 /// Creates a new Lock Save plan for a user
@@ -10,14 +13,17 @@ pub fn create_lock_save(
     user: Address,
     amount: i128,
     duration: u64,
+    rollover: bool,
 ) -> Result<u64, SavingsError> {
-    ensure_not_paused(env)?;
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
     user.require_auth();
 
     // Validate inputs
     if amount <= 0 {
         return Err(SavingsError::InvalidAmount);
     }
+    config::ensure_min_deposit(env, amount)?;
     if duration == 0 {
         // Aligned with the test expectation of a generic invalid duration error
         return Err(SavingsError::InvalidTimestamp);
@@ -45,6 +51,7 @@ pub fn create_lock_save(
         start_time,
         maturity_time,
         is_withdrawn: false,
+        rollover,
     };
 
     // Store the LockSave
@@ -58,15 +65,17 @@ pub fn create_lock_save(
     // Update user's profile stats
     let user_key = DataKey::User(user.clone());
     let mut user_data: User = env.storage().persistent().get(&user_key).unwrap();
-    user_data.total_balance += amount;
     user_data.savings_count += 1;
     env.storage().persistent().set(&user_key, &user_data);
 
+    assets::pull(env, &user, amount)?;
+
     Ok(lock_id)
 }
 
 pub fn withdraw_lock_save(env: &Env, user: Address, lock_id: u64) -> Result<i128, SavingsError> {
-    ensure_not_paused(env)?;
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
     user.require_auth();
 
     let mut lock_save = get_lock_save(env, lock_id).ok_or(SavingsError::PlanNotFound)?;
@@ -90,12 +99,8 @@ pub fn withdraw_lock_save(env: &Env, user: Address, lock_id: u64) -> Result<i128
         .persistent()
         .set(&DataKey::LockSave(lock_id), &lock_save);
 
-    // Update user's total balance (subtracting the locked portion)
-    let user_key = DataKey::User(user.clone());
-    if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
-        user_data.total_balance -= lock_save.amount;
-        env.storage().persistent().set(&user_key, &user_data);
-    }
+    // The locked funds and their yield land in the user's Flexi balance
+    flexi::credit_flexi(env, &user, final_amount)?;
 
     env.events()
         .publish((symbol_short!("withdraw"), user, lock_id), final_amount);
@@ -103,6 +108,55 @@ pub fn withdraw_lock_save(env: &Env, user: Address, lock_id: u64) -> Result<i128
     Ok(final_amount)
 }
 
+/// Re-locks a matured Lock Save that was created with `rollover` enabled.
+///
+/// Anyone (typically a keeper) may trigger the rollover. The principal plus
+/// the yield earned so far becomes the new principal, locked for the same
+/// duration starting now.
+///
+/// # Returns
+/// `Ok(i128)` - The new locked amount
+///
+/// # Errors
+/// * `PlanNotFound` - If the lock doesn't exist
+/// * `PlanCompleted` - If the lock was already withdrawn
+/// * `InvalidPlanConfig` - If rollover is not enabled for the lock
+/// * `TooEarly` - If the lock has not matured yet
+pub fn process_rollover(env: &Env, lock_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+
+    let mut lock_save = get_lock_save(env, lock_id).ok_or(SavingsError::PlanNotFound)?;
+
+    if lock_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if !lock_save.rollover {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    let now = env.ledger().timestamp();
+    if now < lock_save.maturity_time {
+        return Err(SavingsError::TooEarly);
+    }
+
+    let duration = lock_save.maturity_time - lock_save.start_time;
+    let new_amount = calculate_lock_save_yield(&lock_save, now);
+
+    lock_save.amount = new_amount;
+    lock_save.start_time = now;
+    lock_save.maturity_time = now.checked_add(duration).ok_or(SavingsError::Overflow)?;
+    env.storage()
+        .persistent()
+        .set(&DataKey::LockSave(lock_id), &lock_save);
+
+    env.events().publish(
+        (symbol_short!("lock_roll"), lock_save.owner, lock_id),
+        (new_amount, lock_save.maturity_time),
+    );
+
+    Ok(new_amount)
+}
+
 pub fn check_matured_lock(env: &Env, lock_id: u64) -> bool {
     if let Some(lock_save) = get_lock_save(env, lock_id) {
         env.ledger().timestamp() >= lock_save.maturity_time
@@ -0,0 +1,50 @@
+use crate::{NesteraContract, NesteraContractClient, SavingsError};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env,
+};
+
+fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &admin_pk, &None);
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+
+    (env, client, user)
+}
+
+#[test]
+fn test_process_rollover_relocks_principal_and_interest() {
+    let (env, client, user) = setup();
+    let lock_id = client.create_lock_save(&user, &10_000, &1_000, &true);
+
+    let res = client.try_process_rollover(&lock_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooEarly));
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let relocked = client.process_rollover(&lock_id);
+    assert!(relocked >= 10_000);
+    assert_eq!(client.get_user(&user).total_balance, relocked);
+
+    // Locked again for the same duration
+    assert!(!client.check_matured_lock(&lock_id));
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    assert!(client.check_matured_lock(&lock_id));
+}
+
+#[test]
+fn test_process_rollover_requires_opt_in() {
+    let (env, client, user) = setup();
+    let lock_id = client.create_lock_save(&user, &10_000, &1_000, &false);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let res = client.try_process_rollover(&lock_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+}
@@ -0,0 +1,790 @@
+//! Crediting users from payloads the admin signs off-chain.
+//!
+//! The backend signs a `MintPayload` once a user's fiat deposit settles, and
+//! the user (or the backend on their behalf) submits it with the signatures.
+//! A payload is redeemable exactly once, before it expires and unless the
+//! admin has revoked it, and credits the user's minted balance.
+//!
+//! Payloads must be signed by at least `threshold` keys of the signer set,
+//! so a single compromised key can't mint. Until the admin changes the set,
+//! it holds only the public key given at `initialize`, with a threshold of
+//! one.
+//!
+//! Signers use Ed25519 unless registered with `add_secp256k1_signer`, for
+//! backends that can only sign secp256k1. Such a signer is known by its key
+//! id, the SHA-256 of its uncompressed SEC-1 public key, and its signatures
+//! are checked by recovering the key from the SHA-256 of the message.
+//!
+//! As a blast-radius control against a compromised signing backend, the
+//! admin may cap the supply and limit what each user mints at once and per
+//! 24 hours.
+//!
+//! For day-to-day minting the admin can also register delegated minter keys.
+//! A payload signed by a single unexpired minter needs no other signatures,
+//! but the key can only mint up to its own ceiling. Every redeemed payload
+//! records the keys that authorized it.
+//!
+//! Off-ramp flows reclaim minted credits by burning them, either by the user
+//! or through a `BurnPayload` signed by the signers.
+//!
+//! Signers never sign a bare payload. The signed bytes are the XDR of the
+//! tuple `(kind, PAYLOAD_VERSION, contract, network_id, payload)`, where
+//! `kind` is `"mint"`, `"burn"` or `"rotate"` and `network_id` is the
+//! SHA-256 of the network passphrase. A signature is therefore only valid
+//! for one kind of operation on one deployment of one network, and payloads
+//! signed for an older format stop verifying when the version changes.
+
+use crate::assets;
+use crate::errors::SavingsError;
+use crate::plans;
+use crate::storage_types::{
+    BurnPayload, DataKey, MintDataKey, MintLimits, MintOutcome, MintPayload, MintWindow, Minter,
+    PlanType, SavingsPlan, SignatureScheme, SignerSignature,
+};
+use crate::ContractError;
+use soroban_sdk::{
+    panic_with_error, symbol_short, vec, xdr::ToXdr, Address, Bytes, BytesN, Env, Error, IntoVal,
+    Symbol, Val, Vec,
+};
+
+/// Maximum number of payloads in one `mint_batch`
+pub const MAX_MINT_BATCH: u32 = 50;
+
+/// Maximum number of keys in the signer set
+pub const MAX_SIGNERS: u32 = 10;
+
+/// Length of the window `MintLimits.max_per_window` applies to
+pub const MINT_WINDOW: u64 = 24 * 60 * 60;
+
+/// Version of the signed payload format
+pub const PAYLOAD_VERSION: u32 = 1;
+
+/// SHA-256 of the bytes the signers sign for a mint payload
+pub(crate) fn payload_hash(env: &Env, payload: &MintPayload) -> BytesN<32> {
+    env.crypto().sha256(&mint_message(env, payload)).into()
+}
+
+/// The bytes the signers sign for a mint payload
+pub(crate) fn mint_message(env: &Env, payload: &MintPayload) -> Bytes {
+    signed_message(env, symbol_short!("mint"), payload.clone())
+}
+
+/// The bytes the signers sign for a burn payload
+pub(crate) fn burn_message(env: &Env, payload: &BurnPayload) -> Bytes {
+    signed_message(env, symbol_short!("burn"), payload.clone())
+}
+
+/// Checks that the payload has not expired and carries valid signatures from
+/// at least `threshold` signers. An invalid signature aborts the transaction.
+pub(crate) fn verify(
+    env: &Env,
+    payload: &MintPayload,
+    signatures: &Vec<SignerSignature>,
+) -> Result<(), ContractError> {
+    verify_message(
+        env,
+        payload.timestamp + payload.expiry_duration,
+        &mint_message(env, payload),
+        signatures,
+    )
+}
+
+/// SHA-256 of the bytes the admin signs for a burn payload
+pub(crate) fn burn_payload_hash(env: &Env, payload: &BurnPayload) -> BytesN<32> {
+    env.crypto().sha256(&burn_message(env, payload)).into()
+}
+
+/// Redeems a signed payload.
+///
+/// # Returns
+/// The amount minted
+///
+/// # Errors
+/// * `NotInitialized` - If the contract is not initialized
+/// * `SignatureExpired` - If the payload has expired
+/// * `PayloadRevoked` - If the admin revoked the payload
+/// * `PayloadAlreadyUsed` - If the payload was already redeemed
+/// * `SupplyCapExceeded` - If minting would take the supply over the cap
+/// * `InsufficientSignatures` - If fewer than `threshold` signers signed
+/// * `InvalidTargetPlan` - If the target plan is not an open lock or goal
+///   plan of the user in the savings token
+/// * `MintLimitExceeded` - If the mint exceeds the user's limits
+pub(crate) fn mint(
+    env: &Env,
+    payload: &MintPayload,
+    signatures: &Vec<SignerSignature>,
+) -> Result<i128, ContractError> {
+    let hash = payload_hash(env, payload);
+    if env
+        .storage()
+        .persistent()
+        .has(&MintDataKey::RevokedPayload(hash.clone()))
+    {
+        return Err(ContractError::PayloadRevoked);
+    }
+    let key = MintDataKey::ConsumedPayload(hash.clone());
+    if env.storage().persistent().has(&key) {
+        return Err(ContractError::PayloadAlreadyUsed);
+    }
+    let amount = payload.amount;
+    let supply = total_supply(env).saturating_add(amount);
+    if get_supply_cap(env).is_some_and(|cap| supply > cap) {
+        return Err(ContractError::SupplyCapExceeded);
+    }
+    let window = check_limits(env, &payload.user, amount)?;
+    let (authorizers, minter) = match delegated_minter(env, signatures) {
+        Some((public_key, minter)) => {
+            let minter = authorize_minter(env, payload, &public_key, minter, signatures)?;
+            (vec![env, public_key.clone()], Some((public_key, minter)))
+        }
+        None => {
+            verify(env, payload, signatures)?;
+            (signer_keys(env, signatures), None)
+        }
+    };
+
+    match payload.target_plan_id {
+        Some(plan_id) => credit_target_plan(env, &payload.user, plan_id, amount)?,
+        None => {
+            let balance_key = MintDataKey::MintedBalance(payload.user.clone());
+            let balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+            env.storage()
+                .persistent()
+                .set(&balance_key, &balance.saturating_add(amount));
+        }
+    }
+    env.storage().persistent().set(&key, &true);
+    env.storage()
+        .persistent()
+        .set(&MintDataKey::MintAuthorizers(hash), &authorizers);
+    if let Some((public_key, minter)) = minter {
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::Minter(public_key), &minter);
+    }
+    if let Some(window) = window {
+        env.storage()
+            .persistent()
+            .set(&MintDataKey::MintWindow(payload.user.clone()), &window);
+    }
+    env.storage().instance().set(
+        &MintDataKey::TotalMinted,
+        &get_total_minted(env).saturating_add(amount),
+    );
+    env.events()
+        .publish((symbol_short!("mint"), payload.user.clone()), amount);
+    Ok(amount)
+}
+
+/// Returns the key and record of the minter that signed the payload, if it
+/// was signed by exactly one unexpired delegated minter.
+fn delegated_minter(env: &Env, signatures: &Vec<SignerSignature>) -> Option<(BytesN<32>, Minter)> {
+    if signatures.len() != 1 || signatures.get_unchecked(0).scheme != SignatureScheme::Ed25519 {
+        return None;
+    }
+    let public_key = signatures.get_unchecked(0).public_key;
+    get_minter(env, public_key.clone())
+        .filter(|minter| env.ledger().timestamp() <= minter.expires_at)
+        .map(|minter| (public_key, minter))
+}
+
+/// Checks a delegated minter's signature and ceiling, returning the minter
+/// with the payload counted against its ceiling.
+fn authorize_minter(
+    env: &Env,
+    payload: &MintPayload,
+    public_key: &BytesN<32>,
+    mut minter: Minter,
+    signatures: &Vec<SignerSignature>,
+) -> Result<Minter, ContractError> {
+    if env.ledger().timestamp() > payload.timestamp + payload.expiry_duration {
+        return Err(ContractError::SignatureExpired);
+    }
+    minter.minted = minter.minted.saturating_add(payload.amount);
+    if minter.minted > minter.ceiling {
+        return Err(ContractError::MinterCeilingExceeded);
+    }
+    env.crypto().ed25519_verify(
+        public_key,
+        &mint_message(env, payload),
+        &signatures.get_unchecked(0).signature,
+    );
+    Ok(minter)
+}
+
+/// The distinct signer-set keys among `signatures`, once they have verified
+fn signer_keys(env: &Env, signatures: &Vec<SignerSignature>) -> Vec<BytesN<32>> {
+    let signers = get_signers(env);
+    let mut keys: Vec<BytesN<32>> = Vec::new(env);
+    for signed in signatures.iter() {
+        if signers.contains(&signed.public_key) && !keys.contains(&signed.public_key) {
+            keys.push_back(signed.public_key);
+        }
+    }
+    keys
+}
+
+/// Registers a delegated minter key, or updates the ceiling and expiry of an
+/// existing one. What the key has already minted still counts towards the
+/// new ceiling.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If the ceiling is not positive
+/// * `InvalidTimestamp` - If the expiry is not in the future
+pub(crate) fn add_minter(
+    env: &Env,
+    public_key: BytesN<32>,
+    ceiling: i128,
+    expires_at: u64,
+) -> Result<(), SavingsError> {
+    if ceiling <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if expires_at <= env.ledger().timestamp() {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+    let minted = get_minter(env, public_key.clone()).map_or(0, |minter| minter.minted);
+    env.storage().persistent().set(
+        &MintDataKey::Minter(public_key.clone()),
+        &Minter {
+            ceiling,
+            minted,
+            expires_at,
+        },
+    );
+    env.events().publish(
+        (symbol_short!("mntr_add"), public_key),
+        (ceiling, expires_at),
+    );
+    Ok(())
+}
+
+/// Removes a delegated minter key.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidSignature` - If the key is not a minter
+pub(crate) fn remove_minter(env: &Env, public_key: BytesN<32>) -> Result<(), SavingsError> {
+    let key = MintDataKey::Minter(public_key.clone());
+    if !env.storage().persistent().has(&key) {
+        return Err(SavingsError::InvalidSignature);
+    }
+    env.storage().persistent().remove(&key);
+    env.events()
+        .publish((symbol_short!("mntr_rm"),), public_key);
+    Ok(())
+}
+
+pub(crate) fn get_minter(env: &Env, public_key: BytesN<32>) -> Option<Minter> {
+    env.storage()
+        .persistent()
+        .get(&MintDataKey::Minter(public_key))
+}
+
+/// Keys that authorized a redeemed payload; empty if it wasn't redeemed.
+pub(crate) fn get_mint_authorizers(env: &Env, payload_hash: BytesN<32>) -> Vec<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&MintDataKey::MintAuthorizers(payload_hash))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Sets per-user mint limits. Zero disables a limit.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If a limit is negative
+pub(crate) fn set_mint_limits(
+    env: &Env,
+    max_single: i128,
+    max_per_window: i128,
+) -> Result<(), SavingsError> {
+    if max_single < 0 || max_per_window < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage().instance().set(
+        &MintDataKey::MintLimits,
+        &MintLimits {
+            max_single,
+            max_per_window,
+        },
+    );
+    env.events()
+        .publish((symbol_short!("set_mlim"),), (max_single, max_per_window));
+    Ok(())
+}
+
+/// Returns the per-user mint limits, if configured.
+pub(crate) fn get_mint_limits(env: &Env) -> Option<MintLimits> {
+    env.storage().instance().get(&MintDataKey::MintLimits)
+}
+
+/// Returns the user's current mint window, if it is still open.
+pub(crate) fn get_mint_window(env: &Env, user: Address) -> Option<MintWindow> {
+    let window: MintWindow = env
+        .storage()
+        .persistent()
+        .get(&MintDataKey::MintWindow(user))?;
+    (env.ledger().timestamp() < window.start.saturating_add(MINT_WINDOW)).then_some(window)
+}
+
+/// Checks a mint of `amount` against the user's limits.
+///
+/// # Returns
+/// The user's window including the mint, to store once it succeeds, or
+/// `None` if no window limit applies
+fn check_limits(
+    env: &Env,
+    user: &Address,
+    amount: i128,
+) -> Result<Option<MintWindow>, ContractError> {
+    let limits = match get_mint_limits(env) {
+        Some(limits) => limits,
+        None => return Ok(None),
+    };
+    if limits.max_single > 0 && amount > limits.max_single {
+        return Err(ContractError::MintLimitExceeded);
+    }
+    if limits.max_per_window == 0 {
+        return Ok(None);
+    }
+
+    let mut window = get_mint_window(env, user.clone()).unwrap_or(MintWindow {
+        start: env.ledger().timestamp(),
+        minted: 0,
+    });
+    window.minted = window.minted.saturating_add(amount);
+    if window.minted > limits.max_per_window {
+        return Err(ContractError::MintLimitExceeded);
+    }
+    Ok(Some(window))
+}
+
+/// Credits a mint straight into one of the user's lock or goal plans,
+/// updating the plan and the user's totals as a deposit would.
+fn credit_target_plan(
+    env: &Env,
+    user: &Address,
+    plan_id: u64,
+    amount: i128,
+) -> Result<(), ContractError> {
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(ContractError::InvalidTargetPlan)?;
+    let is_lock_or_goal = matches!(plan.plan_type, PlanType::Lock(_) | PlanType::Goal(..));
+    if !is_lock_or_goal || assets::holds_other_asset(env, user, plan_id) {
+        return Err(ContractError::InvalidTargetPlan);
+    }
+    plans::credit_plan(env, user.clone(), plan_id, amount)
+        .map_err(|_| ContractError::InvalidTargetPlan)
+}
+
+/// Redeems several signed payloads in one transaction.
+///
+/// Payloads that can't be redeemed (expired, revoked, already used
+/// including a duplicate earlier in the batch, or over the supply cap) are
+/// skipped and reported with their error code; the others are minted. An
+/// invalid signature aborts the whole batch, as it does for `mint`.
+///
+/// # Returns
+/// One outcome per payload, in order
+///
+/// # Errors
+/// * `InvalidBatch` - If the batch is empty, larger than `MAX_MINT_BATCH`,
+///   or the number of signatures differs from the number of payloads
+pub(crate) fn mint_batch(
+    env: &Env,
+    payloads: Vec<MintPayload>,
+    signatures: Vec<Vec<SignerSignature>>,
+) -> Result<Vec<MintOutcome>, ContractError> {
+    if payloads.is_empty() || payloads.len() > MAX_MINT_BATCH || payloads.len() != signatures.len()
+    {
+        return Err(ContractError::InvalidBatch);
+    }
+
+    let mut outcomes = Vec::new(env);
+    for (payload, signed) in payloads.iter().zip(signatures.iter()) {
+        let outcome = match mint(env, &payload, &signed) {
+            Ok(amount) => MintOutcome::Minted(amount),
+            Err(e) => MintOutcome::Failed(e as u32),
+        };
+        outcomes.push_back(outcome);
+    }
+    Ok(outcomes)
+}
+
+/// Burns `amount` of the user's own minted balance.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InsufficientBalance` - If the minted balance can't cover `amount`
+pub(crate) fn burn(env: &Env, user: Address, amount: i128) -> Result<(), SavingsError> {
+    user.require_auth();
+    burn_from(env, &user, amount)
+}
+
+/// Burns a user's minted balance as instructed by a payload the signers
+/// signed. Each payload can be used once.
+///
+/// # Returns
+/// The amount burned
+///
+/// # Errors
+/// * `SignatureExpired` - If the payload has expired
+/// * `PayloadRevoked` - If the admin revoked the payload
+/// * `PayloadAlreadyUsed` - If the payload was already used
+/// * `InsufficientSignatures` - If fewer than `threshold` signers signed
+/// * Any error of `burn`
+pub(crate) fn admin_burn(
+    env: &Env,
+    payload: &BurnPayload,
+    signatures: &Vec<SignerSignature>,
+) -> Result<i128, Error> {
+    let hash = burn_payload_hash(env, payload);
+    if env
+        .storage()
+        .persistent()
+        .has(&MintDataKey::RevokedPayload(hash.clone()))
+    {
+        return Err(ContractError::PayloadRevoked.into());
+    }
+    let key = MintDataKey::ConsumedPayload(hash);
+    if env.storage().persistent().has(&key) {
+        return Err(ContractError::PayloadAlreadyUsed.into());
+    }
+    verify_message(
+        env,
+        payload.timestamp + payload.expiry_duration,
+        &burn_message(env, payload),
+        signatures,
+    )?;
+
+    env.storage().persistent().set(&key, &true);
+    burn_from(env, &payload.user, payload.amount)?;
+    Ok(payload.amount)
+}
+
+/// Returns the user's minted balance.
+pub(crate) fn get_minted_balance(env: &Env, user: Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&MintDataKey::MintedBalance(user))
+        .unwrap_or(0)
+}
+
+/// Caps the outstanding minted supply. A cap below the current supply
+/// blocks minting until enough is burned.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `cap` is negative
+pub(crate) fn set_supply_cap(env: &Env, cap: i128) -> Result<(), SavingsError> {
+    if cap < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage().instance().set(&MintDataKey::SupplyCap, &cap);
+    env.events().publish((symbol_short!("set_cap"),), cap);
+    Ok(())
+}
+
+/// Returns the supply cap, if one is set.
+pub(crate) fn get_supply_cap(env: &Env) -> Option<i128> {
+    env.storage().instance().get(&MintDataKey::SupplyCap)
+}
+
+/// Returns everything ever minted.
+pub(crate) fn get_total_minted(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&MintDataKey::TotalMinted)
+        .unwrap_or(0)
+}
+
+/// Returns everything ever burned.
+pub(crate) fn get_total_burned(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&MintDataKey::TotalBurned)
+        .unwrap_or(0)
+}
+
+/// Returns the minted balance outstanding across all users.
+pub(crate) fn total_supply(env: &Env) -> i128 {
+    get_total_minted(env) - get_total_burned(env)
+}
+
+fn burn_from(env: &Env, user: &Address, amount: i128) -> Result<(), SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let balance = get_minted_balance(env, user.clone());
+    if balance < amount {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    env.storage().persistent().set(
+        &MintDataKey::MintedBalance(user.clone()),
+        &(balance - amount),
+    );
+    env.storage().instance().set(
+        &MintDataKey::TotalBurned,
+        &get_total_burned(env).saturating_add(amount),
+    );
+    env.events()
+        .publish((symbol_short!("burn"), user.clone()), amount);
+    Ok(())
+}
+
+fn verify_message(
+    env: &Env,
+    expiry_time: u64,
+    message: &Bytes,
+    signatures: &Vec<SignerSignature>,
+) -> Result<(), ContractError> {
+    if !env.storage().instance().has(&DataKey::Initialized) {
+        return Err(ContractError::NotInitialized);
+    }
+    if env.ledger().timestamp() > expiry_time {
+        return Err(ContractError::SignatureExpired);
+    }
+
+    let signers = get_signers(env);
+    let mut seen: Vec<BytesN<32>> = Vec::new(env);
+    for signed in signatures.iter() {
+        // Signatures from unknown keys or repeated keys don't count
+        if !signers.contains(&signed.public_key) || seen.contains(&signed.public_key) {
+            continue;
+        }
+        verify_signer(env, message, &signed);
+        seen.push_back(signed.public_key);
+    }
+    if seen.len() < get_threshold(env) {
+        return Err(ContractError::InsufficientSignatures);
+    }
+    Ok(())
+}
+
+/// Checks one signature under its scheme, aborting the transaction if it is
+/// invalid.
+fn verify_signer(env: &Env, message: &Bytes, signed: &SignerSignature) {
+    match signed.scheme {
+        SignatureScheme::Ed25519 => {
+            env.crypto()
+                .ed25519_verify(&signed.public_key, message, &signed.signature)
+        }
+        SignatureScheme::Secp256k1(recovery_id) => {
+            let public_key: Option<BytesN<65>> = env
+                .storage()
+                .instance()
+                .get(&MintDataKey::Secp256k1Key(signed.public_key.clone()));
+            let recovered = env.crypto().secp256k1_recover(
+                &env.crypto().sha256(message),
+                &signed.signature,
+                recovery_id,
+            );
+            if public_key != Some(recovered) {
+                panic_with_error!(env, ContractError::InvalidSignature);
+            }
+        }
+    }
+}
+
+/// Adds a secp256k1 key, given in uncompressed SEC-1 form, to the signer set
+/// and returns its key id.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If the key already is a signer
+/// * `MaxPlansExceeded` - If the set already holds `MAX_SIGNERS` keys
+pub(crate) fn add_secp256k1_signer(
+    env: &Env,
+    public_key: BytesN<65>,
+) -> Result<BytesN<32>, SavingsError> {
+    let key_id: BytesN<32> = env.crypto().sha256(&public_key.clone().into()).into();
+    add_signer(env, key_id.clone())?;
+    env.storage()
+        .instance()
+        .set(&MintDataKey::Secp256k1Key(key_id.clone()), &public_key);
+    Ok(key_id)
+}
+
+/// Adds a key to the signer set.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If the key already is a signer
+/// * `MaxPlansExceeded` - If the set already holds `MAX_SIGNERS` keys
+pub(crate) fn add_signer(env: &Env, public_key: BytesN<32>) -> Result<(), SavingsError> {
+    let mut signers = get_signers(env);
+    if signers.contains(&public_key) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if signers.len() >= MAX_SIGNERS {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+    signers.push_back(public_key.clone());
+    env.storage()
+        .instance()
+        .set(&MintDataKey::MintSigners, &signers);
+    env.events()
+        .publish((symbol_short!("sgnr_add"),), public_key);
+    Ok(())
+}
+
+/// Removes a key from the signer set.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidSignature` - If the key is not a signer
+/// * `InvariantViolation` - If fewer signers than the threshold would remain
+pub(crate) fn remove_signer(env: &Env, public_key: BytesN<32>) -> Result<(), SavingsError> {
+    let mut signers = get_signers(env);
+    let index = signers
+        .first_index_of(&public_key)
+        .ok_or(SavingsError::InvalidSignature)?;
+    if signers.len() - 1 < get_threshold(env) {
+        return Err(SavingsError::InvariantViolation);
+    }
+    signers.remove(index);
+    env.storage()
+        .instance()
+        .set(&MintDataKey::MintSigners, &signers);
+    env.storage()
+        .instance()
+        .remove(&MintDataKey::Secp256k1Key(public_key.clone()));
+    env.events()
+        .publish((symbol_short!("sgnr_rm"),), public_key);
+    Ok(())
+}
+
+/// Sets how many signers must sign a payload.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `threshold` is zero or exceeds the number of signers
+pub(crate) fn set_threshold(env: &Env, threshold: u32) -> Result<(), SavingsError> {
+    if threshold == 0 || threshold > get_signers(env).len() {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage()
+        .instance()
+        .set(&MintDataKey::MintThreshold, &threshold);
+    env.events()
+        .publish((symbol_short!("set_thold"),), threshold);
+    Ok(())
+}
+
+/// Returns the signer set.
+pub(crate) fn get_signers(env: &Env) -> Vec<BytesN<32>> {
+    env.storage()
+        .instance()
+        .get(&MintDataKey::MintSigners)
+        .unwrap_or_else(|| {
+            let admin_public_key: BytesN<32> = env
+                .storage()
+                .instance()
+                .get(&DataKey::AdminPublicKey)
+                .expect("Admin PK not found");
+            vec![env, admin_public_key]
+        })
+}
+
+/// Returns how many signers must sign a payload.
+pub(crate) fn get_threshold(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&MintDataKey::MintThreshold)
+        .unwrap_or(1)
+}
+
+/// Stages a new admin public key. It replaces the current one only once
+/// `confirm_rotation` proves the new key's holder can sign with it, so a
+/// mistyped key can't lock the backend out.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If the key already is a signer
+pub(crate) fn rotate_admin_key(env: &Env, new_key: BytesN<32>) -> Result<(), SavingsError> {
+    if get_signers(env).contains(&new_key) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    env.storage()
+        .instance()
+        .set(&MintDataKey::PendingAdminKey, &new_key);
+    env.events().publish((symbol_short!("key_stage"),), new_key);
+    Ok(())
+}
+
+/// Activates the staged admin key. `signature` must be the new key's
+/// signature over `rotation_message`. The new key takes the old key's place
+/// in the signer set.
+///
+/// # Errors
+/// * `MissingParameter` - If no key rotation is staged
+pub(crate) fn confirm_rotation(env: &Env, signature: BytesN<64>) -> Result<(), SavingsError> {
+    let new_key: BytesN<32> = env
+        .storage()
+        .instance()
+        .get(&MintDataKey::PendingAdminKey)
+        .ok_or(SavingsError::MissingParameter)?;
+    env.crypto()
+        .ed25519_verify(&new_key, &rotation_message(env, &new_key), &signature);
+
+    let old_key: BytesN<32> = env
+        .storage()
+        .instance()
+        .get(&DataKey::AdminPublicKey)
+        .expect("Admin PK not found");
+    let stored: Option<Vec<BytesN<32>>> = env.storage().instance().get(&MintDataKey::MintSigners);
+    if let Some(mut signers) = stored {
+        if let Some(index) = signers.first_index_of(&old_key) {
+            signers.set(index, new_key.clone());
+            env.storage()
+                .instance()
+                .set(&MintDataKey::MintSigners, &signers);
+        }
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::AdminPublicKey, &new_key);
+    env.storage()
+        .instance()
+        .remove(&MintDataKey::PendingAdminKey);
+    env.events().publish((symbol_short!("key_rot"),), new_key);
+    Ok(())
+}
+
+/// Returns the staged admin key, if a rotation is pending.
+pub(crate) fn get_pending_admin_key(env: &Env) -> Option<BytesN<32>> {
+    env.storage().instance().get(&MintDataKey::PendingAdminKey)
+}
+
+/// The bytes the new key signs to confirm a rotation
+pub(crate) fn rotation_message(env: &Env, new_key: &BytesN<32>) -> Bytes {
+    signed_message(env, symbol_short!("rotate"), new_key.clone())
+}
+
+/// Binds `payload` to its kind, the payload format, this contract and the
+/// network it runs on.
+fn signed_message<T: IntoVal<Env, Val>>(env: &Env, kind: Symbol, payload: T) -> Bytes {
+    let payload: Val = payload.into_val(env);
+    (
+        kind,
+        PAYLOAD_VERSION,
+        env.current_contract_address(),
+        env.ledger().network_id(),
+        payload,
+    )
+        .to_xdr(env)
+}
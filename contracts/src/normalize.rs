@@ -0,0 +1,87 @@
+//! Conversion between token units and the contract's canonical precision.
+//!
+//! Whitelisted assets may use any number of decimals, so every amount the
+//! contract accounts for (plan and group balances, targets, interest) is kept
+//! in `CANONICAL_DECIMALS`. An asset's decimals are recorded when it becomes
+//! supported, and amounts are converted to the token's own units only when
+//! tokens actually move. Rounding always favours the contract: deposits round
+//! up and payouts round down.
+
+use crate::errors::SavingsError;
+use crate::storage_types::DataKeyExt;
+use soroban_sdk::{symbol_short, token::TokenClient, Address, Env};
+
+/// Decimals of every amount in the contract's accounting
+pub const CANONICAL_DECIMALS: u32 = 7;
+
+/// Reads and stores the decimals of a newly supported asset.
+pub(crate) fn record_decimals(env: &Env, asset: &Address) {
+    let decimals = TokenClient::new(env, asset).decimals();
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::AssetDecimals(asset.clone()), &decimals);
+    env.events()
+        .publish((symbol_short!("asset_dec"), asset.clone()), decimals);
+}
+
+/// Returns the decimals recorded for an asset, or `CANONICAL_DECIMALS` if
+/// none were recorded.
+pub fn get_asset_decimals(env: &Env, asset: &Address) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::AssetDecimals(asset.clone()))
+        .unwrap_or(CANONICAL_DECIMALS)
+}
+
+/// Whether decimals were recorded for an asset, i.e. it has been supported
+/// at some point.
+pub(crate) fn is_recorded(env: &Env, asset: &Address) -> bool {
+    env.storage()
+        .instance()
+        .has(&DataKeyExt::AssetDecimals(asset.clone()))
+}
+
+/// Converts an amount in the asset's units to canonical precision, rounding
+/// down.
+pub(crate) fn to_canonical(env: &Env, asset: &Address, amount: i128) -> Result<i128, SavingsError> {
+    let decimals = get_asset_decimals(env, asset);
+    if decimals > CANONICAL_DECIMALS {
+        Ok(amount / scale(decimals - CANONICAL_DECIMALS)?)
+    } else {
+        amount
+            .checked_mul(scale(CANONICAL_DECIMALS - decimals)?)
+            .ok_or(SavingsError::Overflow)
+    }
+}
+
+/// Converts a canonical amount to the asset's units for a transfer into the
+/// contract, rounding up.
+pub(crate) fn to_units_in(env: &Env, asset: &Address, amount: i128) -> Result<i128, SavingsError> {
+    let decimals = get_asset_decimals(env, asset);
+    if decimals < CANONICAL_DECIMALS {
+        let scale = scale(CANONICAL_DECIMALS - decimals)?;
+        let units = amount / scale;
+        Ok(if amount % scale > 0 { units + 1 } else { units })
+    } else {
+        amount
+            .checked_mul(scale(decimals - CANONICAL_DECIMALS)?)
+            .ok_or(SavingsError::Overflow)
+    }
+}
+
+/// Converts a canonical amount to the asset's units for a transfer out of
+/// the contract, rounding down.
+pub(crate) fn to_units_out(env: &Env, asset: &Address, amount: i128) -> Result<i128, SavingsError> {
+    let decimals = get_asset_decimals(env, asset);
+    if decimals < CANONICAL_DECIMALS {
+        Ok(amount / scale(CANONICAL_DECIMALS - decimals)?)
+    } else {
+        amount
+            .checked_mul(scale(decimals - CANONICAL_DECIMALS)?)
+            .ok_or(SavingsError::Overflow)
+    }
+}
+
+fn scale(exponent: u32) -> Result<i128, SavingsError> {
+    10i128.checked_pow(exponent).ok_or(SavingsError::Overflow)
+}
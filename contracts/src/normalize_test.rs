@@ -0,0 +1,138 @@
+use crate::{NesteraContract, NesteraContractClient, PlanType};
+use soroban_sdk::{
+    contract, contractimpl, contracttype,
+    testutils::{Address as _, Ledger},
+    token::StellarAssetClient,
+    Address, BytesN, Env,
+};
+
+const YEAR: u64 = 365 * 24 * 60 * 60;
+
+#[contracttype]
+enum TokenKey {
+    Decimals,
+    Balance(Address),
+}
+
+/// Minimal token with configurable decimals
+#[contract]
+struct MockToken;
+
+#[contractimpl]
+impl MockToken {
+    pub fn __constructor(env: Env, decimals: u32) {
+        env.storage().instance().set(&TokenKey::Decimals, &decimals);
+    }
+
+    pub fn decimals(env: Env) -> u32 {
+        env.storage().instance().get(&TokenKey::Decimals).unwrap()
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&TokenKey::Balance(id))
+            .unwrap_or(0)
+    }
+
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage()
+            .instance()
+            .set(&TokenKey::Balance(to), &(balance + amount));
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        let balance = Self::balance(env.clone(), from.clone());
+        assert!(balance >= amount);
+        env.storage()
+            .instance()
+            .set(&TokenKey::Balance(from), &(balance - amount));
+        Self::mint(env, to, amount);
+    }
+}
+
+/// Contract with a 7-decimal savings token and a user holding a whitelisted
+/// token of the given decimals.
+fn setup(
+    decimals: u32,
+) -> (
+    Env,
+    NesteraContractClient<'static>,
+    Address,
+    MockTokenClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    client.initialize(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(usdc.clone()),
+    );
+
+    let token_id = env.register(MockToken, (decimals,));
+    let token = MockTokenClient::new(&env, &token_id);
+    client.add_supported_asset(&token_id);
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &usdc).mint(&user, &10_000_000);
+    token.mint(&user, &1_000_000_000_000_000_000_000);
+
+    (env, client, user, token)
+}
+
+#[test]
+fn test_decimals_recorded_at_whitelist_time() {
+    let (_env, client, _user, token) = setup(18);
+    assert_eq!(client.get_asset_decimals(&token.address), 18);
+    let usdc = client.get_savings_token().unwrap();
+    assert_eq!(client.get_asset_decimals(&usdc), 7);
+}
+
+#[test]
+fn test_high_decimal_asset_is_accounted_canonically() {
+    let (env, client, user, token) = setup(18);
+
+    // 1 whole token is 10^7 in the plan and 10^18 on the token
+    let plan_id =
+        client.create_asset_plan(&user, &PlanType::Lock(YEAR), &token.address, &10_000_000);
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        10_000_000
+    );
+    assert_eq!(token.balance(&client.address), 1_000_000_000_000_000_000);
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    let payout = client.withdraw_lock(&user, &plan_id);
+    client.withdraw_asset(&user, &token.address, &10_000_000);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(
+        client.get_asset_balance(&user, &token.address),
+        payout - 10_000_000
+    );
+}
+
+#[test]
+fn test_low_decimal_asset_rounds_in_contracts_favour() {
+    let (env, client, user, token) = setup(6);
+    let before = token.balance(&user);
+
+    // 15 canonical units cost 2 of the token's units
+    let plan_id = client.create_asset_plan(&user, &PlanType::Lock(YEAR), &token.address, &15);
+    assert_eq!(token.balance(&client.address), 2);
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    let payout = client.withdraw_lock(&user, &plan_id);
+    assert_eq!(payout, 15);
+    // Paying out 15 sends only 1 unit
+    client.withdraw_asset(&user, &token.address, &payout);
+    assert_eq!(token.balance(&user), before - 1);
+    assert_eq!(client.get_asset_balance(&user, &token.address), 0);
+}
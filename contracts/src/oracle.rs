@@ -0,0 +1,232 @@
+//! Price oracle adapter for goals with a USD target.
+//!
+//! A goal plan may carry a target in USD while its deposits stay in the
+//! plan's token. Its progress is the balance valued at the oracle's latest
+//! price, and the goal completes once that value reaches the USD target.
+//!
+//! Prices older than the configured maximum age are stale. The fallback
+//! policy then decides whether to use the last fresh price the contract
+//! read, judge the goal by its token target instead, or reject the
+//! operation.
+
+use crate::assets;
+use crate::ensure_not_paused;
+use crate::errors::SavingsError;
+use crate::storage_types::{
+    DataKey, DataKeyExt, OracleAsset, OracleConfig, OracleFallback, PlanType, PriceData,
+    SavingsPlan,
+};
+use soroban_sdk::{contractclient, symbol_short, Address, Env};
+
+/// Interface of a Reflector-style price oracle.
+#[allow(dead_code)]
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    /// Number of decimals prices are scaled by.
+    fn decimals(env: Env) -> u32;
+    /// Latest USD price of `asset`, if the oracle has one.
+    fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData>;
+}
+
+/// Sets the price oracle used for USD goals.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidTimestamp` - If `max_age` is zero
+pub fn set_price_oracle(
+    env: &Env,
+    oracle: Address,
+    max_age: u64,
+    fallback: OracleFallback,
+) -> Result<(), SavingsError> {
+    if max_age == 0 {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+    env.storage().instance().set(
+        &DataKeyExt::OracleConfig,
+        &OracleConfig {
+            oracle: oracle.clone(),
+            max_age,
+            fallback,
+        },
+    );
+    env.events()
+        .publish((symbol_short!("set_orcl"),), (oracle, max_age));
+    Ok(())
+}
+
+/// Returns the price oracle settings, if configured.
+pub fn get_price_oracle(env: &Env) -> Option<OracleConfig> {
+    env.storage().instance().get(&DataKeyExt::OracleConfig)
+}
+
+/// Gives a goal plan a target in USD, in canonical precision. The
+/// goal then completes once its balance is worth `usd_target`.
+///
+/// # Errors
+/// * `InvalidAmount` - If `usd_target` is not positive
+/// * `MissingParameter` - If no oracle is set or the plan has no token
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal plan
+/// * `PlanCompleted` - If the goal is already completed or withdrawn
+pub fn set_goal_usd_target(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    usd_target: i128,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    if usd_target <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if get_price_oracle(env).is_none() {
+        return Err(SavingsError::MissingParameter);
+    }
+    let plan = load_goal(env, &user, plan_id)?;
+    if plan.is_completed || plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if assets::get_plan_asset(env, &user, plan_id).is_none() {
+        return Err(SavingsError::MissingParameter);
+    }
+
+    env.storage().persistent().set(
+        &DataKeyExt::GoalUsdTarget(user.clone(), plan_id),
+        &usd_target,
+    );
+    env.events()
+        .publish((symbol_short!("goal_usd"), user, plan_id), usd_target);
+    Ok(())
+}
+
+/// Returns a goal's USD target, if it has one.
+pub fn get_goal_usd_target(env: &Env, user: Address, plan_id: u64) -> Option<i128> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::GoalUsdTarget(user, plan_id))
+}
+
+/// Returns what a plan's balance is worth in USD at the oracle price.
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `MissingParameter` - If no oracle is set or the plan has no token
+/// * `StalePrice` - If no usable price is available
+pub fn get_plan_usd_value(env: &Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    let asset =
+        assets::get_plan_asset(env, &user, plan_id).ok_or(SavingsError::MissingParameter)?;
+    usd_value(env, &asset, plan.balance)?.ok_or(SavingsError::StalePrice)
+}
+
+/// Completes a USD goal whose balance has come to be worth its target
+/// through a price rise rather than a deposit. Anyone may call this.
+///
+/// # Returns
+/// `Ok(true)` if the goal is completed
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal with a USD target
+/// * `PlanCompleted` - If the goal was already withdrawn
+/// * `StalePrice` - If no usable price is available under `Reject`
+pub fn refresh_usd_goal(env: &Env, user: Address, plan_id: u64) -> Result<bool, SavingsError> {
+    ensure_not_paused(env)?;
+
+    let mut plan = load_goal(env, &user, plan_id)?;
+    if plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if plan.is_completed {
+        return Ok(true);
+    }
+    let reached = usd_goal_reached(env, &user, plan_id, plan.balance)?
+        .ok_or(SavingsError::InvalidPlanConfig)?;
+    if reached {
+        plan.is_completed = true;
+        env.storage()
+            .persistent()
+            .set(&DataKey::SavingsPlan(user.clone(), plan_id), &plan);
+        env.events()
+            .publish((symbol_short!("goal_done"), user, plan_id), plan.balance);
+    }
+    Ok(reached)
+}
+
+/// Whether a goal with a USD target has reached it with `balance`.
+///
+/// # Returns
+/// `None` if the goal has no USD target, or the price is stale and the
+/// fallback is to judge by the token target
+pub(crate) fn usd_goal_reached(
+    env: &Env,
+    user: &Address,
+    plan_id: u64,
+    balance: i128,
+) -> Result<Option<bool>, SavingsError> {
+    let usd_target: i128 = match get_goal_usd_target(env, user.clone(), plan_id) {
+        Some(target) => target,
+        None => return Ok(None),
+    };
+    let asset = match assets::get_plan_asset(env, user, plan_id) {
+        Some(asset) => asset,
+        None => return Ok(None),
+    };
+    Ok(usd_value(env, &asset, balance)?.map(|value| value >= usd_target))
+}
+
+/// Values `amount` of `asset` in USD, applying the fallback policy when the
+/// price is stale.
+fn usd_value(env: &Env, asset: &Address, amount: i128) -> Result<Option<i128>, SavingsError> {
+    let config = get_price_oracle(env).ok_or(SavingsError::MissingParameter)?;
+    let oracle = PriceOracleClient::new(env, &config.oracle);
+    let now = env.ledger().timestamp();
+
+    let fresh = oracle
+        .lastprice(&OracleAsset::Stellar(asset.clone()))
+        .filter(|data| now.saturating_sub(data.timestamp) <= config.max_age);
+    let price = match fresh {
+        Some(data) => {
+            env.storage()
+                .persistent()
+                .set(&DataKeyExt::LastPrice(asset.clone()), &data);
+            data.price
+        }
+        None => match config.fallback {
+            OracleFallback::LastPrice => {
+                let last: Option<PriceData> = env
+                    .storage()
+                    .persistent()
+                    .get(&DataKeyExt::LastPrice(asset.clone()));
+                last.ok_or(SavingsError::StalePrice)?.price
+            }
+            OracleFallback::TokenTarget => return Ok(None),
+            OracleFallback::Reject => return Err(SavingsError::StalePrice),
+        },
+    };
+
+    let scale = 10i128
+        .checked_pow(oracle.decimals())
+        .ok_or(SavingsError::Overflow)?;
+    let value = amount.checked_mul(price).ok_or(SavingsError::Overflow)? / scale;
+    Ok(Some(value))
+}
+
+fn load_goal(env: &Env, user: &Address, plan_id: u64) -> Result<SavingsPlan, SavingsError> {
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    match plan.plan_type {
+        PlanType::Goal(..) => Ok(plan),
+        _ => Err(SavingsError::InvalidPlanConfig),
+    }
+}
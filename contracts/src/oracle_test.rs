@@ -0,0 +1,137 @@
+use crate::{
+    NesteraContract, NesteraContractClient, OracleAsset, OracleFallback, PlanType, PriceData,
+    SavingsError,
+};
+use soroban_sdk::{
+    contract, contractimpl, contracttype,
+    testutils::{Address as _, Ledger},
+    token::StellarAssetClient,
+    Address, BytesN, Env, Symbol,
+};
+
+const HOUR: u64 = 60 * 60;
+
+#[contracttype]
+enum OracleKey {
+    Price(OracleAsset),
+}
+
+/// Oracle stand-in with prices set by the test, in 14 decimals
+#[contract]
+struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, asset: OracleAsset, price: i128, timestamp: u64) {
+        env.storage()
+            .instance()
+            .set(&OracleKey::Price(asset), &PriceData { price, timestamp });
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
+
+    pub fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData> {
+        env.storage().instance().get(&OracleKey::Price(asset))
+    }
+}
+
+/// A goal of 1_000 tokens with a USD target of 500, with the token at $0.25
+fn setup(fallback: OracleFallback) -> (Env, NesteraContractClient<'static>, Address, u64) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 10 * HOUR);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    client.initialize(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+
+    let oracle_id = env.register(MockOracle, ());
+    MockOracleClient::new(&env, &oracle_id).set_price(
+        &OracleAsset::Stellar(token),
+        &25_000_000_000_000,
+        &(10 * HOUR),
+    );
+    client.set_price_oracle(&oracle_id, &HOUR, &fallback);
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &client.get_savings_token().unwrap()).mint(&user, &10_000);
+    let category = client.add_goal_category(&Symbol::new(&env, "Travel"));
+    let plan_id = client.create_savings_plan(&user, &PlanType::Goal(category, 1_000, 1), &0);
+    client.set_goal_usd_target(&user, &plan_id, &500);
+
+    (env, client, user, plan_id)
+}
+
+fn set_price(env: &Env, client: &NesteraContractClient, price: i128, timestamp: u64) {
+    let config = client.get_price_oracle().unwrap();
+    let token = client.get_savings_token().unwrap();
+    MockOracleClient::new(env, &config.oracle).set_price(
+        &OracleAsset::Stellar(token),
+        &price,
+        &timestamp,
+    );
+}
+
+#[test]
+fn test_usd_goal_completes_on_value_not_token_amount() {
+    let (_env, client, user, plan_id) = setup(OracleFallback::Reject);
+
+    // 1_000 tokens reach the token target but are only worth $250
+    assert!(!client.deposit_goal(&user, &plan_id, &1_000));
+    assert_eq!(client.get_plan_usd_value(&user, &plan_id), 250);
+    assert!(client.deposit_goal(&user, &plan_id, &1_000));
+    assert!(
+        client
+            .get_savings_plan(&user, &plan_id)
+            .unwrap()
+            .is_completed
+    );
+}
+
+#[test]
+fn test_refresh_usd_goal_after_price_rise() {
+    let (env, client, user, plan_id) = setup(OracleFallback::Reject);
+    client.deposit_goal(&user, &plan_id, &1_500);
+    assert!(!client.refresh_usd_goal(&user, &plan_id));
+
+    set_price(&env, &client, 50_000_000_000_000, 10 * HOUR);
+    assert!(client.refresh_usd_goal(&user, &plan_id));
+    assert!(
+        client
+            .get_savings_plan(&user, &plan_id)
+            .unwrap()
+            .is_completed
+    );
+}
+
+#[test]
+fn test_stale_price_fallback_policies() {
+    let (env, client, user, plan_id) = setup(OracleFallback::Reject);
+    env.ledger().with_mut(|li| li.timestamp = 12 * HOUR);
+    assert_eq!(
+        client.try_deposit_goal(&user, &plan_id, &100),
+        Err(Ok(SavingsError::StalePrice))
+    );
+
+    // Judged by the token target instead
+    let (env, client, user, plan_id) = setup(OracleFallback::TokenTarget);
+    env.ledger().with_mut(|li| li.timestamp = 12 * HOUR);
+    assert!(client.deposit_goal(&user, &plan_id, &1_000));
+
+    // The last fresh price read is reused
+    let (env, client, user, plan_id) = setup(OracleFallback::LastPrice);
+    client.deposit_goal(&user, &plan_id, &1_000);
+    set_price(&env, &client, 100_000_000_000_000, 10 * HOUR);
+    env.ledger().with_mut(|li| li.timestamp = 12 * HOUR);
+    assert!(!client.deposit_goal(&user, &plan_id, &100));
+    assert_eq!(client.get_plan_usd_value(&user, &plan_id), 275);
+}
@@ -0,0 +1,327 @@
+//! Penalty pool shared among savers who keep their locks.
+//!
+//! When configured, a share of every early break fee is paid into the pool
+//! instead of to the fee recipient. Once an epoch has passed, anyone may
+//! distribute the pool pro-rata to the principal held in active lock plans at
+//! that moment. A user who breaks a lock loses that plan's weight before the
+//! next distribution, so penalties go to the savers who stayed.
+//!
+//! Distribution uses a running "penalties per locked unit" index, so it costs
+//! the same no matter how many savers there are. Each user's share is settled
+//! whenever their locked principal changes and paid to their Flexi balance by
+//! `claim_penalty_share`.
+//!
+//! The admin may instead set a routing policy, which splits every early break
+//! fee and late group penalty in the savings token between the treasury, the
+//! pool and a burn.
+
+use crate::assets;
+use crate::ensure_not_paused;
+use crate::errors::SavingsError;
+use crate::fees::{self, FeeKind};
+use crate::flexi;
+use crate::storage_types::{
+    ControlDataKey, DataKeyExt, PenaltyPool, PenaltyPoolConfig, PenaltyRouting, PenaltyShare,
+};
+use soroban_sdk::{symbol_short, Address, Env};
+
+/// Fixed-point scale of `PenaltyPool.acc_per_share`
+pub const SCALE: i128 = 1_000_000_000_000;
+
+/// Sets the share of early break fees paid into the penalty pool and the
+/// minimum time between distributions. A zero share sends every fee to the
+/// fee recipient again; penalties already pooled stay in the pool.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `share_bps` exceeds 10_000
+/// * `InvalidTimestamp` - If `epoch_length` is zero
+pub fn set_penalty_pool(env: &Env, share_bps: u32, epoch_length: u64) -> Result<(), SavingsError> {
+    if share_bps > 10_000 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if epoch_length == 0 {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    env.storage().instance().set(
+        &DataKeyExt::PenaltyPoolConfig,
+        &PenaltyPoolConfig {
+            share_bps,
+            epoch_length,
+        },
+    );
+    if !env.storage().persistent().has(&DataKeyExt::PenaltyPool) {
+        env.storage()
+            .persistent()
+            .set(&DataKeyExt::PenaltyPool, &get_penalty_pool(env));
+    }
+    env.events()
+        .publish((symbol_short!("set_ppool"),), (share_bps, epoch_length));
+    Ok(())
+}
+
+/// Returns the penalty pool settings, if configured.
+pub fn get_penalty_pool_config(env: &Env) -> Option<PenaltyPoolConfig> {
+    env.storage().instance().get(&DataKeyExt::PenaltyPoolConfig)
+}
+
+/// Sets how penalties are split between the treasury, the penalty pool and a
+/// burn. Once set, penalties no longer go to the fee recipient, and late
+/// group penalties are no longer shared among on-time members.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If the shares don't sum to 10_000
+/// * `MissingParameter` - If a pool share is set but the pool is not
+///   configured
+pub fn set_penalty_routing(env: &Env, routing: PenaltyRouting) -> Result<(), SavingsError> {
+    let total = routing.treasury_bps as u64 + routing.pool_bps as u64 + routing.burn_bps as u64;
+    if total != 10_000 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if routing.pool_bps > 0 && get_penalty_pool_config(env).is_none() {
+        return Err(SavingsError::MissingParameter);
+    }
+
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::PenaltyRouting, &routing);
+    env.events().publish(
+        (symbol_short!("set_prte"),),
+        (routing.treasury_bps, routing.pool_bps, routing.burn_bps),
+    );
+    Ok(())
+}
+
+/// Returns the penalty routing policy, if set.
+pub fn get_penalty_routing(env: &Env) -> Option<PenaltyRouting> {
+    env.storage()
+        .instance()
+        .get(&ControlDataKey::PenaltyRouting)
+}
+
+/// Returns the penalty pool balances and current epoch.
+pub fn get_penalty_pool(env: &Env) -> PenaltyPool {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::PenaltyPool)
+        .unwrap_or(PenaltyPool {
+            epoch: 0,
+            epoch_start: env.ledger().timestamp(),
+            pending: 0,
+            acc_per_share: 0,
+            total_locked: 0,
+        })
+}
+
+/// Routes a penalty `user` paid in the savings token.
+///
+/// Under a routing policy the penalty is split between the treasury, the pool
+/// and a burn, and the user's referrer shares the treasury's part. Otherwise
+/// the pool takes its configured share, the referrer their share of what is
+/// left, and the rest is returned.
+///
+/// # Returns
+/// The amount the caller pays to the fee recipient
+pub(crate) fn route_penalty(
+    env: &Env,
+    user: &Address,
+    penalty: i128,
+) -> Result<i128, SavingsError> {
+    if penalty <= 0 {
+        return Ok(0);
+    }
+    fees::record_fee(
+        env,
+        FeeKind::Penalty,
+        &assets::get_savings_token(env),
+        penalty,
+    )?;
+
+    if let Some(routing) = get_penalty_routing(env) {
+        let pooled = share_of(penalty, routing.pool_bps)?;
+        let burned = share_of(penalty, routing.burn_bps)?;
+        let to_treasury = penalty - pooled - burned;
+        add_to_pool(env, pooled)?;
+        assets::burn(env, burned)?;
+        let referred = fees::pay_referral_share(env, user, to_treasury)?;
+        fees::credit_treasury(env, to_treasury - referred)?;
+        env.events().publish(
+            (symbol_short!("pen_split"), user.clone()),
+            (to_treasury, pooled, burned),
+        );
+        return Ok(0);
+    }
+
+    let pooled = match get_penalty_pool_config(env) {
+        Some(config) => share_of(penalty, config.share_bps)?,
+        None => 0,
+    };
+    add_to_pool(env, pooled)?;
+    let fee = penalty - pooled;
+    Ok(fee - fees::pay_referral_share(env, user, fee)?)
+}
+
+fn share_of(amount: i128, bps: u32) -> Result<i128, SavingsError> {
+    Ok(amount
+        .checked_mul(bps as i128)
+        .ok_or(SavingsError::Overflow)?
+        / 10_000)
+}
+
+/// Adds `pooled` to the penalties waiting for the next distribution.
+fn add_to_pool(env: &Env, pooled: i128) -> Result<(), SavingsError> {
+    if pooled == 0 {
+        return Ok(());
+    }
+
+    let mut pool = get_penalty_pool(env);
+    pool.pending = pool
+        .pending
+        .checked_add(pooled)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::PenaltyPool, &pool);
+    env.events()
+        .publish((symbol_short!("ppool_in"), pool.epoch), pooled);
+
+    Ok(())
+}
+
+/// Records a change to the principal `user` holds in active lock plans,
+/// settling what they have earned from the pool at their old weight first.
+pub(crate) fn update_locked(env: &Env, user: &Address, delta: i128) {
+    if delta == 0 {
+        return;
+    }
+    let mut pool = get_penalty_pool(env);
+    let mut share = settle(env, user, &pool);
+
+    share.locked = share.locked.saturating_add(delta).max(0);
+    share.debt = accrued(share.locked, pool.acc_per_share);
+    pool.total_locked = pool.total_locked.saturating_add(delta).max(0);
+
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::PenaltyShare(user.clone()), &share);
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::PenaltyPool, &pool);
+}
+
+/// Shares the penalties collected this epoch among savers pro-rata to their
+/// locked principal and starts a new epoch.
+///
+/// Anyone (typically a keeper) may trigger the distribution. If nothing is
+/// locked, the penalties carry over to the next epoch.
+///
+/// # Returns
+/// `Ok(i128)` - The amount distributed
+///
+/// # Errors
+/// * `MissingParameter` - If the penalty pool is not configured
+/// * `TooEarly` - If the current epoch has not ended
+pub fn distribute_penalty_pool(env: &Env) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+
+    let config = get_penalty_pool_config(env).ok_or(SavingsError::MissingParameter)?;
+    let mut pool = get_penalty_pool(env);
+    let now = env.ledger().timestamp();
+    if now < pool.epoch_start.saturating_add(config.epoch_length) {
+        return Err(SavingsError::TooEarly);
+    }
+
+    let mut distributed = 0;
+    if pool.total_locked > 0 && pool.pending > 0 {
+        let increment = pool
+            .pending
+            .checked_mul(SCALE)
+            .ok_or(SavingsError::Overflow)?
+            / pool.total_locked;
+        // Dust lost to rounding stays pending for the next epoch
+        distributed = increment
+            .checked_mul(pool.total_locked)
+            .ok_or(SavingsError::Overflow)?
+            / SCALE;
+        pool.acc_per_share = pool
+            .acc_per_share
+            .checked_add(increment)
+            .ok_or(SavingsError::Overflow)?;
+        pool.pending -= distributed;
+    }
+
+    env.events()
+        .publish((symbol_short!("ppool_dst"), pool.epoch), distributed);
+    pool.epoch += 1;
+    pool.epoch_start = now;
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::PenaltyPool, &pool);
+
+    Ok(distributed)
+}
+
+/// Pays the user's share of distributed penalties to their Flexi balance.
+///
+/// # Returns
+/// `Ok(i128)` - The amount credited
+///
+/// # Errors
+/// * `InsufficientBalance` - If there is nothing to claim
+pub fn claim_penalty_share(env: &Env, user: Address) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    let pool = get_penalty_pool(env);
+    let mut share = settle(env, &user, &pool);
+    let amount = share.owed;
+    if amount <= 0 {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    share.owed = 0;
+    share.debt = accrued(share.locked, pool.acc_per_share);
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::PenaltyShare(user.clone()), &share);
+    flexi::credit_flexi(env, &user, amount)?;
+
+    env.events()
+        .publish((symbol_short!("ppool_clm"), user), amount);
+
+    Ok(amount)
+}
+
+/// Returns the user's stake in the penalty pool, including penalties
+/// distributed to them but not yet claimed.
+pub fn get_penalty_share(env: &Env, user: Address) -> PenaltyShare {
+    let pool = get_penalty_pool(env);
+    let mut share = settle(env, &user, &pool);
+    share.debt = accrued(share.locked, pool.acc_per_share);
+    share
+}
+
+/// Loads the user's share with everything distributed since it was last
+/// settled moved into `owed`.
+fn settle(env: &Env, user: &Address, pool: &PenaltyPool) -> PenaltyShare {
+    let mut share: PenaltyShare = env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::PenaltyShare(user.clone()))
+        .unwrap_or(PenaltyShare {
+            locked: 0,
+            debt: 0,
+            owed: 0,
+        });
+    let earned = accrued(share.locked, pool.acc_per_share).saturating_sub(share.debt);
+    share.owed = share.owed.saturating_add(earned.max(0));
+    share
+}
+
+fn accrued(locked: i128, acc_per_share: i128) -> i128 {
+    locked.saturating_mul(acc_per_share) / SCALE
+}
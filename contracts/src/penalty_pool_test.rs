@@ -0,0 +1,159 @@
+use crate::{NesteraContract, NesteraContractClient, PenaltyRouting, PlanType, SavingsError};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+const YEAR: u64 = 365 * DAY;
+
+fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &admin_pk, &None);
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+
+    (env, client, user)
+}
+
+fn new_user(env: &Env, client: &NesteraContractClient) -> Address {
+    let user = Address::generate(env);
+    client.initialize_user(&user);
+    user
+}
+
+#[test]
+fn test_penalties_are_shared_among_savers_who_keep_their_locks() {
+    let (env, client, user) = setup();
+    let other = new_user(&env, &client);
+    let breaker = new_user(&env, &client);
+    let treasury = Address::generate(&env);
+    client.set_fee_recipient(&treasury);
+    client.set_early_break_fee_bps(&1_000);
+    client.set_penalty_pool(&5_000, &(7 * DAY));
+
+    client.create_savings_plan(&user, &PlanType::Lock(YEAR), &1_000);
+    client.create_savings_plan(&other, &PlanType::Lock(YEAR), &3_000);
+    let broken = client.create_savings_plan(&breaker, &PlanType::Lock(YEAR), &10_000);
+
+    // 1_000 fee: half to the pool, half to the treasury
+    assert_eq!(client.early_withdraw_lock(&breaker, &broken), 9_000);
+    assert_eq!(client.get_treasury_balance(), 500);
+    let pool = client.get_penalty_pool();
+    assert_eq!(pool.pending, 500);
+    assert_eq!(pool.total_locked, 4_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 7 * DAY);
+    assert_eq!(client.distribute_penalty_pool(), 500);
+    assert_eq!(client.get_penalty_pool().epoch, 1);
+    assert_eq!(client.get_penalty_share(&breaker).owed, 0);
+    assert_eq!(client.get_penalty_share(&user).owed, 125);
+
+    assert_eq!(client.claim_penalty_share(&other), 375);
+    assert_eq!(client.get_flexi_balance(&other), 375);
+    assert_eq!(client.get_user(&other).total_balance, 3_375);
+    assert_eq!(client.get_penalty_share(&other).owed, 0);
+}
+
+#[test]
+fn test_distribute_penalty_pool_waits_for_epoch_end() {
+    let (env, client, _) = setup();
+    assert_eq!(
+        client.try_distribute_penalty_pool(),
+        Err(Ok(SavingsError::MissingParameter))
+    );
+
+    client.set_penalty_pool(&5_000, &(7 * DAY));
+    env.ledger().with_mut(|li| li.timestamp = 7 * DAY - 1);
+    assert_eq!(
+        client.try_distribute_penalty_pool(),
+        Err(Ok(SavingsError::TooEarly))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 7 * DAY);
+    assert_eq!(client.distribute_penalty_pool(), 0);
+    assert_eq!(
+        client.try_distribute_penalty_pool(),
+        Err(Ok(SavingsError::TooEarly))
+    );
+}
+
+#[test]
+fn test_penalties_carry_over_when_nothing_is_locked() {
+    let (env, client, user) = setup();
+    client.set_early_break_fee_bps(&1_000);
+    client.set_penalty_pool(&10_000, &DAY);
+
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &2_000);
+    client.early_withdraw_lock(&user, &plan_id);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+    assert_eq!(client.distribute_penalty_pool(), 0);
+    assert_eq!(client.get_penalty_pool().pending, 200);
+
+    // A lock made after the breach collects the carried-over penalties
+    let saver = new_user(&env, &client);
+    client.create_savings_plan(&saver, &PlanType::Lock(YEAR), &500);
+    env.ledger().with_mut(|li| li.timestamp = 2 * DAY);
+    assert_eq!(client.distribute_penalty_pool(), 200);
+    assert_eq!(client.get_penalty_share(&saver).owed, 200);
+}
+
+#[test]
+fn test_withdrawn_locks_drop_out_of_the_pool() {
+    let (env, client, user) = setup();
+    let breaker = new_user(&env, &client);
+    client.set_early_break_fee_bps(&1_000);
+    client.set_penalty_pool(&10_000, &DAY);
+
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(2 * DAY), &1_000);
+    let broken = client.create_savings_plan(&breaker, &PlanType::Lock(YEAR), &1_000);
+    client.early_withdraw_lock(&breaker, &broken);
+
+    env.ledger().with_mut(|li| li.timestamp = 2 * DAY);
+    client.withdraw_lock(&user, &plan_id);
+    assert_eq!(client.get_penalty_pool().total_locked, 0);
+    // Withdrawn before the distribution, so nothing is shared out
+    assert_eq!(client.distribute_penalty_pool(), 0);
+    assert_eq!(
+        client.try_claim_penalty_share(&user),
+        Err(Ok(SavingsError::InsufficientBalance))
+    );
+}
+
+#[test]
+fn test_penalty_routing_splits_break_fees() {
+    let (env, client, user) = setup();
+    let treasury = Address::generate(&env);
+    client.set_fee_recipient(&treasury);
+    client.set_early_break_fee_bps(&1_000);
+
+    let routing = |treasury_bps, pool_bps, burn_bps| PenaltyRouting {
+        treasury_bps,
+        pool_bps,
+        burn_bps,
+    };
+    assert_eq!(
+        client.try_set_penalty_routing(&routing(5_000, 3_000, 1_000)),
+        Err(Ok(SavingsError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_set_penalty_routing(&routing(5_000, 3_000, 2_000)),
+        Err(Ok(SavingsError::MissingParameter))
+    );
+    client.set_penalty_pool(&0, &(7 * DAY));
+    client.set_penalty_routing(&routing(5_000, 3_000, 2_000));
+
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &10_000);
+    assert_eq!(client.early_withdraw_lock(&user, &plan_id), 9_000);
+    assert_eq!(client.get_treasury_balance(), 500);
+    assert_eq!(client.get_penalty_pool().pending, 300);
+    assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+}
@@ -0,0 +1,2150 @@
+use crate::assets;
+use crate::config;
+use crate::errors::SavingsError;
+use crate::fees;
+use crate::flexi;
+use crate::group::MAX_PAGE_SIZE;
+use crate::interest;
+use crate::oracle;
+use crate::penalty_pool;
+use crate::rates;
+use crate::storage_types::{
+    DataKey, DataKeyExt, GoalBonusConfig, GoalCategory, GoalMilestone, GoalProgress,
+    IdleFundsPolicy, LockMaturityPolicy, PauseScope, PlanFreeze, PlanKind, PlanType,
+    RequiredContribution, RoundupGoal, SavingsPlan, User,
+};
+use crate::users;
+use crate::{ensure_not_frozen, ensure_not_paused, ensure_not_paused_for};
+use soroban_sdk::{symbol_short, Address, Env, String, Symbol, Vec};
+
+/// Maximum number of locks a single ladder may create
+pub const MAX_LADDER_TRANCHES: u32 = 24;
+
+/// Maximum length of a plan label in bytes (an emoji takes up to 4)
+pub const MAX_PLAN_LABEL_LEN: u32 = 32;
+
+const DAY: u64 = 24 * 60 * 60;
+const WEEK: u64 = 7 * DAY;
+const MONTH: u64 = 30 * DAY;
+
+/// Splits a deposit into `tranches` Lock plans maturing `interval` seconds
+/// apart, the first one `interval` seconds from now.
+///
+/// The amount, less the deposit fee, is split evenly; any remainder goes to
+/// the last tranche.
+///
+/// # Returns
+/// `Ok(Vec<u64>)` - The created plan IDs, earliest maturity first
+///
+/// # Errors
+/// * `UserNotFound` - If the user has no account
+/// * `InvalidAmount` - If the amount can't give every tranche a positive balance
+/// * `InvalidPlanConfig` - If `tranches` is zero or above `MAX_LADDER_TRANCHES`
+/// * `InvalidTimestamp` - If `interval` is zero
+pub fn create_lock_ladder(
+    env: &Env,
+    user: Address,
+    total_amount: i128,
+    tranches: u32,
+    interval: u64,
+) -> Result<Vec<u64>, SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+
+    if !users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+    if tranches == 0 || tranches > MAX_LADDER_TRANCHES {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if interval == 0 {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+    let fee = fees::collect_transfer_fee(env, &user, total_amount, true)?;
+    let net_amount = total_amount - fee;
+    if net_amount < tranches as i128 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let tranche_amount = net_amount / tranches as i128;
+    let remainder = net_amount % tranches as i128;
+    let now = env.ledger().timestamp();
+
+    let mut plan_ids = Vec::new(env);
+    for i in 1..=tranches {
+        let until = interval
+            .checked_mul(i as u64)
+            .and_then(|offset| now.checked_add(offset))
+            .ok_or(SavingsError::Overflow)?;
+        let amount = if i == tranches {
+            tranche_amount + remainder
+        } else {
+            tranche_amount
+        };
+        plan_ids.push_back(store_new_plan(env, &user, PlanType::Lock(until), amount));
+    }
+
+    assets::pull(env, &user, total_amount)?;
+
+    env.events()
+        .publish((symbol_short!("lock_lad"), user), plan_ids.clone());
+
+    Ok(plan_ids)
+}
+
+/// Moves part of a user's Flexi balance into a new Lock plan.
+///
+/// The funds never leave the contract, so `User.total_balance` is unchanged.
+///
+/// # Returns
+/// `Ok(u64)` - The new plan ID
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InvalidTimestamp` - If `lock_duration` is zero
+/// * `UserNotFound` - If the user has no account
+/// * `InsufficientBalance` - If the Flexi balance can't cover `amount`
+pub fn convert_flexi_to_lock(
+    env: &Env,
+    user: Address,
+    amount: i128,
+    lock_duration: u64,
+) -> Result<u64, SavingsError> {
+    ensure_not_paused(env)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if lock_duration == 0 {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+    let until = env
+        .ledger()
+        .timestamp()
+        .checked_add(lock_duration)
+        .ok_or(SavingsError::Overflow)?;
+
+    flexi::debit_flexi(env, &user, amount)?;
+    let plan_id = store_new_plan(env, &user, PlanType::Lock(until), amount);
+
+    env.events()
+        .publish((symbol_short!("flx_lock"), user, plan_id), amount);
+
+    Ok(plan_id)
+}
+
+/// Withdraws a matured `PlanType::Lock` savings plan.
+///
+/// The principal plus the interest earned over the lock term is credited to
+/// the user's Flexi balance and the plan is marked withdrawn. Funds left in
+/// the plan past the maturity grace period earn the idle rate of the
+/// `LockMaturityPolicy` on top.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - The plan owner (must authorize)
+/// * `plan_id` - The savings plan ID
+///
+/// # Returns
+/// `Ok(i128)` - The amount credited to the user
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a lock plan
+/// * `PlanCompleted` - If the plan was already withdrawn
+/// * `PlanLocked` - If the lock has not yet expired
+pub fn withdraw_lock(env: &Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+    ensure_plan_not_frozen(env, &user, plan_id)?;
+
+    let (plan, until) = load_lock_plan(env, &user, plan_id)?;
+    if env.ledger().timestamp() < until {
+        return Err(SavingsError::PlanLocked);
+    }
+
+    let policy = get_lock_maturity_policy(env);
+    let payout = settle_matured_lock(env, &user, plan, until, &policy)?;
+
+    env.events()
+        .publish((symbol_short!("lock_wd"), user, plan_id), payout);
+
+    Ok(payout)
+}
+
+/// Sweeps a matured lock plan into the owner's Flexi balance once its grace
+/// period has passed, when the idle-funds policy is `SweepToFlexi`.
+///
+/// Anyone (typically a keeper) may trigger the sweep.
+///
+/// # Returns
+/// `Ok(i128)` - The amount credited to the user
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a lock plan, or idle funds are
+///   not swept under the current policy
+/// * `PlanCompleted` - If the plan was already withdrawn
+/// * `TooEarly` - If the plan is still locked or within its grace period
+pub fn sweep_matured_lock(env: &Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+    ensure_plan_not_frozen(env, &user, plan_id)?;
+
+    let (plan, until) = load_lock_plan(env, &user, plan_id)?;
+    let policy = get_lock_maturity_policy(env);
+    if policy.idle_policy != IdleFundsPolicy::SweepToFlexi {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if env.ledger().timestamp() <= until.saturating_add(policy.grace_period) {
+        return Err(SavingsError::TooEarly);
+    }
+
+    let payout = settle_matured_lock(env, &user, plan, until, &policy)?;
+
+    env.events()
+        .publish((symbol_short!("lock_swp"), user, plan_id), payout);
+
+    Ok(payout)
+}
+
+/// Sets how matured lock plans are treated.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidInterestRate` - If the reduced idle rate exceeds 10_000 bps
+pub fn set_lock_maturity_policy(env: &Env, policy: LockMaturityPolicy) -> Result<(), SavingsError> {
+    if let IdleFundsPolicy::ReducedRate(rate) = policy.idle_policy {
+        if rate > 10_000 {
+            return Err(SavingsError::InvalidInterestRate);
+        }
+    }
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::LockMaturityPolicy, &policy);
+    env.events()
+        .publish((symbol_short!("set_lmat"),), policy.grace_period);
+    Ok(())
+}
+
+/// Returns the matured lock policy. By default there is no grace period and
+/// idle funds stop earning at maturity.
+pub fn get_lock_maturity_policy(env: &Env) -> LockMaturityPolicy {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::LockMaturityPolicy)
+        .unwrap_or(LockMaturityPolicy {
+            grace_period: 0,
+            idle_policy: IdleFundsPolicy::ReducedRate(0),
+        })
+}
+
+/// Breaks a `PlanType::Lock` savings plan before it matures.
+///
+/// No interest is paid. The early break fee (`early_break_fee_bps`, shared with
+/// Goal Save) is deducted from the principal and routed as in
+/// `pay_out_early`; the remainder goes to the user's Flexi balance.
+///
+/// # Returns
+/// `Ok(i128)` - The amount credited to the user after the penalty
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a lock plan
+/// * `PlanCompleted` - If the plan was already withdrawn
+/// * `TooLate` - If the lock has already matured (use `withdraw_lock`)
+pub fn early_withdraw_lock(env: &Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+    ensure_plan_not_frozen(env, &user, plan_id)?;
+
+    let (mut plan, until) = load_lock_plan(env, &user, plan_id)?;
+    if env.ledger().timestamp() >= until {
+        return Err(SavingsError::TooLate);
+    }
+
+    update_pool_weight(env, &user, plan_id, -plan.balance);
+    plan.is_withdrawn = true;
+    plan.last_withdraw = env.ledger().timestamp();
+    env.storage()
+        .persistent()
+        .set(&DataKey::SavingsPlan(user.clone(), plan_id), &plan);
+
+    let (net_amount, penalty) = pay_out_early(env, &user, plan_id, plan.balance)?;
+
+    env.events().publish(
+        (symbol_short!("lock_brk"), user, plan_id),
+        (net_amount, penalty),
+    );
+
+    Ok(net_amount)
+}
+
+/// Adds funds to an active `PlanType::Lock` savings plan.
+///
+/// The plan's `start_time` moves to the balance-weighted average of the old
+/// start and now, so the top-up only earns interest from the time it was
+/// deposited while the unlock time stays the same.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a lock plan
+/// * `PlanCompleted` - If the plan was already withdrawn
+/// * `TooLate` - If the lock has already matured
+pub fn deposit_lock(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+    let fee = collect_plan_transfer_fee(env, &user, plan_id, amount, true)?;
+    add_to_lock(env, user.clone(), plan_id, amount - fee)?;
+    assets::transfer_in(
+        env,
+        &assets::get_plan_asset(env, &user, plan_id),
+        &user,
+        amount,
+    )
+}
+
+fn add_to_lock(env: &Env, user: Address, plan_id: u64, amount: i128) -> Result<(), SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let (mut plan, until) = load_lock_plan(env, &user, plan_id)?;
+    let now = env.ledger().timestamp();
+    if now >= until {
+        return Err(SavingsError::TooLate);
+    }
+
+    let new_balance = plan
+        .balance
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    let weighted_start = (plan.start_time as i128)
+        .checked_mul(plan.balance)
+        .and_then(|old| old.checked_add((now as i128).checked_mul(amount)?))
+        .ok_or(SavingsError::Overflow)?
+        / new_balance;
+
+    plan.start_time = weighted_start as u64;
+    plan.balance = new_balance;
+    plan.last_deposit = now;
+    env.storage()
+        .persistent()
+        .set(&DataKey::SavingsPlan(user.clone(), plan_id), &plan);
+    update_pool_weight(env, &user, plan_id, amount);
+
+    env.events()
+        .publish((symbol_short!("lock_dep"), user, plan_id), amount);
+
+    Ok(())
+}
+
+/// Extends an active `PlanType::Lock` savings plan to a later unlock time.
+///
+/// The new unlock time is stored in the plan. If the rate tier for the
+/// extended term (in whole days from the plan's start) pays more than the
+/// plan's current rate, the plan moves up to that rate.
+///
+/// # Returns
+/// `Ok(u32)` - The plan's interest rate after the extension
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a lock plan
+/// * `PlanCompleted` - If the plan was already withdrawn
+/// * `TooLate` - If the lock has already matured
+/// * `InvalidTimestamp` - If `new_until` is not after the current unlock time
+pub fn extend_lock(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    new_until: u64,
+) -> Result<u32, SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    let (mut plan, until) = load_lock_plan(env, &user, plan_id)?;
+    if env.ledger().timestamp() >= until {
+        return Err(SavingsError::TooLate);
+    }
+    if new_until <= until {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    let term_days = new_until.saturating_sub(plan.start_time) / 86_400;
+    let tier_rate = rates::lock_tier_rate(env, term_days);
+    if tier_rate > plan.interest_rate {
+        plan.interest_rate = tier_rate;
+    }
+    plan.plan_type = PlanType::Lock(new_until);
+    env.storage()
+        .persistent()
+        .set(&DataKey::SavingsPlan(user.clone(), plan_id), &plan);
+
+    env.events().publish(
+        (symbol_short!("lock_ext"), user, plan_id),
+        (until, new_until),
+    );
+
+    Ok(plan.interest_rate)
+}
+
+/// Deposits into a `PlanType::Goal` savings plan.
+///
+/// The plan is marked completed once its balance reaches the goal's target;
+/// completed goals accept no further deposits.
+///
+/// # Returns
+/// `Ok(bool)` - Whether the goal is now completed
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal plan
+/// * `PlanCompleted` - If the goal was already reached or withdrawn
+pub fn deposit_goal(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    amount: i128,
+) -> Result<bool, SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+    let fee = collect_plan_transfer_fee(env, &user, plan_id, amount, true)?;
+    let completed = add_to_goal(env, user.clone(), plan_id, amount - fee)?;
+    assets::transfer_in(
+        env,
+        &assets::get_plan_asset(env, &user, plan_id),
+        &user,
+        amount,
+    )?;
+    Ok(completed)
+}
+
+fn add_to_goal(env: &Env, user: Address, plan_id: u64, amount: i128) -> Result<bool, SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let plan = credit_goal(env, &user, plan_id, amount)?;
+
+    env.events().publish(
+        (symbol_short!("goal_dep"), user, plan_id),
+        (amount, plan.balance),
+    );
+
+    Ok(plan.is_completed)
+}
+
+/// Creates a `PlanType::Goal` savings plan, optionally with a date by which
+/// the target should be reached.
+///
+/// # Returns
+/// `Ok(u64)` - The new plan ID
+///
+/// # Errors
+/// * `InvalidAmount` - If the target is not positive or the deposit negative
+/// * `InvalidTimestamp` - If `target_date` is not in the future
+/// * `InvalidPlanConfig` - If the category is not registered
+pub fn create_goal_plan(
+    env: &Env,
+    user: Address,
+    category_id: u32,
+    target_amount: i128,
+    contribution_type: u32,
+    initial_deposit: i128,
+    target_date: Option<u64>,
+) -> Result<u64, SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+
+    if target_amount <= 0 || initial_deposit < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if let Some(date) = target_date {
+        if date <= env.ledger().timestamp() {
+            return Err(SavingsError::InvalidTimestamp);
+        }
+    }
+
+    let plan_type = PlanType::Goal(category_id, target_amount, contribution_type);
+    ensure_valid_plan_type(env, &plan_type)?;
+    let fee = fees::collect_transfer_fee(env, &user, initial_deposit, true)?;
+    let plan_id = store_new_plan(env, &user, plan_type, initial_deposit - fee);
+    assets::pull(env, &user, initial_deposit)?;
+    if initial_deposit - fee >= target_amount {
+        let plan_key = DataKey::SavingsPlan(user.clone(), plan_id);
+        let mut plan: SavingsPlan = env.storage().persistent().get(&plan_key).unwrap();
+        plan.is_completed = true;
+        env.storage().persistent().set(&plan_key, &plan);
+    }
+    if let Some(date) = target_date {
+        env.storage()
+            .persistent()
+            .set(&DataKeyExt::GoalTargetDate(user, plan_id), &date);
+    }
+
+    Ok(plan_id)
+}
+
+/// Computes how much a goal plan still needs per week and per month to reach
+/// its target by its target date. Months are counted as 30 days.
+///
+/// Once the target date has passed, the whole remainder is due now.
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal plan or has no target date
+pub fn get_required_contribution(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+) -> Result<RequiredContribution, SavingsError> {
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    let target = match plan.plan_type {
+        PlanType::Goal(_, target, _) => target,
+        _ => return Err(SavingsError::InvalidPlanConfig),
+    };
+    let target_date: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::GoalTargetDate(user.clone(), plan_id))
+        .ok_or(SavingsError::InvalidPlanConfig)?;
+
+    let remaining = target.saturating_sub(plan.balance).max(0);
+    let time_left = target_date.saturating_sub(env.ledger().timestamp());
+    let per_period = |period: u64| {
+        let periods = time_left.div_ceil(period).max(1) as i128;
+        (remaining + periods - 1) / periods
+    };
+
+    Ok(RequiredContribution {
+        remaining,
+        per_week: per_period(WEEK),
+        per_month: per_period(MONTH),
+        off_track: goal_off_track(env, &user, &plan, target),
+    })
+}
+
+/// Reports a goal plan's progress and projects its completion time from the
+/// average daily savings rate since the plan started.
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal plan
+pub fn get_goal_progress(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+) -> Result<GoalProgress, SavingsError> {
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user, plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    let target = match plan.plan_type {
+        PlanType::Goal(_, target, _) => target,
+        _ => return Err(SavingsError::InvalidPlanConfig),
+    };
+
+    let progress_bps = (plan.balance.saturating_mul(10_000) / target).clamp(0, 10_000) as u32;
+
+    let now = env.ledger().timestamp();
+    // Count at least one day so same-day deposits give a finite rate
+    let elapsed = now.saturating_sub(plan.start_time).max(DAY) as i128;
+    let daily_rate = plan.balance.saturating_mul(DAY as i128) / elapsed;
+
+    let projected_completion = if plan.is_completed {
+        Some(plan.last_deposit)
+    } else if daily_rate > 0 {
+        let remaining = target - plan.balance;
+        let days = (remaining + daily_rate - 1) / daily_rate;
+        u64::try_from(days)
+            .ok()
+            .and_then(|days| days.checked_mul(DAY))
+            .and_then(|secs| now.checked_add(secs))
+    } else {
+        None
+    };
+
+    Ok(GoalProgress {
+        balance: plan.balance,
+        target,
+        progress_bps,
+        daily_rate,
+        projected_completion,
+    })
+}
+
+/// Whether a goal plan with a target date is behind a straight-line path from
+/// its start to the target. Goals without a target date are never off track.
+pub(crate) fn goal_off_track(env: &Env, user: &Address, plan: &SavingsPlan, target: i128) -> bool {
+    if plan.is_completed {
+        return false;
+    }
+    let target_date: u64 = match env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::GoalTargetDate(user.clone(), plan.plan_id))
+    {
+        Some(date) => date,
+        None => return false,
+    };
+
+    let now = env.ledger().timestamp();
+    if now >= target_date {
+        return true;
+    }
+    let elapsed = now.saturating_sub(plan.start_time) as i128;
+    let term = target_date.saturating_sub(plan.start_time) as i128;
+    plan.balance.saturating_mul(term) < target.saturating_mul(elapsed)
+}
+
+/// Changes the target of a `PlanType::Goal` savings plan.
+///
+/// The plan's completion is recomputed against the new target, so lowering
+/// the target to or below the balance completes the plan and raising it
+/// above the balance reopens it.
+///
+/// # Returns
+/// `Ok(bool)` - Whether the goal is completed under the new target
+///
+/// # Errors
+/// * `InvalidAmount` - If `new_target` is not positive
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal plan
+/// * `PlanCompleted` - If the goal was already withdrawn
+pub fn update_goal_target(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    new_target: i128,
+) -> Result<bool, SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    if new_target <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let plan_key = DataKey::SavingsPlan(user.clone(), plan_id);
+    let mut plan = load_plan(env, &user, plan_id)?;
+    let (category_id, old_target, contribution_type) = match plan.plan_type {
+        PlanType::Goal(category_id, target, contribution_type) => {
+            (category_id, target, contribution_type)
+        }
+        _ => return Err(SavingsError::InvalidPlanConfig),
+    };
+    if plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    plan.plan_type = PlanType::Goal(category_id, new_target, contribution_type);
+    plan.is_completed = plan.balance >= new_target;
+    env.storage().persistent().set(&plan_key, &plan);
+    mark_goal_milestones(env, &user, plan_id, plan.balance, new_target);
+
+    env.events().publish(
+        (symbol_short!("goal_tgt"), user, plan_id),
+        (old_target, new_target, plan.balance),
+    );
+
+    Ok(plan.is_completed)
+}
+
+/// Sets the progress milestones of a `PlanType::Goal` savings plan.
+///
+/// `percents` are percentages of the target in strictly increasing order
+/// (e.g. 25, 50, 75, 100). Milestones the balance has already reached are
+/// marked immediately.
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal plan, or the
+///   percentages are empty, unordered or outside 1..=100
+pub fn set_goal_milestones(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    percents: Vec<u32>,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    let target = match plan.plan_type {
+        PlanType::Goal(_, target, _) => target,
+        _ => return Err(SavingsError::InvalidPlanConfig),
+    };
+
+    if percents.is_empty() {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    let mut milestones = Vec::new(env);
+    let mut prev = 0;
+    for percent in percents.iter() {
+        if percent <= prev || percent > 100 {
+            return Err(SavingsError::InvalidPlanConfig);
+        }
+        prev = percent;
+        milestones.push_back(GoalMilestone {
+            percent,
+            reached: false,
+        });
+    }
+    env.storage().persistent().set(
+        &DataKeyExt::GoalMilestones(user.clone(), plan_id),
+        &milestones,
+    );
+
+    mark_goal_milestones(env, &user, plan_id, plan.balance, target);
+    Ok(())
+}
+
+/// Returns a goal plan's milestones and whether each has been reached.
+pub fn get_goal_milestones(env: &Env, user: Address, plan_id: u64) -> Vec<GoalMilestone> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::GoalMilestones(user, plan_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Sets the goal plan completion bonus.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `bonus_bps` exceeds 10_000
+pub fn set_goal_bonus_config(env: &Env, config: GoalBonusConfig) -> Result<(), SavingsError> {
+    if config.bonus_bps > 10_000 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::GoalBonusConfig, &config);
+    env.events()
+        .publish((symbol_short!("set_gbon"),), config.bonus_bps);
+    Ok(())
+}
+
+/// Returns the goal completion bonus settings. No bonus is paid by default.
+pub fn get_goal_bonus_config(env: &Env) -> GoalBonusConfig {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::GoalBonusConfig)
+        .unwrap_or(GoalBonusConfig {
+            bonus_bps: 0,
+            deadline: 0,
+        })
+}
+
+/// Adds `amount` of the savings token from `from` to the goal bonus pool.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Returns
+/// `Ok(i128)` - The new pool balance
+pub fn fund_goal_bonus_pool(env: &Env, from: &Address, amount: i128) -> Result<i128, SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let pool = get_goal_bonus_pool(env)
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    assets::pull(env, from, amount)?;
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::GoalBonusPool, &pool);
+    Ok(pool)
+}
+
+pub fn get_goal_bonus_pool(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::GoalBonusPool)
+        .unwrap_or(0)
+}
+
+/// Claims the completion bonus for a goal plan that reached its target
+/// within the bonus deadline.
+///
+/// The bonus is paid from the bonus pool to the user's Flexi balance, once
+/// per plan. A goal's completion time is its last deposit, since completed
+/// goals accept no further deposits.
+///
+/// # Returns
+/// `Ok(i128)` - The bonus paid
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal plan or no bonus is set
+/// * `TooEarly` - If the goal has not been reached yet
+/// * `TooLate` - If the goal was reached after the bonus deadline
+/// * `PlanCompleted` - If the bonus was already claimed
+/// * `InsufficientBalance` - If the bonus pool can't cover the bonus
+pub fn claim_goal_bonus(env: &Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    let target = match plan.plan_type {
+        PlanType::Goal(_, target, _) => target,
+        _ => return Err(SavingsError::InvalidPlanConfig),
+    };
+
+    let config = get_goal_bonus_config(env);
+    if config.bonus_bps == 0 {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if !plan.is_completed {
+        return Err(SavingsError::TooEarly);
+    }
+    if plan.last_deposit.saturating_sub(plan.start_time) > config.deadline {
+        return Err(SavingsError::TooLate);
+    }
+
+    let claimed_key = DataKeyExt::GoalBonusClaimed(user.clone(), plan_id);
+    if env.storage().persistent().has(&claimed_key) {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let bonus = target
+        .checked_mul(config.bonus_bps as i128)
+        .ok_or(SavingsError::Overflow)?
+        / 10_000;
+    let pool = get_goal_bonus_pool(env);
+    if pool < bonus {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::GoalBonusPool, &(pool - bonus));
+    env.storage().persistent().set(&claimed_key, &true);
+    flexi::credit_flexi(env, &user, bonus)?;
+
+    env.events()
+        .publish((symbol_short!("goal_bon"), user, plan_id), bonus);
+
+    Ok(bonus)
+}
+
+/// Registers a goal category.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Returns
+/// `Ok(u32)` - The new category ID
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If a category with this name already exists
+pub fn add_goal_category(env: &Env, name: Symbol) -> Result<u32, SavingsError> {
+    let categories = list_goal_categories(env);
+    if categories.iter().any(|category| category.name == name) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    let id = categories.len() + 1;
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::GoalCategory(id), &name);
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::GoalCategoryCount, &id);
+
+    env.events().publish((symbol_short!("goal_cat"), id), name);
+
+    Ok(id)
+}
+
+/// Lists the registered goal categories in ID order.
+pub fn list_goal_categories(env: &Env) -> Vec<GoalCategory> {
+    let count: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::GoalCategoryCount)
+        .unwrap_or(0);
+    let mut categories = Vec::new(env);
+    for id in 1..=count {
+        if let Some(name) = env
+            .storage()
+            .persistent()
+            .get(&DataKeyExt::GoalCategory(id))
+        {
+            categories.push_back(GoalCategory { id, name });
+        }
+    }
+    categories
+}
+
+/// Rejects plan types that reference unregistered data, such as goal
+/// categories.
+pub(crate) fn ensure_valid_plan_type(env: &Env, plan_type: &PlanType) -> Result<(), SavingsError> {
+    if let PlanType::Goal(category_id, _, _) = plan_type {
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKeyExt::GoalCategory(*category_id))
+        {
+            return Err(SavingsError::InvalidPlanConfig);
+        }
+    }
+    Ok(())
+}
+
+/// Withdraws part of an incomplete `PlanType::Goal` savings plan early.
+///
+/// The early break fee (`early_break_fee_bps`) is deducted from the withdrawn
+/// amount and routed as in `pay_out_early`; the rest goes to the user's
+/// Flexi balance. The goal stays open for deposits.
+///
+/// # Returns
+/// `Ok(i128)` - The amount credited to the user after the penalty
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal plan
+/// * `PlanCompleted` - If the goal was already reached or withdrawn
+/// * `InsufficientBalance` - If `amount` exceeds the plan balance
+pub fn withdraw_goal(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    amount: i128,
+) -> Result<i128, SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+    ensure_plan_not_frozen(env, &user, plan_id)?;
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let plan_key = DataKey::SavingsPlan(user.clone(), plan_id);
+    let mut plan = load_plan(env, &user, plan_id)?;
+    let target = match plan.plan_type {
+        PlanType::Goal(_, target, _) => target,
+        _ => return Err(SavingsError::InvalidPlanConfig),
+    };
+    if plan.is_completed || plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if amount > plan.balance {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    plan.balance -= amount;
+    plan.last_withdraw = env.ledger().timestamp();
+    plan.is_completed = plan.balance >= target;
+    env.storage().persistent().set(&plan_key, &plan);
+
+    let (net_amount, penalty) = pay_out_early(env, &user, plan_id, amount)?;
+
+    env.events().publish(
+        (symbol_short!("goal_wd"), user, plan_id),
+        (net_amount, penalty),
+    );
+
+    Ok(net_amount)
+}
+
+/// Closes an incomplete `PlanType::Goal` savings plan, refunding its whole
+/// balance to the user's Flexi balance without a fee.
+///
+/// The plan is marked withdrawn and its milestones and target date are
+/// cleared, so it no longer shows up among the user's live goals.
+///
+/// # Returns
+/// `Ok(i128)` - The amount refunded
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal plan
+/// * `PlanCompleted` - If the goal was already reached or withdrawn
+pub fn abandon_goal(env: &Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+    ensure_plan_not_frozen(env, &user, plan_id)?;
+
+    let plan_key = DataKey::SavingsPlan(user.clone(), plan_id);
+    let mut plan = load_plan(env, &user, plan_id)?;
+    if !matches!(plan.plan_type, PlanType::Goal(..)) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if plan.is_completed || plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let refund = plan.balance;
+    plan.balance = 0;
+    plan.is_withdrawn = true;
+    plan.last_withdraw = env.ledger().timestamp();
+    env.storage().persistent().set(&plan_key, &plan);
+    env.storage()
+        .persistent()
+        .remove(&DataKeyExt::GoalMilestones(user.clone(), plan_id));
+    env.storage()
+        .persistent()
+        .remove(&DataKeyExt::GoalTargetDate(user.clone(), plan_id));
+
+    assets::credit(
+        env,
+        &user,
+        &assets::get_plan_asset(env, &user, plan_id),
+        refund,
+    )?;
+
+    env.events()
+        .publish((symbol_short!("goal_abn"), user, plan_id), refund);
+
+    Ok(refund)
+}
+
+/// Sets the goal plan that receives round-ups of the user's Flexi deposits.
+///
+/// Each Flexi deposit is rounded up to the next multiple of `rounding_unit`
+/// and the difference is moved from the Flexi balance into the goal. A
+/// `rounding_unit` of zero turns round-ups off.
+///
+/// # Errors
+/// * `InvalidAmount` - If `rounding_unit` is negative
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is not a goal plan
+/// * `PlanCompleted` - If the goal was already reached or withdrawn
+pub fn set_roundup_goal(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    rounding_unit: i128,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    if rounding_unit < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let key = DataKeyExt::RoundupGoal(user.clone());
+    if rounding_unit == 0 {
+        env.storage().persistent().remove(&key);
+        return Ok(());
+    }
+
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    if !matches!(plan.plan_type, PlanType::Goal(..)) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if plan.is_completed || plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    env.storage().persistent().set(
+        &key,
+        &RoundupGoal {
+            plan_id,
+            rounding_unit,
+        },
+    );
+    Ok(())
+}
+
+/// Returns the user's round-up goal settings, if round-ups are on.
+pub fn get_roundup_goal(env: &Env, user: Address) -> Option<RoundupGoal> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::RoundupGoal(user))
+}
+
+/// Moves the round-up of a Flexi deposit into the user's round-up goal.
+///
+/// Called after the deposit is credited. Round-ups are best effort: they are
+/// skipped when the goal no longer accepts deposits or the Flexi balance
+/// can't cover them. `User.total_balance` is unchanged since the funds stay
+/// in the contract.
+///
+/// # Returns
+/// The amount moved into the goal
+pub(crate) fn apply_roundup(env: &Env, user: &Address, deposit: i128) -> i128 {
+    let roundup = match get_roundup_goal(env, user.clone()) {
+        Some(roundup) => roundup,
+        None => return 0,
+    };
+
+    let remainder = deposit % roundup.rounding_unit;
+    if remainder == 0 {
+        return 0;
+    }
+    let diff = roundup.rounding_unit - remainder;
+
+    let flexi_key = DataKey::FlexiBalance(user.clone());
+    let flexi_balance: i128 = env.storage().persistent().get(&flexi_key).unwrap_or(0);
+    if flexi::unallocated_balance(env, user, flexi_balance) < diff
+        || credit_goal(env, user, roundup.plan_id, diff).is_err()
+    {
+        return 0;
+    }
+    env.storage()
+        .persistent()
+        .set(&flexi_key, &(flexi_balance - diff));
+
+    env.events().publish(
+        (symbol_short!("roundup"), user.clone(), roundup.plan_id),
+        diff,
+    );
+
+    diff
+}
+
+/// Deposits into any of the user's savings plans, collecting the deposit in
+/// the plan's asset.
+///
+/// Flexi plans take the deposit directly; lock and goal plans follow
+/// `deposit_lock` and `deposit_goal`. Group plans are funded through their
+/// group. The deposit fee is taken out of `amount` as for Flexi deposits.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is a group plan
+/// * `PlanCompleted` - If the plan was already withdrawn
+pub fn deposit_to_plan(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Deposits)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+    let fee = collect_plan_transfer_fee(env, &user, plan_id, amount, true)?;
+    credit_plan(env, user.clone(), plan_id, amount - fee)?;
+    assets::transfer_in(
+        env,
+        &assets::get_plan_asset(env, &user, plan_id),
+        &user,
+        amount,
+    )
+}
+
+/// Adds `amount` to a savings plan as `deposit_to_plan` does, without
+/// requiring the user's authorization or collecting any tokens. Callers must
+/// perform their own permission checks and bring the funds in themselves.
+pub(crate) fn credit_plan(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    let plan = load_plan(env, &user, plan_id)?;
+    match plan.plan_type {
+        PlanType::Flexi => adjust_flexi_plan(env, user, plan, amount),
+        PlanType::Lock(_) => add_to_lock(env, user, plan_id, amount),
+        PlanType::Goal(..) => add_to_goal(env, user, plan_id, amount).map(|_| ()),
+        PlanType::Group(..) => Err(SavingsError::InvalidPlanConfig),
+    }
+}
+
+/// Withdraws from any of the user's savings plans.
+///
+/// Funds withdrawn from a Flexi plan are sent to the user in the plan's
+/// asset, less the withdrawal fee. Lock plans must
+/// have matured and are withdrawn in full (`withdraw_lock`); goal plans
+/// follow `withdraw_goal`. Both of those pay out to the Flexi balance.
+///
+/// # Returns
+/// `Ok(i128)` - The amount paid out
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is a group plan, or `amount` isn't
+///   the full balance of a lock plan
+/// * `InsufficientBalance` - If `amount` exceeds the plan balance
+pub fn withdraw_from_plan(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    amount: i128,
+) -> Result<i128, SavingsError> {
+    let plan = load_plan(env, &user, plan_id)?;
+    match plan.plan_type {
+        PlanType::Flexi => {
+            ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+            ensure_not_frozen(env, &user)?;
+            user.require_auth();
+            adjust_flexi_plan(env, user.clone(), plan, -amount)?;
+            let fee = collect_plan_transfer_fee(env, &user, plan_id, amount, false)?;
+            let asset = assets::get_plan_asset(env, &user, plan_id);
+            assets::transfer_out(env, &asset, &user, amount - fee)?;
+            Ok(amount - fee)
+        }
+        PlanType::Lock(_) if amount != plan.balance => Err(SavingsError::InvalidPlanConfig),
+        PlanType::Lock(_) => withdraw_lock(env, user, plan_id),
+        PlanType::Goal(..) => withdraw_goal(env, user, plan_id, amount),
+        PlanType::Group(..) => Err(SavingsError::InvalidPlanConfig),
+    }
+}
+
+/// Closes one of the user's savings plans, paying out whatever it still
+/// holds.
+///
+/// A Flexi plan's balance is sent to the user in the plan's asset, less the
+/// withdrawal fee. A lock
+/// plan must have matured
+/// and settles as in `withdraw_lock`; an unfinished goal is abandoned as in
+/// `abandon_goal`, and a completed goal's balance is credited to the Flexi
+/// balance. The closed plan is marked withdrawn, which drops it from the
+/// active plan views and rejects further deposits.
+///
+/// # Returns
+/// `Ok(i128)` - The amount paid out
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is a group plan
+/// * `PlanCompleted` - If the plan was already closed
+/// * `PlanLocked` - If a lock plan has not yet matured
+pub fn close_plan(env: &Env, user: Address, plan_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused_for(env, PauseScope::Withdrawals)?;
+    ensure_not_frozen(env, &user)?;
+    ensure_plan_not_frozen(env, &user, plan_id)?;
+    let mut plan = load_plan(env, &user, plan_id)?;
+    if plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let payout = match plan.plan_type {
+        PlanType::Lock(_) => withdraw_lock(env, user.clone(), plan_id)?,
+        PlanType::Goal(..) if !plan.is_completed => abandon_goal(env, user.clone(), plan_id)?,
+        PlanType::Group(..) => return Err(SavingsError::InvalidPlanConfig),
+        PlanType::Flexi | PlanType::Goal(..) => {
+            user.require_auth();
+
+            let payout = plan.balance;
+            plan.balance = 0;
+            plan.is_withdrawn = true;
+            plan.last_withdraw = env.ledger().timestamp();
+            env.storage()
+                .persistent()
+                .set(&DataKey::SavingsPlan(user.clone(), plan_id), &plan);
+
+            if matches!(plan.plan_type, PlanType::Goal(..)) {
+                env.storage()
+                    .persistent()
+                    .remove(&DataKeyExt::GoalMilestones(user.clone(), plan_id));
+                env.storage()
+                    .persistent()
+                    .remove(&DataKeyExt::GoalTargetDate(user.clone(), plan_id));
+                assets::credit(
+                    env,
+                    &user,
+                    &assets::get_plan_asset(env, &user, plan_id),
+                    payout,
+                )?;
+                payout
+            } else {
+                let fee = collect_plan_transfer_fee(env, &user, plan_id, payout, false)?;
+                let asset = assets::get_plan_asset(env, &user, plan_id);
+                assets::transfer_out(env, &asset, &user, payout - fee)?;
+                payout - fee
+            }
+        }
+    };
+
+    env.events()
+        .publish((symbol_short!("plan_cls"), user, plan_id), payout);
+
+    Ok(payout)
+}
+
+/// Splits `amount` out of one of the user's savings plans into a new plan of
+/// the same type.
+///
+/// The new plan keeps the original's unlock time, start time and rate. A goal
+/// plan's target is divided in proportion to the balances, and the new goal
+/// shares the original's target date and claimed completion bonus.
+/// `User.total_balance` is unchanged.
+///
+/// # Returns
+/// `Ok(u64)` - The new plan ID
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InsufficientBalance` - If `amount` is not less than the plan balance
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the plan is a group plan
+/// * `PlanCompleted` - If the plan was already withdrawn
+/// * `PlanFrozen` - If the plan is frozen
+/// * `InvariantViolation` - If the split would not conserve the balance
+pub fn split_plan(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    amount: i128,
+) -> Result<u64, SavingsError> {
+    ensure_not_paused(env)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    ensure_plan_not_frozen(env, &user, plan_id)?;
+    let mut plan = load_plan(env, &user, plan_id)?;
+    if plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if amount >= plan.balance {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    let original_balance = plan.balance;
+    let mut new_plan = plan.clone();
+    new_plan.balance = amount;
+    plan.balance -= amount;
+
+    match plan.plan_type {
+        PlanType::Flexi | PlanType::Lock(_) => {}
+        PlanType::Goal(category_id, target, contribution_type) => {
+            let new_target =
+                target.checked_mul(amount).ok_or(SavingsError::Overflow)? / original_balance;
+            if new_target <= 0 || new_target >= target {
+                return Err(SavingsError::InvalidPlanConfig);
+            }
+            new_plan.plan_type = PlanType::Goal(category_id, new_target, contribution_type);
+            new_plan.is_completed = new_plan.balance >= new_target;
+            plan.plan_type = PlanType::Goal(category_id, target - new_target, contribution_type);
+            plan.is_completed = plan.balance >= target - new_target;
+        }
+        PlanType::Group(..) => return Err(SavingsError::InvalidPlanConfig),
+    }
+    if plan.balance + new_plan.balance != original_balance {
+        return Err(SavingsError::InvariantViolation);
+    }
+
+    let user_key = DataKey::User(user.clone());
+    let mut user_data: User = env
+        .storage()
+        .persistent()
+        .get(&user_key)
+        .ok_or(SavingsError::UserNotFound)?;
+    user_data.savings_count += 1;
+    new_plan.plan_id = user_data.savings_count as u64;
+    env.storage().persistent().set(&user_key, &user_data);
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::SavingsPlan(user.clone(), plan_id), &plan);
+    env.storage().persistent().set(
+        &DataKey::SavingsPlan(user.clone(), new_plan.plan_id),
+        &new_plan,
+    );
+    track_active_plan(env, &user, new_plan.plan_id);
+    interest::start_accrual(env, &user, new_plan.plan_id);
+    let asset: Option<Address> = env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::PlanAsset(user.clone(), plan_id));
+    if let Some(asset) = asset {
+        env.storage().persistent().set(
+            &DataKeyExt::PlanAsset(user.clone(), new_plan.plan_id),
+            &asset,
+        );
+    }
+    let target_date: Option<u64> = env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::GoalTargetDate(user.clone(), plan_id));
+    if let Some(target_date) = target_date {
+        env.storage().persistent().set(
+            &DataKeyExt::GoalTargetDate(user.clone(), new_plan.plan_id),
+            &target_date,
+        );
+    }
+    // A claimed bonus stays claimed for both halves, so a split can't be
+    // used to claim it again
+    if env
+        .storage()
+        .persistent()
+        .has(&DataKeyExt::GoalBonusClaimed(user.clone(), plan_id))
+    {
+        env.storage().persistent().set(
+            &DataKeyExt::GoalBonusClaimed(user.clone(), new_plan.plan_id),
+            &true,
+        );
+    }
+
+    env.events().publish(
+        (symbol_short!("plan_spl"), user, plan_id),
+        (new_plan.plan_id, amount),
+    );
+
+    Ok(new_plan.plan_id)
+}
+
+/// Folds one of the user's savings plans into another of the same type.
+///
+/// Flexi plans merge freely; lock plans must share an unlock time and goal
+/// plans a category and contribution type, with the targets added together.
+/// The target's start time and rate become the balance-weighted averages of
+/// the two plans, so neither balance gains or loses accrued interest. The
+/// target counts as having claimed its completion bonus if either plan did.
+/// The source is closed and `User.total_balance` is unchanged.
+///
+/// # Errors
+/// * `PlanNotFound` - If the user lacks either plan
+/// * `InvalidPlanConfig` - If the plans are the same, incompatible or group
+///   plans
+/// * `PlanCompleted` - If either plan was already withdrawn
+/// * `PlanFrozen` - If the source plan is frozen
+pub fn merge_plans(
+    env: &Env,
+    user: Address,
+    source_id: u64,
+    target_id: u64,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    ensure_not_frozen(env, &user)?;
+    user.require_auth();
+
+    if source_id == target_id {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    ensure_plan_not_frozen(env, &user, source_id)?;
+    let mut source = load_plan(env, &user, source_id)?;
+    let mut target = load_plan(env, &user, target_id)?;
+    if source.is_withdrawn || target.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if assets::get_plan_asset(env, &user, source_id)
+        != assets::get_plan_asset(env, &user, target_id)
+    {
+        return Err(SavingsError::UnsupportedAsset);
+    }
+
+    match (&source.plan_type, &target.plan_type) {
+        (PlanType::Flexi, PlanType::Flexi) => {}
+        (PlanType::Lock(a), PlanType::Lock(b)) if a == b => {}
+        (PlanType::Goal(cat_a, goal_a, kind_a), PlanType::Goal(cat_b, goal_b, kind_b))
+            if cat_a == cat_b && kind_a == kind_b =>
+        {
+            let merged_goal = goal_a.checked_add(*goal_b).ok_or(SavingsError::Overflow)?;
+            target.plan_type = PlanType::Goal(*cat_b, merged_goal, *kind_b);
+        }
+        _ => return Err(SavingsError::InvalidPlanConfig),
+    }
+
+    let merged = target
+        .balance
+        .checked_add(source.balance)
+        .ok_or(SavingsError::Overflow)?;
+    if merged > 0 {
+        let weighted = |a: i128, b: i128| -> Result<i128, SavingsError> {
+            a.checked_mul(target.balance)
+                .and_then(|a| a.checked_add(b.checked_mul(source.balance)?))
+                .map(|sum| sum / merged)
+                .ok_or(SavingsError::Overflow)
+        };
+        target.start_time = weighted(target.start_time as i128, source.start_time as i128)? as u64;
+        target.interest_rate =
+            weighted(target.interest_rate as i128, source.interest_rate as i128)? as u32;
+    }
+    target.balance = merged;
+    target.last_deposit = env.ledger().timestamp();
+    if let PlanType::Goal(_, goal, _) = target.plan_type {
+        target.is_completed = target.balance >= goal;
+    }
+
+    source.balance = 0;
+    source.is_withdrawn = true;
+    source.last_withdraw = env.ledger().timestamp();
+    env.storage()
+        .persistent()
+        .set(&DataKey::SavingsPlan(user.clone(), source_id), &source);
+    env.storage()
+        .persistent()
+        .set(&DataKey::SavingsPlan(user.clone(), target_id), &target);
+    env.storage()
+        .persistent()
+        .remove(&DataKeyExt::GoalMilestones(user.clone(), source_id));
+    env.storage()
+        .persistent()
+        .remove(&DataKeyExt::GoalTargetDate(user.clone(), source_id));
+    if env
+        .storage()
+        .persistent()
+        .has(&DataKeyExt::GoalBonusClaimed(user.clone(), source_id))
+    {
+        env.storage().persistent().set(
+            &DataKeyExt::GoalBonusClaimed(user.clone(), target_id),
+            &true,
+        );
+    }
+
+    env.events().publish(
+        (symbol_short!("plan_mrg"), user, target_id),
+        (source_id, merged),
+    );
+
+    Ok(())
+}
+
+/// Freezes one of the user's savings plans so nothing can be withdrawn from
+/// it until the user unfreezes it.
+///
+/// With a non-zero `unfreeze_delay`, withdrawals only reopen that many
+/// seconds after `unfreeze_plan` is called. Deposits are unaffected.
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `PlanCompleted` - If the plan was already withdrawn
+/// * `PlanFrozen` - If the plan is already frozen
+pub fn freeze_plan(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    unfreeze_delay: u64,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    let plan = load_plan(env, &user, plan_id)?;
+    if plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    // Re-freezing could otherwise be used to shorten the unfreeze delay
+    ensure_plan_not_frozen(env, &user, plan_id)?;
+
+    env.storage().persistent().set(
+        &DataKeyExt::PlanFreeze(user.clone(), plan_id),
+        &PlanFreeze {
+            unfreeze_delay,
+            unfreeze_at: None,
+        },
+    );
+    env.events()
+        .publish((symbol_short!("plan_frz"), user, plan_id), unfreeze_delay);
+    Ok(())
+}
+
+/// Starts unfreezing a frozen savings plan. Without an unfreeze delay the
+/// plan is released immediately; otherwise calling again keeps the time set
+/// by the first call.
+///
+/// # Returns
+/// `Ok(u64)` - When withdrawals from the plan reopen
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If the plan is not frozen
+pub fn unfreeze_plan(env: &Env, user: Address, plan_id: u64) -> Result<u64, SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    let key = DataKeyExt::PlanFreeze(user.clone(), plan_id);
+    let mut freeze: PlanFreeze = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(SavingsError::InvalidPlanConfig)?;
+
+    let now = env.ledger().timestamp();
+    let unfreeze_at = match freeze.unfreeze_at {
+        Some(at) => at,
+        None => now.saturating_add(freeze.unfreeze_delay),
+    };
+    if unfreeze_at <= now {
+        env.storage().persistent().remove(&key);
+    } else {
+        freeze.unfreeze_at = Some(unfreeze_at);
+        env.storage().persistent().set(&key, &freeze);
+    }
+
+    env.events()
+        .publish((symbol_short!("plan_ufz"), user, plan_id), unfreeze_at);
+    Ok(unfreeze_at)
+}
+
+/// Returns the freeze on a savings plan, if any.
+pub fn get_plan_freeze(env: &Env, user: Address, plan_id: u64) -> Option<PlanFreeze> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::PlanFreeze(user, plan_id))
+}
+
+/// Attaches a display label to one of the user's savings plans, such as
+/// "🏠 House fund". An empty label removes it.
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no such plan
+/// * `InvalidPlanConfig` - If the label is longer than `MAX_PLAN_LABEL_LEN`
+///   bytes
+pub fn set_plan_label(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    label: String,
+) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    load_plan(env, &user, plan_id)?;
+    if label.len() > MAX_PLAN_LABEL_LEN {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    let key = DataKeyExt::PlanLabel(user, plan_id);
+    if label.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &label);
+    }
+    Ok(())
+}
+
+/// Returns the label of a savings plan, if it has one.
+pub fn get_plan_label(env: &Env, user: Address, plan_id: u64) -> Option<String> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::PlanLabel(user, plan_id))
+}
+
+/// Moves the user's withdrawn savings plans (including completed plans that
+/// have been paid out) from the active plan index to the archive.
+///
+/// Only the indexes change, so anyone may trigger archival.
+///
+/// # Returns
+/// The number of plans archived
+pub fn archive_plans(env: &Env, user: Address) -> u32 {
+    let mut active = Vec::new(env);
+    let mut archived = 0u32;
+    let mut archive_count: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::ArchivedPlanCount(user.clone()))
+        .unwrap_or(0);
+
+    for plan_id in active_plan_ids(env, &user).iter() {
+        let plan: Option<SavingsPlan> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SavingsPlan(user.clone(), plan_id));
+        match plan {
+            Some(plan) if plan.is_withdrawn => {
+                env.storage().persistent().set(
+                    &DataKeyExt::ArchivedPlan(user.clone(), archive_count),
+                    &plan_id,
+                );
+                archive_count += 1;
+                archived += 1;
+            }
+            Some(_) => active.push_back(plan_id),
+            None => {}
+        }
+    }
+
+    if archived > 0 {
+        env.storage()
+            .persistent()
+            .set(&DataKeyExt::ActivePlans(user.clone()), &active);
+        env.storage()
+            .persistent()
+            .set(&DataKeyExt::ArchivedPlanCount(user.clone()), &archive_count);
+        env.events()
+            .publish((symbol_short!("plan_arc"), user), archived);
+    }
+    archived
+}
+
+/// Returns the user's savings plans that have not been archived, in plan ID
+/// order.
+pub fn get_user_savings_plans(env: &Env, user: Address) -> Vec<SavingsPlan> {
+    let mut plans = Vec::new(env);
+    for plan_id in active_plan_ids(env, &user).iter() {
+        if let Some(plan) = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        {
+            plans.push_back(plan);
+        }
+    }
+    plans
+}
+
+/// Returns a page of the user's archived savings plans, oldest archived
+/// first.
+///
+/// # Arguments
+/// * `start` - Index of the first archived plan to return
+/// * `limit` - Maximum number of plans to return (capped at `MAX_PAGE_SIZE`)
+pub fn get_archived_plans(env: &Env, user: Address, start: u32, limit: u32) -> Vec<SavingsPlan> {
+    let count: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::ArchivedPlanCount(user.clone()))
+        .unwrap_or(0);
+    let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+    let mut plans = Vec::new(env);
+    for index in start..end {
+        let plan_id: Option<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKeyExt::ArchivedPlan(user.clone(), index));
+        if let Some(plan) = plan_id.and_then(|plan_id| {
+            env.storage()
+                .persistent()
+                .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        }) {
+            plans.push_back(plan);
+        }
+    }
+    plans
+}
+
+/// Checks the ledgers `User.total_balance` is derived from: the Flexi
+/// balance, active savings plans (other than group plans, which are
+/// accounted for by their group) and active Lock Saves.
+///
+/// # Returns
+/// `Ok(i128)` - The user's total balance
+///
+/// # Errors
+/// * `UserNotFound` - If the user has no account
+/// * `InvariantViolation` - If any of the ledgers is negative
+pub fn check_balance_invariant(env: &Env, user: Address) -> Result<i128, SavingsError> {
+    users::get_user(env, &user).map(|user_data| user_data.total_balance)
+}
+
+// ========== Helper Functions ==========
+
+/// Takes the deposit or withdrawal fee out of `amount` moving into or out of
+/// a plan and returns it. Plans held in another asset pay no fee, as the
+/// treasury only holds the savings token.
+fn collect_plan_transfer_fee(
+    env: &Env,
+    user: &Address,
+    plan_id: u64,
+    amount: i128,
+    is_deposit: bool,
+) -> Result<i128, SavingsError> {
+    if amount <= 0 || assets::holds_other_asset(env, user, plan_id) {
+        return Ok(0);
+    }
+    fees::collect_transfer_fee(env, user, amount, is_deposit)
+}
+
+/// Adjusts the user's penalty pool weight for a change to a lock plan's
+/// principal. Plans in assets other than the savings token carry no weight.
+fn update_pool_weight(env: &Env, user: &Address, plan_id: u64, delta: i128) {
+    if !assets::holds_other_asset(env, user, plan_id) {
+        penalty_pool::update_locked(env, user, delta);
+    }
+}
+
+/// Fails with `PlanFrozen` while the user has a plan frozen, including
+/// during its unfreeze delay.
+fn ensure_plan_not_frozen(env: &Env, user: &Address, plan_id: u64) -> Result<(), SavingsError> {
+    match get_plan_freeze(env, user.clone(), plan_id) {
+        Some(PlanFreeze {
+            unfreeze_at: Some(at),
+            ..
+        }) if env.ledger().timestamp() >= at => Ok(()),
+        Some(_) => Err(SavingsError::PlanFrozen),
+        None => Ok(()),
+    }
+}
+
+/// IDs in the user's active plan index. Users whose plans predate the index
+/// get it rebuilt from their plan IDs.
+fn active_plan_ids(env: &Env, user: &Address) -> Vec<u64> {
+    if let Some(ids) = env
+        .storage()
+        .persistent()
+        .get(&DataKeyExt::ActivePlans(user.clone()))
+    {
+        return ids;
+    }
+
+    let mut ids = Vec::new(env);
+    let savings_count = users::get_user(env, user)
+        .map(|user_data| user_data.savings_count)
+        .unwrap_or(0);
+    for plan_id in 1..=savings_count as u64 {
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::SavingsPlan(user.clone(), plan_id))
+        {
+            ids.push_back(plan_id);
+        }
+    }
+    ids
+}
+
+/// Adds a newly stored plan to the user's active plan index.
+fn track_active_plan(env: &Env, user: &Address, plan_id: u64) {
+    let mut ids = active_plan_ids(env, user);
+    if !ids.contains(plan_id) {
+        ids.push_back(plan_id);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKeyExt::ActivePlans(user.clone()), &ids);
+}
+
+/// Loads one of the user's savings plans, settling the interest it has
+/// accrued so far.
+fn load_plan(env: &Env, user: &Address, plan_id: u64) -> Result<SavingsPlan, SavingsError> {
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+    interest::accrue(env, user, &plan)?;
+    Ok(plan)
+}
+
+/// Deposits into (positive `delta`) or withdraws from (negative `delta`) a
+/// Flexi savings plan. The caller is responsible for the user's
+/// authorization and for moving the tokens.
+fn adjust_flexi_plan(
+    env: &Env,
+    user: Address,
+    mut plan: SavingsPlan,
+    delta: i128,
+) -> Result<(), SavingsError> {
+    if delta == 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if delta < 0 {
+        ensure_plan_not_frozen(env, &user, plan.plan_id)?;
+    }
+
+    plan.balance = plan
+        .balance
+        .checked_add(delta)
+        .ok_or(SavingsError::Overflow)?;
+    if plan.balance < 0 {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    let now = env.ledger().timestamp();
+    if delta > 0 {
+        plan.last_deposit = now;
+    } else {
+        plan.last_withdraw = now;
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::SavingsPlan(user.clone(), plan.plan_id), &plan);
+    if delta > 0 {
+        interest::record_contribution(env, &user, &plan);
+    }
+
+    env.events()
+        .publish((symbol_short!("plan_bal"), user, plan.plan_id), delta);
+
+    Ok(())
+}
+
+/// Adds `amount` to an open goal plan's balance, completing it once the
+/// target is reached.
+fn credit_goal(
+    env: &Env,
+    user: &Address,
+    plan_id: u64,
+    amount: i128,
+) -> Result<SavingsPlan, SavingsError> {
+    let plan_key = DataKey::SavingsPlan(user.clone(), plan_id);
+    let mut plan = load_plan(env, user, plan_id)?;
+    let target = match plan.plan_type {
+        PlanType::Goal(_, target, _) => target,
+        _ => return Err(SavingsError::InvalidPlanConfig),
+    };
+    if plan.is_completed || plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    plan.balance = plan
+        .balance
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    plan.last_deposit = env.ledger().timestamp();
+    // Goals with a USD target complete on the value of their balance
+    plan.is_completed = match oracle::usd_goal_reached(env, user, plan_id, plan.balance)? {
+        Some(reached) => reached,
+        None => plan.balance >= target,
+    };
+    env.storage().persistent().set(&plan_key, &plan);
+    interest::record_contribution(env, user, &plan);
+    mark_goal_milestones(env, user, plan_id, plan.balance, target);
+
+    Ok(plan)
+}
+
+/// Pays `amount` released early from a plan to the user, minus the early
+/// break fee.
+///
+/// A fee in the savings token goes through `penalty_pool::route_penalty`:
+/// under a penalty routing policy it is split between the treasury, the
+/// penalty pool and a burn, and otherwise the pool takes its share and the
+/// rest goes to the treasury, in both cases less the referrer's share. A fee
+/// in another asset goes to the fee recipient's balance of that asset, or to
+/// the treasurer's while no fee recipient is set.
+///
+/// # Returns
+/// `(net_amount, penalty)`
+fn pay_out_early(
+    env: &Env,
+    user: &Address,
+    plan_id: u64,
+    amount: i128,
+) -> Result<(i128, i128), SavingsError> {
+    let fee_bps = config::get_config(env).early_break_fee_bps;
+    let penalty = amount
+        .checked_mul(fee_bps as i128)
+        .ok_or(SavingsError::Overflow)?
+        / 10_000;
+    let net_amount = amount.checked_sub(penalty).ok_or(SavingsError::Underflow)?;
+
+    let asset = assets::get_plan_asset(env, user, plan_id);
+    assets::credit(env, user, &asset, net_amount)?;
+    if assets::holds_other_asset(env, user, plan_id) {
+        // The treasury only pays out the savings token, so penalties in
+        // another asset go to the fee recipient's balance of that asset
+        fees::record_fee(env, fees::FeeKind::Penalty, &asset, penalty)?;
+        let recipient = env
+            .storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::FeeRecipient)
+            .unwrap_or_else(|| fees::get_treasurer(env));
+        assets::credit(env, &recipient, &asset, penalty)?;
+    } else {
+        // `route_penalty` records the penalty in the fee stats
+        let fee = penalty_pool::route_penalty(env, user, penalty)?;
+        fees::credit_treasury(env, fee)?;
+    }
+
+    Ok((net_amount, penalty))
+}
+
+/// Marks the goal milestones that `balance` has reached, emitting an event
+/// the first time each one is crossed. Reached milestones stay reached.
+fn mark_goal_milestones(env: &Env, user: &Address, plan_id: u64, balance: i128, target: i128) {
+    let mut milestones = get_goal_milestones(env, user.clone(), plan_id);
+    let mut changed = false;
+    for i in 0..milestones.len() {
+        let mut milestone = milestones.get(i).unwrap();
+        if milestone.reached {
+            continue;
+        }
+        // balance / target >= percent / 100, without rounding
+        if balance.saturating_mul(100) < target.saturating_mul(milestone.percent as i128) {
+            break;
+        }
+        milestone.reached = true;
+        milestones.set(i, milestone.clone());
+        changed = true;
+        env.events().publish(
+            (symbol_short!("goal_ms"), user.clone(), plan_id),
+            milestone.percent,
+        );
+    }
+    if changed {
+        env.storage().persistent().set(
+            &DataKeyExt::GoalMilestones(user.clone(), plan_id),
+            &milestones,
+        );
+    }
+}
+
+/// Interest a lock plan unlocking at `until` has earned by `at`.
+pub(crate) fn lock_interest(
+    plan: &SavingsPlan,
+    until: u64,
+    at: u64,
+    policy: &LockMaturityPolicy,
+) -> Result<i128, SavingsError> {
+    let mut interest = rates::calculate_flexi_interest(
+        plan.balance,
+        plan.interest_rate as i128,
+        at.min(until).saturating_sub(plan.start_time),
+    );
+    if let IdleFundsPolicy::ReducedRate(rate) = policy.idle_policy {
+        let idle_since = until.saturating_add(policy.grace_period);
+        interest = interest
+            .checked_add(rates::calculate_flexi_interest(
+                plan.balance,
+                rate as i128,
+                at.saturating_sub(idle_since),
+            ))
+            .ok_or(SavingsError::Overflow)?;
+    }
+    Ok(interest)
+}
+
+/// Pays out a matured lock plan to the owner's Flexi balance and marks it
+/// withdrawn.
+///
+/// Interest is earned at the plan rate up to maturity, then at the reduced
+/// idle rate (if any) for the time after the grace period.
+fn settle_matured_lock(
+    env: &Env,
+    user: &Address,
+    mut plan: SavingsPlan,
+    until: u64,
+    policy: &LockMaturityPolicy,
+) -> Result<i128, SavingsError> {
+    let now = env.ledger().timestamp();
+    let interest = lock_interest(&plan, until, now, policy)?;
+    let asset = assets::get_plan_asset(env, user, plan.plan_id);
+    let fee = fees::collect_performance_fee(env, user, PlanKind::Lock, &asset, interest)?;
+    let payout = plan
+        .balance
+        .checked_add(interest - fee)
+        .ok_or(SavingsError::Overflow)?;
+
+    update_pool_weight(env, user, plan.plan_id, -plan.balance);
+    plan.is_withdrawn = true;
+    plan.is_completed = true;
+    plan.last_withdraw = now;
+    env.storage()
+        .persistent()
+        .set(&DataKey::SavingsPlan(user.clone(), plan.plan_id), &plan);
+
+    assets::credit(env, user, &asset, payout)?;
+
+    Ok(payout)
+}
+
+/// Stores a new savings plan for `user` and counts its deposit towards the
+/// user's total balance.
+///
+/// Lock plans get the rate tier matching their duration.
+pub(crate) fn store_new_plan(
+    env: &Env,
+    user: &Address,
+    plan_type: PlanType,
+    initial_deposit: i128,
+) -> u64 {
+    store_new_asset_plan(env, user, plan_type, initial_deposit, None)
+}
+
+/// Like `store_new_plan`, recording `other_asset` as the plan's asset when
+/// it is not held in the savings token.
+pub(crate) fn store_new_asset_plan(
+    env: &Env,
+    user: &Address,
+    plan_type: PlanType,
+    initial_deposit: i128,
+    other_asset: Option<Address>,
+) -> u64 {
+    let user_key = DataKey::User(user.clone());
+    let mut user_data = env.storage().persistent().get(&user_key).unwrap_or(User {
+        total_balance: 0,
+        savings_count: 0,
+    });
+    user_data.savings_count += 1;
+    let plan_id = user_data.savings_count as u64;
+    let interest_rate = match plan_type {
+        PlanType::Lock(until) => {
+            let duration = until.saturating_sub(env.ledger().timestamp());
+            rates::lock_tier_rate(env, duration / 86_400)
+        }
+        _ => 500,
+    };
+    let new_plan = SavingsPlan {
+        plan_id,
+        plan_type,
+        balance: initial_deposit,
+        start_time: env.ledger().timestamp(),
+        last_deposit: env.ledger().timestamp(),
+        last_withdraw: 0,
+        interest_rate,
+        is_completed: false,
+        is_withdrawn: false,
+    };
+    env.storage().persistent().set(&user_key, &user_data);
+    env.storage()
+        .persistent()
+        .set(&DataKey::SavingsPlan(user.clone(), plan_id), &new_plan);
+    track_active_plan(env, user, plan_id);
+    if let Some(asset) = other_asset {
+        env.storage()
+            .persistent()
+            .set(&DataKeyExt::PlanAsset(user.clone(), plan_id), &asset);
+    }
+    env.events().publish(
+        (Symbol::new(env, "create_plan"), user.clone(), plan_id),
+        initial_deposit,
+    );
+    if let PlanType::Lock(until) = new_plan.plan_type {
+        update_pool_weight(env, user, plan_id, initial_deposit);
+        env.events().publish(
+            (symbol_short!("lock_new"), user.clone(), plan_id),
+            (initial_deposit, until),
+        );
+    }
+    plan_id
+}
+
+/// Loads a user's lock plan that has not been withdrawn yet, along with its
+/// unlock timestamp.
+fn load_lock_plan(
+    env: &Env,
+    user: &Address,
+    plan_id: u64,
+) -> Result<(SavingsPlan, u64), SavingsError> {
+    let plan: SavingsPlan = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    let until = match plan.plan_type {
+        PlanType::Lock(until) => until,
+        _ => return Err(SavingsError::InvalidPlanConfig),
+    };
+    if plan.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    Ok((plan, until))
+}
@@ -0,0 +1,818 @@
+use crate::{
+    GoalBonusConfig, IdleFundsPolicy, LockMaturityPolicy, LockRateTier, NesteraContract,
+    NesteraContractClient, PlanType, SavingsError,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, BytesN, Env, String, Symbol,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+const YEAR: u64 = 365 * 24 * 60 * 60;
+
+fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &admin_pk, &None);
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+
+    (env, client, user)
+}
+
+#[test]
+fn test_withdraw_lock_after_maturity_pays_principal_and_interest() {
+    let (env, client, user) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &10_000);
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    // 5% over a full year
+    assert_eq!(client.withdraw_lock(&user, &plan_id), 10_500);
+    assert_eq!(client.get_flexi_balance(&user), 10_500);
+    assert_eq!(client.get_user(&user).total_balance, 10_500);
+
+    let plan = client.get_savings_plan(&user, &plan_id).unwrap();
+    assert!(plan.is_withdrawn);
+
+    let res = client.try_withdraw_lock(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_withdraw_lock_before_maturity_fails() {
+    let (env, client, user) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(1_000), &10_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 999);
+    let res = client.try_withdraw_lock(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanLocked));
+
+    let flexi_id = client.create_savings_plan(&user, &PlanType::Flexi, &100);
+    let res = client.try_withdraw_lock(&user, &flexi_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+}
+
+#[test]
+fn test_early_withdraw_lock_routes_penalty_to_treasury() {
+    let (env, client, user) = setup();
+    let treasury = Address::generate(&env);
+    client.set_fee_recipient(&treasury);
+    client.set_early_break_fee_bps(&1_000);
+
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &10_000);
+    env.ledger().with_mut(|li| li.timestamp = YEAR / 2);
+
+    assert_eq!(client.early_withdraw_lock(&user, &plan_id), 9_000);
+    assert_eq!(client.get_flexi_balance(&user), 9_000);
+    assert_eq!(client.get_user(&user).total_balance, 9_000);
+    assert_eq!(client.get_treasury_balance(), 1_000);
+    assert_eq!(client.get_fee_stats().penalties, 1_000);
+    assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+    assert!(
+        client
+            .get_savings_plan(&user, &plan_id)
+            .unwrap()
+            .is_withdrawn
+    );
+
+    // Matured locks must use the regular withdrawal
+    let matured_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR / 2), &100);
+    let res = client.try_early_withdraw_lock(&user, &matured_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooLate));
+}
+
+#[test]
+fn test_extend_lock_moves_unlock_time_and_rate_tier() {
+    let (env, client, user) = setup();
+    client.set_lock_rate_tiers(&vec![
+        &env,
+        LockRateTier {
+            min_duration_days: 0,
+            rate: 500,
+        },
+        LockRateTier {
+            min_duration_days: 730,
+            rate: 800,
+        },
+    ]);
+
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &10_000);
+
+    let res = client.try_extend_lock(&user, &plan_id, &YEAR);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidTimestamp));
+
+    // Extending to two years picks up the 730-day tier
+    assert_eq!(client.extend_lock(&user, &plan_id, &(2 * YEAR)), 800);
+    let plan = client.get_savings_plan(&user, &plan_id).unwrap();
+    assert_eq!(plan.plan_type, PlanType::Lock(2 * YEAR));
+    assert_eq!(plan.interest_rate, 800);
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    let res = client.try_withdraw_lock(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanLocked));
+
+    env.ledger().with_mut(|li| li.timestamp = 2 * YEAR);
+    let res = client.try_extend_lock(&user, &plan_id, &(3 * YEAR));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooLate));
+}
+
+#[test]
+fn test_deposit_lock_only_earns_from_deposit_time() {
+    let (env, client, user) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &10_000);
+
+    // Half way through, double the balance
+    env.ledger().with_mut(|li| li.timestamp = YEAR / 2);
+    client.deposit_lock(&user, &plan_id, &10_000);
+    let plan = client.get_savings_plan(&user, &plan_id).unwrap();
+    assert_eq!(plan.balance, 20_000);
+    assert_eq!(plan.start_time, YEAR / 4);
+    assert_eq!(client.get_user(&user).total_balance, 20_000);
+
+    // 10_000 for a full year plus 10_000 for half a year at 5%
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    assert_eq!(client.withdraw_lock(&user, &plan_id), 20_750);
+
+    let res = client.try_deposit_lock(&user, &plan_id, &0);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidAmount));
+}
+
+#[test]
+fn test_create_lock_ladder_staggers_maturities() {
+    let (_env, client, user) = setup();
+    let day = 86_400u64;
+
+    let ids = client.create_lock_ladder(&user, &1_000, &3, &(30 * day));
+    assert_eq!(ids.len(), 3);
+    for (i, id) in ids.iter().enumerate() {
+        let plan = client.get_savings_plan(&user, &id).unwrap();
+        assert_eq!(plan.plan_type, PlanType::Lock(30 * day * (i as u64 + 1)));
+        // The remainder goes to the last tranche
+        assert_eq!(plan.balance, if i == 2 { 334 } else { 333 });
+    }
+    assert_eq!(client.get_user(&user).total_balance, 1_000);
+
+    let res = client.try_create_lock_ladder(&user, &1_000, &0, &day);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+    let res = client.try_create_lock_ladder(&user, &2, &3, &day);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidAmount));
+}
+
+#[test]
+fn test_get_upcoming_maturities_lists_locks_in_window() {
+    let (env, client, user) = setup();
+    let day = 86_400u64;
+
+    let soon = client.create_savings_plan(&user, &PlanType::Lock(5 * day), &100);
+    let later = client.create_savings_plan(&user, &PlanType::Lock(60 * day), &100);
+    client.create_savings_plan(&user, &PlanType::Flexi, &100);
+    let withdrawn = client.create_savings_plan(&user, &PlanType::Lock(day), &100);
+
+    env.ledger().with_mut(|li| li.timestamp = day);
+    client.withdraw_lock(&user, &withdrawn);
+
+    assert_eq!(
+        client.get_upcoming_maturities(&user, &(7 * day)),
+        vec![&env, soon]
+    );
+    assert_eq!(
+        client.get_upcoming_maturities(&user, &(90 * day)),
+        vec![&env, soon, later]
+    );
+}
+
+#[test]
+fn test_convert_flexi_to_lock_keeps_total_balance() {
+    let (_env, client, user) = setup();
+    client.deposit_flexi(&user, &1_000);
+
+    let plan_id = client.convert_flexi_to_lock(&user, &600, &YEAR);
+    assert_eq!(client.get_flexi_balance(&user), 400);
+    assert_eq!(client.get_user(&user).total_balance, 1_000);
+
+    let plan = client.get_savings_plan(&user, &plan_id).unwrap();
+    assert_eq!(plan.balance, 600);
+    assert_eq!(plan.plan_type, PlanType::Lock(YEAR));
+
+    let res = client.try_convert_flexi_to_lock(&user, &500, &YEAR);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientBalance));
+}
+
+#[test]
+fn test_idle_lock_funds_earn_reduced_rate_after_grace() {
+    let (env, client, user) = setup();
+    client.set_lock_maturity_policy(&LockMaturityPolicy {
+        grace_period: YEAR,
+        idle_policy: IdleFundsPolicy::ReducedRate(100),
+    });
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &10_000);
+
+    // 5% for the lock term, nothing during the grace year, 1% for the last year
+    env.ledger().with_mut(|li| li.timestamp = 3 * YEAR);
+    assert_eq!(client.withdraw_lock(&user, &plan_id), 10_600);
+
+    let res = client.try_sweep_matured_lock(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_sweep_matured_lock_after_grace() {
+    let (env, client, user) = setup();
+    client.set_lock_maturity_policy(&LockMaturityPolicy {
+        grace_period: 1_000,
+        idle_policy: IdleFundsPolicy::SweepToFlexi,
+    });
+    let plan_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &10_000);
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR + 1_000);
+    let res = client.try_sweep_matured_lock(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooEarly));
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR + 1_001);
+    assert_eq!(client.sweep_matured_lock(&user, &plan_id), 10_500);
+    assert_eq!(client.get_flexi_balance(&user), 10_500);
+    assert!(
+        client
+            .get_savings_plan(&user, &plan_id)
+            .unwrap()
+            .is_withdrawn
+    );
+}
+
+#[test]
+fn test_deposit_goal_tracks_progress_to_target() {
+    let (env, client, user) = setup();
+    let category = client.add_goal_category(&Symbol::new(&env, "laptop"));
+    let goal = PlanType::Goal(category, 1_000, 1);
+    let plan_id = client.create_savings_plan(&user, &goal, &200);
+
+    assert!(!client.deposit_goal(&user, &plan_id, &500));
+    assert!(client.deposit_goal(&user, &plan_id, &300));
+
+    let plan = client.get_savings_plan(&user, &plan_id).unwrap();
+    assert_eq!(plan.balance, 1_000);
+    assert!(plan.is_completed);
+    assert_eq!(client.get_user(&user).total_balance, 1_000);
+
+    let res = client.try_deposit_goal(&user, &plan_id, &1);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+
+    let lock_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &100);
+    let res = client.try_deposit_goal(&user, &lock_id, &1);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+}
+
+#[test]
+fn test_claim_goal_bonus_once_from_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    let minter = StellarAssetClient::new(&env, &token);
+    minter.mint(&admin, &30);
+    minter.mint(&user, &3_000);
+    let balances = TokenClient::new(&env, &token);
+
+    client.set_goal_bonus_config(&GoalBonusConfig {
+        bonus_bps: 200,
+        deadline: YEAR,
+    });
+    // The pool is funded with the admin's tokens
+    client.fund_goal_bonus_pool(&30);
+    assert_eq!(balances.balance(&admin), 0);
+    assert_eq!(balances.balance(&contract_id), 30);
+
+    let category = client.add_goal_category(&Symbol::new(&env, "trip"));
+    let goal = PlanType::Goal(category, 1_000, 1);
+    let plan_id = client.create_savings_plan(&user, &goal, &500);
+    let res = client.try_claim_goal_bonus(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooEarly));
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR / 2);
+    client.deposit_goal(&user, &plan_id, &500);
+    assert_eq!(client.claim_goal_bonus(&user, &plan_id), 20);
+    assert_eq!(client.get_flexi_balance(&user), 20);
+    assert_eq!(client.get_goal_bonus_pool(), 10);
+    client.withdraw_flexi(&user, &20);
+    assert_eq!(balances.balance(&user), 2_020);
+    assert_eq!(balances.balance(&contract_id), 1_010);
+
+    let res = client.try_claim_goal_bonus(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+
+    // Splitting or merging a claimed goal doesn't free up another bonus
+    let split_id = client.split_plan(&user, &plan_id, &400);
+    let res = client.try_claim_goal_bonus(&user, &split_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+    let other_id = client.create_savings_plan(&user, &goal, &1_000);
+    client.merge_plans(&user, &split_id, &other_id);
+    let res = client.try_claim_goal_bonus(&user, &other_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+    assert_eq!(client.get_goal_bonus_pool(), 10);
+
+    // Reached after the deadline
+    let late_id = client.create_savings_plan(&user, &goal, &500);
+    env.ledger().with_mut(|li| li.timestamp = 2 * YEAR);
+    client.deposit_goal(&user, &late_id, &500);
+    let res = client.try_claim_goal_bonus(&user, &late_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooLate));
+}
+
+#[test]
+fn test_update_goal_target_recomputes_completion() {
+    let (env, client, user) = setup();
+    let category = client.add_goal_category(&Symbol::new(&env, "car"));
+    let goal = PlanType::Goal(category, 1_000, 1);
+    let plan_id = client.create_savings_plan(&user, &goal, &600);
+
+    // Lowering below the balance completes the goal
+    assert!(client.update_goal_target(&user, &plan_id, &500));
+    let plan = client.get_savings_plan(&user, &plan_id).unwrap();
+    assert_eq!(plan.plan_type, PlanType::Goal(category, 500, 1));
+    assert!(plan.is_completed);
+
+    // Raising it again reopens the goal for deposits
+    assert!(!client.update_goal_target(&user, &plan_id, &2_000));
+    assert!(!client.deposit_goal(&user, &plan_id, &100));
+
+    let res = client.try_update_goal_target(&user, &plan_id, &0);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidAmount));
+}
+
+#[test]
+fn test_goal_milestones_are_marked_as_balance_grows() {
+    let (env, client, user) = setup();
+    let category = client.add_goal_category(&Symbol::new(&env, "house"));
+    let goal = PlanType::Goal(category, 1_000, 1);
+    let plan_id = client.create_savings_plan(&user, &goal, &300);
+
+    client.set_goal_milestones(&user, &plan_id, &vec![&env, 25, 50, 75, 100]);
+    let reached = |client: &NesteraContractClient| {
+        client
+            .get_goal_milestones(&user, &plan_id)
+            .iter()
+            .filter(|m| m.reached)
+            .count()
+    };
+    assert_eq!(reached(&client), 1);
+
+    client.deposit_goal(&user, &plan_id, &450);
+    assert_eq!(reached(&client), 3);
+
+    // Raising the target doesn't un-reach milestones
+    client.update_goal_target(&user, &plan_id, &5_000);
+    assert_eq!(reached(&client), 3);
+
+    let res = client.try_set_goal_milestones(&user, &plan_id, &vec![&env, 50, 50]);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+}
+
+#[test]
+fn test_required_contribution_for_target_date_goal() {
+    let (env, client, user) = setup();
+    let week = 7 * 86_400u64;
+    let name = client.add_goal_category(&Symbol::new(&env, "wedding"));
+
+    let plan_id = client.create_goal_plan(&user, &name, &1_000, &1, &0, &Some(10 * week));
+    let required = client.get_required_contribution(&user, &plan_id);
+    assert_eq!(required.remaining, 1_000);
+    assert_eq!(required.per_week, 100);
+    assert!(!required.off_track);
+
+    // Half way there in time but not in savings
+    env.ledger().with_mut(|li| li.timestamp = 5 * week);
+    client.deposit_goal(&user, &plan_id, &200);
+    let required = client.get_required_contribution(&user, &plan_id);
+    assert_eq!(required.per_week, 160);
+    assert!(required.off_track);
+
+    let no_date = client.create_goal_plan(&user, &name, &1_000, &1, &0, &None);
+    let res = client.try_get_required_contribution(&user, &no_date);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+    let res = client.try_create_goal_plan(&user, &name, &1_000, &1, &0, &Some(5 * week));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidTimestamp));
+}
+
+#[test]
+fn test_goal_plans_require_registered_category() {
+    let (env, client, user) = setup();
+    let education = client.add_goal_category(&Symbol::new(&env, "education"));
+    let travel = client.add_goal_category(&Symbol::new(&env, "travel"));
+    assert_eq!((education, travel), (1, 2));
+
+    let categories = client.list_goal_categories();
+    assert_eq!(categories.len(), 2);
+    assert_eq!(categories.get(1).unwrap().name, Symbol::new(&env, "travel"));
+
+    let res = client.try_add_goal_category(&Symbol::new(&env, "travel"));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+
+    let res = client.try_create_goal_plan(&user, &7, &1_000, &1, &0, &None);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+    assert!(client
+        .try_create_savings_plan(&user, &PlanType::Goal(7, 1_000, 1), &0)
+        .is_err());
+}
+
+#[test]
+fn test_withdraw_goal_partially_with_penalty() {
+    let (env, client, user) = setup();
+    let treasury = Address::generate(&env);
+    client.set_fee_recipient(&treasury);
+    client.set_early_break_fee_bps(&500);
+
+    let category = client.add_goal_category(&Symbol::new(&env, "bike"));
+    let plan_id = client.create_goal_plan(&user, &category, &1_000, &1, &600, &None);
+
+    assert_eq!(client.withdraw_goal(&user, &plan_id, &200), 190);
+    assert_eq!(client.get_treasury_balance(), 10);
+    assert_eq!(client.get_flexi_balance(&user), 190);
+    assert_eq!(client.get_user(&user).total_balance, 590);
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        400
+    );
+
+    let res = client.try_withdraw_goal(&user, &plan_id, &401);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientBalance));
+
+    // Reached goals are no longer early withdrawals
+    client.deposit_goal(&user, &plan_id, &600);
+    let res = client.try_withdraw_goal(&user, &plan_id, &100);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_abandon_goal_refunds_balance_to_flexi() {
+    let (env, client, user) = setup();
+    client.set_early_break_fee_bps(&500);
+    let category = client.add_goal_category(&Symbol::new(&env, "phone"));
+    let plan_id = client.create_goal_plan(&user, &category, &1_000, &1, &300, &Some(YEAR));
+
+    assert_eq!(client.abandon_goal(&user, &plan_id), 300);
+    assert_eq!(client.get_flexi_balance(&user), 300);
+    assert_eq!(client.get_user(&user).total_balance, 300);
+
+    let plan = client.get_savings_plan(&user, &plan_id).unwrap();
+    assert!(plan.is_withdrawn);
+    assert_eq!(plan.balance, 0);
+
+    let res = client.try_deposit_goal(&user, &plan_id, &100);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+    let res = client.try_abandon_goal(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_goal_progress_projects_completion() {
+    let (env, client, user) = setup();
+    let day = 86_400u64;
+    let category = client.add_goal_category(&Symbol::new(&env, "camera"));
+    let plan_id = client.create_goal_plan(&user, &category, &1_000, &1, &0, &None);
+
+    let progress = client.get_goal_progress(&user, &plan_id);
+    assert_eq!(progress.progress_bps, 0);
+    assert_eq!(progress.projected_completion, None);
+
+    // 250 saved over 10 days: 25 a day, 30 more days to go
+    env.ledger().with_mut(|li| li.timestamp = 10 * day);
+    client.deposit_goal(&user, &plan_id, &250);
+    let progress = client.get_goal_progress(&user, &plan_id);
+    assert_eq!(progress.progress_bps, 2_500);
+    assert_eq!(progress.daily_rate, 25);
+    assert_eq!(progress.projected_completion, Some(40 * day));
+}
+
+#[test]
+fn test_flexi_deposits_round_up_into_goal() {
+    let (env, client, user) = setup();
+    let category = client.add_goal_category(&Symbol::new(&env, "holiday"));
+    let plan_id = client.create_goal_plan(&user, &category, &1_000, &1, &0, &None);
+    client.set_roundup_goal(&user, &plan_id, &100);
+
+    client.deposit_flexi(&user, &1_234);
+    assert_eq!(client.get_flexi_balance(&user), 1_168);
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        66
+    );
+    assert_eq!(client.get_user(&user).total_balance, 1_234);
+
+    // Exact multiples have nothing to round up
+    client.deposit_flexi(&user, &200);
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        66
+    );
+
+    client.set_roundup_goal(&user, &plan_id, &0);
+    assert!(client.get_roundup_goal(&user).is_none());
+}
+
+#[test]
+fn test_plan_deposits_and_withdrawals_keep_total_balance_reconciled() {
+    let (env, client, user) = setup();
+    client.deposit_flexi(&user, &1_000);
+    let flexi_id = client.create_savings_plan(&user, &PlanType::Flexi, &200);
+    let lock_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &300);
+    client.create_lock_save(&user, &400, &YEAR, &false);
+
+    client.deposit_to_plan(&user, &flexi_id, &50);
+    client.deposit_to_plan(&user, &lock_id, &100);
+    assert_eq!(client.withdraw_from_plan(&user, &flexi_id, &150), 150);
+    client.convert_flexi_to_lock(&user, &250, &YEAR);
+    assert_eq!(client.check_balance_invariant(&user), 1_900);
+
+    let res = client.try_withdraw_from_plan(&user, &flexi_id, &101);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientBalance));
+    let res = client.try_withdraw_from_plan(&user, &lock_id, &400);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanLocked));
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    client.withdraw_from_plan(&user, &lock_id, &400);
+    client.check_balance_invariant(&user);
+}
+
+#[test]
+fn test_close_plan_pays_out_and_blocks_deposits() {
+    let (env, client, user) = setup();
+    let flexi_id = client.create_savings_plan(&user, &PlanType::Flexi, &500);
+    let lock_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &1_000);
+    let category = client.add_goal_category(&Symbol::new(&env, "car"));
+    let goal_id = client.create_goal_plan(&user, &category, &1_000, &1, &1_000, &None);
+
+    assert_eq!(client.close_plan(&user, &flexi_id), 500);
+    let plan = client.get_savings_plan(&user, &flexi_id).unwrap();
+    assert!(plan.is_withdrawn);
+    assert_eq!(plan.balance, 0);
+    let res = client.try_deposit_to_plan(&user, &flexi_id, &100);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+    let res = client.try_close_plan(&user, &flexi_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+
+    let res = client.try_close_plan(&user, &lock_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanLocked));
+
+    // A completed goal has no other way out, so closing pays it to Flexi
+    assert_eq!(client.close_plan(&user, &goal_id), 1_000);
+    assert_eq!(client.get_flexi_balance(&user), 1_000);
+    assert!(
+        client
+            .get_savings_plan(&user, &goal_id)
+            .unwrap()
+            .is_withdrawn
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    assert!(client.close_plan(&user, &lock_id) >= 1_000);
+    assert!(
+        client
+            .get_savings_plan(&user, &lock_id)
+            .unwrap()
+            .is_withdrawn
+    );
+    client.check_balance_invariant(&user);
+}
+
+#[test]
+fn test_split_plan_preserves_lock_and_goal_terms() {
+    let (env, client, user) = setup();
+    let lock_id = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &1_000);
+    let category = client.add_goal_category(&Symbol::new(&env, "trip"));
+    let goal_id = client.create_goal_plan(&user, &category, &2_000, &1, &800, &None);
+
+    let res = client.try_split_plan(&user, &lock_id, &1_000);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientBalance));
+
+    let split_lock = client.split_plan(&user, &lock_id, &400);
+    let original = client.get_savings_plan(&user, &lock_id).unwrap();
+    let carved = client.get_savings_plan(&user, &split_lock).unwrap();
+    assert_eq!(original.balance, 600);
+    assert_eq!(carved.balance, 400);
+    assert_eq!(carved.plan_type, PlanType::Lock(YEAR));
+    assert_eq!(carved.interest_rate, original.interest_rate);
+
+    let split_goal = client.split_plan(&user, &goal_id, &200);
+    let original = client.get_savings_plan(&user, &goal_id).unwrap();
+    let carved = client.get_savings_plan(&user, &split_goal).unwrap();
+    assert_eq!(original.plan_type, PlanType::Goal(category, 1_500, 1));
+    assert_eq!(carved.plan_type, PlanType::Goal(category, 500, 1));
+    assert_eq!(original.balance + carved.balance, 800);
+
+    assert_eq!(client.check_balance_invariant(&user), 1_800);
+}
+
+#[test]
+fn test_merge_plans_weights_interest_and_closes_source() {
+    let (env, client, user) = setup();
+    let first = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &1_000);
+    env.ledger().with_mut(|li| li.timestamp = YEAR / 2);
+    let second = client.create_savings_plan(&user, &PlanType::Lock(YEAR), &1_000);
+    let other = client.create_savings_plan(&user, &PlanType::Lock(2 * YEAR), &1_000);
+
+    let res = client.try_merge_plans(&user, &other, &first);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+
+    client.merge_plans(&user, &second, &first);
+    let merged = client.get_savings_plan(&user, &first).unwrap();
+    assert_eq!(merged.balance, 2_000);
+    assert_eq!(merged.start_time, YEAR / 4);
+    let source = client.get_savings_plan(&user, &second).unwrap();
+    assert!(source.is_withdrawn);
+    assert_eq!(source.balance, 0);
+
+    let res = client.try_merge_plans(&user, &second, &first);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+    assert_eq!(client.check_balance_invariant(&user), 3_000);
+}
+
+#[test]
+fn test_frozen_plan_blocks_withdrawals_until_unfreeze_delay_passes() {
+    let (env, client, user) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &1_000);
+    client.freeze_plan(&user, &plan_id, &DAY);
+
+    let res = client.try_withdraw_from_plan(&user, &plan_id, &100);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanFrozen));
+    let res = client.try_close_plan(&user, &plan_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanFrozen));
+    // Nor can the balance be split out into an unfrozen plan
+    let res = client.try_split_plan(&user, &plan_id, &999);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanFrozen));
+    client.deposit_to_plan(&user, &plan_id, &100);
+
+    assert_eq!(client.unfreeze_plan(&user, &plan_id), DAY);
+    env.ledger().with_mut(|li| li.timestamp = DAY / 2);
+    // Asking again doesn't restart the delay
+    assert_eq!(client.unfreeze_plan(&user, &plan_id), DAY);
+    let res = client.try_withdraw_from_plan(&user, &plan_id, &100);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanFrozen));
+
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+    assert_eq!(client.withdraw_from_plan(&user, &plan_id, &100), 100);
+}
+
+#[test]
+fn test_plan_label_length_is_validated() {
+    let (env, client, user) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &100);
+    assert_eq!(client.get_plan_label(&user, &plan_id), None);
+
+    let label = String::from_str(&env, "🏠 House fund");
+    client.set_plan_label(&user, &plan_id, &label);
+    assert_eq!(client.get_plan_label(&user, &plan_id), Some(label));
+
+    let long = String::from_str(&env, "🏠🏠🏠🏠🏠🏠🏠🏠 and a bit more");
+    let res = client.try_set_plan_label(&user, &plan_id, &long);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidPlanConfig));
+
+    client.set_plan_label(&user, &plan_id, &String::from_str(&env, ""));
+    assert_eq!(client.get_plan_label(&user, &plan_id), None);
+}
+
+#[test]
+fn test_archive_plans_moves_withdrawn_plans_out_of_active_list() {
+    let (_env, client, user) = setup();
+    let kept = client.create_savings_plan(&user, &PlanType::Flexi, &100);
+    let closed = client.create_savings_plan(&user, &PlanType::Flexi, &200);
+    let also_closed = client.create_savings_plan(&user, &PlanType::Flexi, &300);
+    client.close_plan(&user, &closed);
+    client.close_plan(&user, &also_closed);
+    assert_eq!(client.get_user_savings_plans(&user).len(), 3);
+
+    assert_eq!(client.archive_plans(&user), 2);
+    assert_eq!(client.archive_plans(&user), 0);
+    let active = client.get_user_savings_plans(&user);
+    assert_eq!(active.len(), 1);
+    assert_eq!(active.get(0).unwrap().plan_id, kept);
+
+    let page = client.get_archived_plans(&user, &1, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().plan_id, also_closed);
+    assert_eq!(client.get_archived_plans(&user, &0, &10).len(), 2);
+}
+
+#[test]
+fn test_plan_deposits_collect_savings_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    client.initialize(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+    let victim = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    client.initialize_user(&victim);
+    client.initialize_user(&attacker);
+    StellarAssetClient::new(&env, &token).mint(&victim, &10_000);
+    StellarAssetClient::new(&env, &token).mint(&attacker, &6_000);
+    let balances = TokenClient::new(&env, &token);
+    client.deposit_flexi(&victim, &10_000);
+
+    let plan_id = client.create_savings_plan(&attacker, &PlanType::Lock(YEAR), &5_000);
+    client.deposit_lock(&attacker, &plan_id, &1_000);
+    assert_eq!(balances.balance(&attacker), 0);
+    assert_eq!(balances.balance(&contract_id), 16_000);
+
+    // Without tokens there is nothing to lock
+    assert!(client
+        .try_create_savings_plan(&attacker, &PlanType::Lock(YEAR), &5_000)
+        .is_err());
+    assert!(client.try_deposit_lock(&attacker, &plan_id, &1).is_err());
+}
+
+#[test]
+fn test_flexi_plan_withdrawals_send_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    client.initialize(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &token).mint(&user, &3_000);
+    let balances = TokenClient::new(&env, &token);
+
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &2_000);
+    client.deposit_to_plan(&user, &plan_id, &1_000);
+    assert_eq!(balances.balance(&user), 0);
+    assert_eq!(client.get_user(&user).total_balance, 3_000);
+
+    assert_eq!(client.withdraw_from_plan(&user, &plan_id, &1_000), 1_000);
+    assert_eq!(balances.balance(&user), 1_000);
+    assert_eq!(client.close_plan(&user, &plan_id), 2_000);
+    assert_eq!(balances.balance(&user), 3_000);
+    assert_eq!(balances.balance(&contract_id), 0);
+    assert_eq!(client.get_user(&user).total_balance, 0);
+}
+
+#[test]
+fn test_plan_deposits_and_withdrawals_pay_transfer_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    client.initialize(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(token.clone()),
+    );
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &token).mint(&user, &3_000);
+    let balances = TokenClient::new(&env, &token);
+    // 1% on deposits, 2% on withdrawals
+    client.set_transfer_fees(&100, &200);
+
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &2_000);
+    client.deposit_to_plan(&user, &plan_id, &1_000);
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        2_970
+    );
+    assert_eq!(client.get_treasury_balance(), 30);
+
+    assert_eq!(client.withdraw_from_plan(&user, &plan_id, &1_000), 980);
+    assert_eq!(client.close_plan(&user, &plan_id), 1_931);
+    assert_eq!(balances.balance(&user), 2_911);
+    assert_eq!(client.get_treasury_balance(), 89);
+    assert_eq!(balances.balance(&contract_id), 89);
+
+    let stats = client.get_fee_stats();
+    assert_eq!(stats.deposit, 30);
+    assert_eq!(stats.withdrawal, 59);
+}
@@ -1,6 +1,9 @@
-use crate::storage_types::DataKey;
+use crate::storage_types::{DataKey, DataKeyExt, LockRateTier};
 use crate::SavingsError;
-use soroban_sdk::Env;
+use soroban_sdk::{Env, Vec};
+
+/// Rate for Lock plans while no tier table has been configured
+pub const DEFAULT_LOCK_RATE: u32 = 500;
 
 // --- Admin Setters ---
 
@@ -38,6 +41,24 @@ pub fn set_lock_rate(env: &Env, duration_days: u64, rate: i128) -> Result<(), Sa
     Ok(())
 }
 
+/// Replaces the Lock plan rate tier table.
+///
+/// Tiers must be ordered by strictly increasing `min_duration_days`. An empty
+/// table restores `DEFAULT_LOCK_RATE` for every duration.
+pub fn set_lock_rate_tiers(env: &Env, tiers: Vec<LockRateTier>) -> Result<(), SavingsError> {
+    for i in 1..tiers.len() {
+        let prev = tiers.get(i - 1).unwrap();
+        let tier = tiers.get(i).unwrap();
+        if tier.min_duration_days <= prev.min_duration_days {
+            return Err(SavingsError::InvalidInterestRate);
+        }
+    }
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::LockRateTiers, &tiers);
+    Ok(())
+}
+
 // --- Getters ---
 
 pub fn get_flexi_rate(env: &Env) -> i128 {
@@ -68,6 +89,28 @@ pub fn get_lock_rate(env: &Env, duration_days: u64) -> Result<i128, SavingsError
         .ok_or(SavingsError::PlanNotFound)
 }
 
+pub fn get_lock_rate_tiers(env: &Env) -> Vec<LockRateTier> {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::LockRateTiers)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Looks up the Lock plan rate for a lock of `duration_days`.
+///
+/// Uses the highest tier whose minimum the duration reaches, or
+/// `DEFAULT_LOCK_RATE` if no tier matches.
+pub fn lock_tier_rate(env: &Env, duration_days: u64) -> u32 {
+    let mut rate = DEFAULT_LOCK_RATE;
+    for tier in get_lock_rate_tiers(env).iter() {
+        if duration_days < tier.min_duration_days {
+            break;
+        }
+        rate = tier.rate;
+    }
+    rate
+}
+
 // --- Interest Calculation Helpers ---
 
 pub fn calculate_flexi_interest(balance: i128, rate: i128, duration_seconds: u64) -> i128 {
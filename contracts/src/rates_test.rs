@@ -1,4 +1,4 @@
-use crate::{NesteraContract, NesteraContractClient, SavingsError};
+use crate::{LockRateTier, NesteraContract, NesteraContractClient, PlanType, SavingsError};
 use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
 
 fn setup() -> (Env, NesteraContractClient<'static>, Address) {
@@ -9,7 +9,7 @@ fn setup() -> (Env, NesteraContractClient<'static>, Address) {
     let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
 
     env.mock_all_auths();
-    client.initialize(&admin, &admin_pk);
+    client.initialize(&admin, &admin_pk, &None);
 
     (env, client, admin)
 }
@@ -121,3 +121,53 @@ fn test_invalid_rates() {
     let res = client.try_set_flexi_rate(&-100);
     assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidInterestRate));
 }
+
+#[test]
+fn test_lock_plan_rate_follows_duration_tier() {
+    let (env, client, _admin) = setup();
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+
+    let day = 86_400u64;
+    client.set_lock_rate_tiers(&soroban_sdk::vec![
+        &env,
+        LockRateTier {
+            min_duration_days: 0,
+            rate: 200,
+        },
+        LockRateTier {
+            min_duration_days: 30,
+            rate: 400,
+        },
+        LockRateTier {
+            min_duration_days: 90,
+            rate: 600,
+        },
+        LockRateTier {
+            min_duration_days: 365,
+            rate: 900,
+        },
+    ]);
+
+    let short = client.create_savings_plan(&user, &PlanType::Lock(10 * day), &100);
+    let medium = client.create_savings_plan(&user, &PlanType::Lock(60 * day), &100);
+    let long = client.create_savings_plan(&user, &PlanType::Lock(400 * day), &100);
+    let rate_of = |id: u64| client.get_savings_plan(&user, &id).unwrap().interest_rate;
+    assert_eq!(rate_of(short), 200);
+    assert_eq!(rate_of(medium), 400);
+    assert_eq!(rate_of(long), 900);
+
+    // Tiers must be strictly increasing
+    let res = client.try_set_lock_rate_tiers(&soroban_sdk::vec![
+        &env,
+        LockRateTier {
+            min_duration_days: 30,
+            rate: 400,
+        },
+        LockRateTier {
+            min_duration_days: 30,
+            rate: 600,
+        },
+    ]);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidInterestRate));
+}
@@ -0,0 +1,175 @@
+use crate::ensure_not_paused;
+use crate::errors::SavingsError;
+use crate::group;
+use crate::storage_types::{DataKey, GroupMode, GroupSave, GroupStatus, RotationState};
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+/// Switches a group into rotating (ROSCA / ajo) mode.
+///
+/// The current member list becomes the payout order. Each round, every
+/// member contributes exactly `contribution_amount`; once all have paid, the
+/// round's pot is paid to the next member in the order. The group settles
+/// after every member has received one payout.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The group ID
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `Unauthorized` - If the caller is not the creator
+/// * `PlanCompleted` - If the group is completed or closed
+/// * `InvalidGroupConfig` - If the group is already rotating, holds pooled
+///   funds, or has fewer than two members
+pub fn start_rotation(env: &Env, creator: Address, group_id: u64) -> Result<(), SavingsError> {
+    ensure_not_paused(env)?;
+    creator.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group = load_group(env, group_id)?;
+
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if group.is_completed || group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if group.mode == GroupMode::Rotating || group.current_amount != 0 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    let members = group::get_group_members(env, group_id);
+    if members.len() < 2 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    let state = RotationState {
+        payout_order: members.clone(),
+        current_round: 0,
+        paid_out: Vec::new(env),
+        round_contributors: Vec::new(env),
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupRotation(group_id), &state);
+
+    group.mode = GroupMode::Rotating;
+    env.storage().persistent().set(&group_key, &group);
+    // Rotations are closed to new members
+    group::delist_public_group(env, group_id);
+
+    env.events()
+        .publish((symbol_short!("rot_start"), group_id), members.len());
+
+    Ok(())
+}
+
+/// Pays the open round's pot to the next member in the rotation.
+///
+/// Anyone may trigger the payout once every member has contributed to the
+/// round. The pot is credited to the recipient's Flexi balance.
+///
+/// # Returns
+/// `Ok(i128)` - The amount paid out
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `InvalidGroupConfig` - If the group is not rotating
+/// * `PlanCompleted` - If every member has already been paid
+/// * `GroupCycleIncomplete` - If some members have not paid into the round
+pub fn payout_rotation(env: &Env, group_id: u64) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group = load_group(env, group_id)?;
+
+    if group.mode != GroupMode::Rotating {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    if group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let mut state = get_rotation(env, group_id).ok_or(SavingsError::DataCorruption)?;
+    if state.round_contributors.len() < state.payout_order.len() {
+        return Err(SavingsError::GroupCycleIncomplete);
+    }
+
+    let recipient = state
+        .payout_order
+        .get(state.current_round)
+        .ok_or(SavingsError::DataCorruption)?;
+    let pot = group.current_amount;
+
+    state.paid_out.push_back(recipient.clone());
+    state.current_round += 1;
+    state.round_contributors = Vec::new(env);
+
+    group.current_amount = 0;
+    if state.current_round >= state.payout_order.len() {
+        group.is_completed = true;
+        group.status = GroupStatus::Settled;
+        group::close_member_plans(env, group_id, &state.payout_order);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupRotation(group_id), &state);
+    env.storage().persistent().set(&group_key, &group);
+
+    group::pay_member(env, group_id, &recipient, pot)?;
+
+    env.events().publish(
+        (symbol_short!("rot_pay"), recipient, group_id),
+        state.current_round,
+    );
+
+    Ok(pot)
+}
+
+/// Returns the rotation state of a group, if it is rotating.
+pub fn get_rotation(env: &Env, group_id: u64) -> Option<RotationState> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GroupRotation(group_id))
+}
+
+/// Records a member's payment into the open round of a rotating group.
+///
+/// Each member pays exactly the group's `contribution_amount` once per round.
+pub(crate) fn record_contribution(
+    env: &Env,
+    group: &GroupSave,
+    user: &Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    let mut state = get_rotation(env, group.id).ok_or(SavingsError::DataCorruption)?;
+
+    if amount != group.contribution_amount {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if state.round_contributors.contains(user) {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    state.round_contributors.push_back(user.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupRotation(group.id), &state);
+
+    Ok(())
+}
+
+/// Members who have paid into the open round of a rotating group.
+pub(crate) fn current_round_contributors(env: &Env, group_id: u64) -> Vec<Address> {
+    get_rotation(env, group_id)
+        .map(|state| state.round_contributors)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+// ========== Helper Functions ==========
+
+fn load_group(env: &Env, group_id: u64) -> Result<GroupSave, SavingsError> {
+    group::get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)
+}
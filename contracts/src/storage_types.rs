@@ -1,4 +1,4 @@
-use soroban_sdk::{contracterror, contracttype, Address, String, Symbol};
+use soroban_sdk::{contracterror, contracttype, Address, BytesN, Map, String, Symbol, Vec};
 
 /// Represents the different types of savings plans available in Nestera
 #[contracttype]
@@ -6,7 +6,9 @@ use soroban_sdk::{contracterror, contracttype, Address, String, Symbol};
 pub enum PlanType {
     Flexi,
     Lock(u64),
-    Goal(Symbol, i128, u32),
+    /// (category_id, target_amount, contribution_type); categories are
+    /// registered by the admin
+    Goal(u32, i128, u32),
     Group(u64, bool, u32, i128),
 }
 
@@ -29,6 +31,8 @@ pub struct SavingsPlan {
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct User {
+    /// Sum of the user's Flexi balance, plans and Lock Saves, derived when
+    /// the user is read; not maintained in storage
     pub total_balance: i128,
     pub savings_count: u32,
 }
@@ -61,6 +65,326 @@ pub struct GroupSave {
     pub start_time: u64,
     pub end_time: u64,
     pub is_completed: bool,
+    /// What happens to a member's contribution when they leave
+    pub leave_policy: LeavePolicy,
+    /// Receives the whole pot on distribution; pro-rata split when `None`
+    pub payout_beneficiary: Option<Address>,
+    /// Lifecycle status of the group's pooled funds
+    pub status: GroupStatus,
+    /// How pooled funds are paid out
+    pub mode: GroupMode,
+    /// What happens to an incomplete group's funds after `end_time`
+    pub expiry_policy: ExpiryPolicy,
+    /// Maximum number of members; 0 means no cap
+    pub max_members: u32,
+    /// Percentage of members that must vote for a group change to pass
+    pub change_quorum: u32,
+    /// Member approvals a withdrawal request needs; 0 disables withdrawals
+    pub withdrawal_threshold: u32,
+    /// Smallest amount accepted in a single contribution
+    pub min_contribution: Option<i128>,
+    /// Cap on a member's total contributions to the group
+    pub max_contribution_per_member: Option<i128>,
+    /// Interest earned on `current_amount`, paid out on distribution
+    pub accrued_interest: i128,
+    /// Timestamp up to which interest has been accrued
+    pub last_accrual: u64,
+    /// Length of a contribution cycle in seconds; 0 disables cycle tracking
+    pub cycle_duration: u64,
+    /// Amount due per cycle; `contribution_amount` applies when `None`
+    pub required_amount_per_cycle: Option<i128>,
+    /// Penalty per missed cycle in bps of the amount due, deducted at distribution
+    pub late_penalty_bps: u32,
+}
+
+/// A pending invitation to join a group
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupInvite {
+    pub invited_by: Address,
+    pub created_at: u64,
+    /// The invite can no longer be accepted after this timestamp
+    pub expires_at: u64,
+}
+
+/// Per-member contribution progress across a group's cycles
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemberCycleRecord {
+    /// Cycle in which the member joined; earlier cycles are never missed
+    pub joined_cycle: u32,
+    /// Number of cycles in which the member paid the full contribution
+    pub paid_cycles: u32,
+    /// Cycle `cycle_amount` refers to
+    pub current_cycle: u32,
+    /// Amount contributed during `current_cycle`
+    pub cycle_amount: i128,
+}
+
+/// A member's standing in a cycle-tracked group
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemberCycleStatus {
+    /// Cycle the group is currently in (0-based, counted from `start_time`)
+    pub current_cycle: u32,
+    /// Past cycles in which the member paid the full contribution
+    pub paid_cycles: u32,
+    /// Past cycles since joining in which the member did not pay in full
+    pub missed_cycles: u32,
+    /// Whether the member has already paid for the current cycle
+    pub paid_current: bool,
+    /// Penalty for missed cycles that will be deducted at distribution
+    pub late_penalty: i128,
+}
+
+/// An amendment to a running group's parameters
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GroupChange {
+    /// Replace the group's target amount
+    TargetAmount(i128),
+    /// Replace the group's member cap (0 removes the cap)
+    MaxMembers(u32),
+    /// Replace the group's payout beneficiary (`None` restores pro-rata payout)
+    PayoutBeneficiary(Option<Address>),
+}
+
+/// A member vote to amend a group's parameters
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupChangeProposal {
+    pub id: u64,
+    pub group_id: u64,
+    pub proposer: Address,
+    pub change: GroupChange,
+    /// Members who voted in favour
+    pub votes: Vec<Address>,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub is_executed: bool,
+}
+
+/// Fee charged from a creator's Flexi balance when a group is created
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GroupCreationFee {
+    /// Groups are free to create
+    None,
+    /// A fixed amount per group
+    Flat(i128),
+    /// Basis points of the group's target amount
+    Bps(u32),
+}
+
+/// Snapshot of the protocol's fee settings
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub group_creation_fee: GroupCreationFee,
+    pub early_break_fee_bps: u32,
+    pub fee_recipient: Option<Address>,
+    pub deposit_fee_bps: u32,
+    pub withdrawal_fee_bps: u32,
+}
+
+/// Lifetime fees charged, by type and by asset
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeStats {
+    pub deposit: i128,
+    pub withdrawal: i128,
+    pub performance: i128,
+    /// Early break fees and late penalties
+    pub penalties: i128,
+    /// Fees of every type in each asset; fees on internal balances without
+    /// an asset are left out
+    pub by_asset: Map<Address, i128>,
+}
+
+/// A transfer fee discount for users whose lifetime volume or balance
+/// reaches `min_amount`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTier {
+    pub min_amount: i128,
+    /// Taken off deposit and withdrawal fees, in basis points of the fee
+    pub discount_bps: u32,
+}
+
+/// Asset deployed to yield strategies and the liquidity kept back
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YieldConfig {
+    pub asset: Address,
+    /// Share of the contract's funds kept liquid, in basis points
+    pub buffer_bps: u32,
+}
+
+/// A registered yield strategy contract
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YieldStrategy {
+    pub address: Address,
+    /// Share of the deployable funds allocated to it, in basis points
+    pub weight_bps: u32,
+    /// Principal currently deployed to it
+    pub deployed: i128,
+}
+
+/// Accounting of funds deployed to yield strategies
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YieldState {
+    /// Principal currently deployed across strategies
+    pub deployed: i128,
+    /// Yield brought back from strategies so far
+    pub harvested: i128,
+}
+
+/// Withdrawal waiting for liquidity to be recalled from yield strategies
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalRequest {
+    pub id: u64,
+    pub user: Address,
+    pub asset: Address,
+    /// Amount owed, in canonical precision
+    pub amount: i128,
+    pub requested_at: u64,
+}
+
+/// Bounds of the withdrawal queue: requests `head..tail` are waiting
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WithdrawalQueue {
+    pub head: u64,
+    pub tail: u64,
+}
+
+/// Determines what happens to an incomplete group's funds after its deadline
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExpiryPolicy {
+    /// Members get their contributions back
+    Refund,
+    /// Whatever was raised is paid out as on completion
+    Distribute,
+}
+
+/// How a group's pooled funds are paid out
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GroupMode {
+    /// Funds accumulate towards the target and are distributed at completion
+    Pooled,
+    /// Rotating savings (ROSCA / ajo): each round's pot goes to one member
+    Rotating,
+}
+
+/// Payout schedule of a rotating group
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RotationState {
+    /// Members in the order they receive the pot (snapshot of join order)
+    pub payout_order: Vec<Address>,
+    /// Zero-based index into `payout_order` of the member paid next
+    pub current_round: u32,
+    /// Members who have already received their payout
+    pub paid_out: Vec<Address>,
+    /// Members who have paid into the open round
+    pub round_contributors: Vec<Address>,
+}
+
+/// Lifecycle status of a group's pooled funds
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GroupStatus {
+    /// Accepting members and contributions
+    Active,
+    /// Pool has been distributed
+    Settled,
+    /// Group was closed early and members were refunded
+    Cancelled,
+    /// Members and funds were folded into another group
+    Merged,
+}
+
+/// Determines what happens to a member's contribution when they leave a group
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LeavePolicy {
+    /// Contribution is returned to the member's Flexi balance
+    Refund,
+    /// Contribution stays in the group pool
+    Forfeit,
+}
+
+/// Kind of movement out of a group's pooled funds that a proposal authorizes
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GroupAction {
+    /// Payout of pooled funds to a member or designated recipient
+    Distribution,
+    /// Return of a member's own recorded contribution
+    Refund,
+    /// Loan of pooled funds to a recipient
+    Loan,
+}
+
+/// M-of-N approval policy guarding a group's pooled funds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupApprovalPolicy {
+    /// Members allowed to propose and approve actions (N)
+    pub approvers: Vec<Address>,
+    /// Number of distinct approvals required before execution (M)
+    pub threshold: u32,
+    /// Seconds a proposal stays open for approvals after creation
+    pub proposal_ttl: u64,
+}
+
+/// A pending request to move pooled funds out of a group
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupProposal {
+    pub id: u64,
+    pub group_id: u64,
+    pub proposer: Address,
+    pub action: GroupAction,
+    pub recipient: Address,
+    pub amount: i128,
+    /// Distinct approvers who have signed off so far
+    pub approvals: Vec<Address>,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub is_executed: bool,
+}
+
+/// One entry of a group's on-chain contribution log
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupContributionRecord {
+    pub contributor: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    /// The group's `current_amount` right after this contribution
+    pub running_total: i128,
+}
+
+/// A creator's request to withdraw from a group's pot, pending member approval
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupWithdrawalRequest {
+    pub id: u64,
+    pub group_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+    /// Members other than the creator who approved
+    pub approvals: Vec<Address>,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub is_executed: bool,
+    pub is_cancelled: bool,
 }
 
 /// Represents a Lock Save plan with fixed duration and maturity
@@ -74,6 +398,8 @@ pub struct LockSave {
     pub start_time: u64,
     pub maturity_time: u64,
     pub is_withdrawn: bool,
+    /// Re-lock principal plus interest for the same duration at maturity
+    pub rollover: bool,
 }
 
 /// Custom error types for the savings contract
@@ -116,6 +442,18 @@ pub struct AutoSave {
     pub is_active: bool,
 }
 
+/// A recurring debit into a savings plan, pulled from a token allowance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanAutoSave {
+    pub amount: i128,
+    /// Seconds between debits
+    pub interval: u64,
+    pub next_execution_time: u64,
+    /// Consecutive periods the allowance or balance could not cover
+    pub failures: u32,
+}
+
 /// Storage keys for the contract's persistent data
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -131,7 +469,8 @@ pub enum DataKey {
     WithdrawalFee,
     /// Protocol fee configuration
     PlatformFee,
-    /// Early break fee (basis points) for goal saves
+    /// Early break fee (basis points) under schema version 1; now part of
+    /// ProtocolConfig
     EarlyBreakFeeBps,
     /// Fee recipient for protocol/treasury fees
     FeeRecipient,
@@ -160,8 +499,44 @@ pub enum DataKey {
     NextGoalId,
     /// Maps (group_id, user) to their contribution amount
     GroupMemberContribution(u64, Address),
-    /// Maps group_id to list of member addresses
-    GroupMembers(u64),
+    /// Maps (group_id, index) to the member stored in that slot
+    GroupMemberByIndex(u64, u32),
+    /// Maps (group_id, member) to the member's slot index
+    GroupMemberIndex(u64, Address),
+    /// Maps group_id to its M-of-N GroupApprovalPolicy
+    GroupApprovalPolicy(u64),
+    /// Maps proposal ID to GroupProposal struct
+    GroupProposal(u64),
+    /// Stores the next auto-incrementing GroupProposal ID
+    NextGroupProposalId,
+    /// Maps group_id to the member nominated to take over as creator
+    GroupPendingOwner(u64),
+    /// Maps group_id to its RotationState (rotating groups only)
+    GroupRotation(u64),
+    /// Maps request ID to GroupWithdrawalRequest struct
+    GroupWithdrawalRequest(u64),
+    /// Stores the next auto-incrementing GroupWithdrawalRequest ID
+    NextGroupWithdrawalId,
+    /// Maps proposal ID to GroupChangeProposal struct
+    GroupChangeProposal(u64),
+    /// Stores the next auto-incrementing GroupChangeProposal ID
+    NextGroupChangeId,
+    /// Maps (group_id, invitee) to a pending GroupInvite
+    GroupInvite(u64, Address),
+    /// Maps group_id to the users waiting for a slot, next in line first
+    GroupWaitlist(u64),
+    /// Maps (group_id, member) to their MemberCycleRecord
+    GroupMemberCycle(u64, Address),
+    /// Maps (group_id, ring buffer slot) to a GroupContributionRecord
+    GroupHistory(u64, u32),
+    /// Maps group_id to the number of contributions ever logged
+    GroupHistoryCount(u64),
+    /// Maps a discovery index slot to the ID of a listed public group
+    PublicGroupByIndex(u32),
+    /// Maps a listed public group ID to its discovery index slot
+    PublicGroupIndex(u64),
+    /// Stores the number of listed public groups
+    PublicGroupCount,
     /// Maps schedule ID to AutoSave struct
     AutoSave(u64),
     /// Maps user to a list of their AutoSave schedule IDs
@@ -176,6 +551,476 @@ pub enum DataKey {
     LockRate(u64),
 }
 
+/// Additional storage keys. Contract enums are capped at 50 cases, which
+/// `DataKey` has reached, so newer keys live here.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKeyExt {
+    /// Lock plan interest rate tiers, ordered by minimum duration
+    LockRateTiers,
+    /// How matured lock plans are treated (LockMaturityPolicy)
+    LockMaturityPolicy,
+    /// Goal plan completion bonus settings (GoalBonusConfig)
+    GoalBonusConfig,
+    /// Funds available for goal completion bonuses
+    GoalBonusPool,
+    /// Marks a (user, plan_id) goal bonus as claimed
+    GoalBonusClaimed(Address, u64),
+    /// Maps (user, plan_id) to the goal plan's milestones
+    GoalMilestones(Address, u64),
+    /// Maps (user, plan_id) to the date a goal plan should be reached by
+    GoalTargetDate(Address, u64),
+    /// Maps a goal category ID to its name
+    GoalCategory(u32),
+    /// Number of registered goal categories (IDs run from 1)
+    GoalCategoryCount,
+    /// Maps user to their RoundupGoal settings
+    RoundupGoal(Address),
+    /// Maps user to their named Flexi buckets
+    FlexiBuckets(Address),
+    /// Maps user to their opt-in Flexi withdrawal cooldown in seconds
+    FlexiCooldown(Address),
+    /// Maps user to the timestamp of their last Flexi withdrawal
+    LastFlexiWithdrawal(Address),
+    /// Maps (user, plan_id) to the plan's PlanFreeze
+    PlanFreeze(Address, u64),
+    /// Maps (user, plan_id) to the plan's display label
+    PlanLabel(Address, u64),
+    /// Maps user to the IDs of their savings plans that are not archived
+    ActivePlans(Address),
+    /// Number of savings plans the user has archived
+    ArchivedPlanCount(Address),
+    /// Maps (user, archive index) to an archived savings plan ID
+    ArchivedPlan(Address, u32),
+    /// Maps (user, plan_id) to the plan's PlanAccrual
+    PlanAccrual(Address, u64),
+    /// Maps a PlanKind to how often its interest compounds
+    Compounding(PlanKind),
+    /// Maps a PlanKind to its scheduled RateChanges, earliest first
+    RateSchedule(PlanKind),
+    /// Yield asset and liquidity buffer (YieldConfig)
+    YieldConfig,
+    /// Registered yield strategies (Vec<YieldStrategy>)
+    YieldStrategies,
+    /// Total yield harvested from strategies
+    YieldHarvested,
+    /// Maps (user, plan_id) to the plan's ContributionStreak
+    ContributionStreak(Address, u64),
+    /// Rate boosts for contribution streaks, ordered by minimum streak
+    StreakBoostTiers,
+    /// Token interest can be claimed in (RewardsToken)
+    RewardsToken,
+    /// Share of early break fees paid into the penalty pool (PenaltyPoolConfig)
+    PenaltyPoolConfig,
+    /// Penalty pool balances and epoch (PenaltyPool)
+    PenaltyPool,
+    /// Maps user to their PenaltyShare
+    PenaltyShare(Address),
+    /// SEP-41 token deposits and withdrawals are made in
+    SavingsToken,
+    /// Whitelisted assets besides the savings token (Vec<Address>)
+    SupportedAssets,
+    /// Maps (user, plan_id) to the asset of a plan not in the savings token
+    PlanAsset(Address, u64),
+    /// Maps group_id to the asset of a group not in the savings token
+    GroupAsset(u64),
+    /// Maps (user, asset) to the user's balance of a non-savings-token asset
+    AssetBalance(Address, Address),
+    /// Stellar Asset Contract of native XLM
+    NativeAsset,
+    /// Maps (user, plan_id) to the plan's allowance-based PlanAutoSave
+    PlanAutoSave(Address, u64),
+    /// DEX router used for swap-on-deposit
+    SwapRouter,
+    /// Price oracle settings (OracleConfig)
+    OracleConfig,
+    /// Maps an asset to the last fresh PriceData read from the oracle
+    LastPrice(Address),
+    /// Maps (user, plan_id) to a goal's target in USD
+    GoalUsdTarget(Address, u64),
+    /// Maps a supported asset to its token decimals
+    AssetDecimals(Address),
+    /// Bounds of the withdrawal queue (WithdrawalQueue)
+    WithdrawalQueue,
+    /// Maps request id to a queued WithdrawalRequest
+    WithdrawalRequest(u64),
+}
+
+/// Storage keys of emergency and administrative controls
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ControlDataKey {
+    /// Pause flag for one kind of operation (bool)
+    PausedScope(PauseScope),
+    /// Version of the storage layout (u32)
+    SchemaVersion,
+    /// Seconds between queueing a sensitive action and executing it (u64)
+    TimelockDelay,
+    /// Id the next queued action will get (u64)
+    NextTimelockId,
+    /// Maps an id to its QueuedAction
+    QueuedAction(u64),
+    /// Protocol-wide parameters (ProtocolConfig)
+    ProtocolConfig,
+    /// Maps a frozen account to its AccountFreeze
+    FrozenAccount(Address),
+    /// Deposit and withdrawal fees held by the contract (i128)
+    TreasuryBalance,
+    /// Address allowed to withdraw from the treasury
+    Treasurer,
+    /// Cumulative performance fees taken into the treasury (i128)
+    PerformanceFees,
+    /// Marks an address as exempt from deposit and withdrawal fees (bool)
+    FeeExempt(Address),
+    /// Maps a user to the user who referred them (Address)
+    Referrer(Address),
+    /// How penalties are split between treasury, pool and burn
+    /// (PenaltyRouting)
+    PenaltyRouting,
+    /// Lifetime fees charged (FeeStats)
+    FeeStats,
+    /// Transfer fee discount tiers (Vec<FeeTier>)
+    FeeTiers,
+    /// Maps a user to their lifetime deposit and withdrawal volume
+    /// (i128)
+    UserVolume(Address),
+}
+
+/// Why and since when the admin froze an account
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountFreeze {
+    /// Reason code chosen by the operator, e.g. for a compliance request
+    pub reason: u32,
+    pub frozen_at: u64,
+}
+
+/// Protocol-wide parameters
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolConfig {
+    /// Smallest Flexi deposit, lock, goal deposit or group contribution
+    pub min_deposit: i128,
+    /// Fee, in basis points, on early breaks of lock and goal plans
+    pub early_break_fee_bps: u32,
+    /// Fee, in basis points, on Flexi and savings plan deposits
+    pub deposit_fee_bps: u32,
+    /// Fee, in basis points, on Flexi and savings plan withdrawals
+    pub withdrawal_fee_bps: u32,
+    /// Performance fee, in basis points of earned interest, by plan kind;
+    /// kinds not listed pay none
+    pub performance_fee_bps: Map<PlanKind, u32>,
+    /// Share, in basis points, of a referred user's fees and penalties paid
+    /// to their referrer
+    pub referral_share_bps: u32,
+    /// Fee charged from a creator's Flexi balance when a group is created
+    pub group_creation_fee: GroupCreationFee,
+    /// Most members any group may have, on top of its own cap; zero for none
+    pub max_group_members: u32,
+    /// Most users a group's waitlist holds
+    pub max_waitlist_size: u32,
+    /// Seconds a group withdrawal request stays open for approvals
+    pub withdrawal_request_ttl: u64,
+    /// Seconds a proposed group change stays open for votes
+    pub group_change_ttl: u64,
+    /// Failed AutoSave runs in a row after which the schedule is removed
+    pub max_autosave_failures: u32,
+}
+
+/// A sensitive admin action that goes through the timelock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TimelockAction {
+    SetFlexiRate(i128),
+    SetGoalRate(i128),
+    SetGroupRate(i128),
+    /// Lock duration in days and rate
+    SetLockRate(u64, i128),
+    /// Plan kind, rate in bps and when it takes effect
+    SetInterestRate(PlanKind, u32, u64),
+    SetEarlyBreakFeeBps(u32),
+    SetFeeRecipient(Address),
+    SetGroupCreationFee(GroupCreationFee),
+    /// Yield asset and liquidity buffer in bps
+    SetYieldConfig(Address, u32),
+    /// Strategy address and weight in bps
+    RegisterStrategy(Address, u32),
+    /// Strategy address and weight in bps
+    SetStrategyWeight(Address, u32),
+    RemoveStrategy(Address),
+    /// Hash of the uploaded WASM to upgrade to
+    Upgrade(BytesN<32>),
+    SetTimelockDelay(u64),
+    UpdateConfig(ProtocolConfig),
+    /// Deposit and withdrawal fees in bps
+    SetTransferFees(u32, u32),
+    /// Recipient and amount of a treasury withdrawal
+    WithdrawTreasury(Address, i128),
+    SetTreasurer(Address),
+    /// Plan kind and performance fee in bps
+    SetPerformanceFee(PlanKind, u32),
+    /// Referral share of fees and penalties in bps
+    SetReferralShare(u32),
+    /// Transfer fee discount tiers
+    SetFeeTiers(Vec<FeeTier>),
+    SetLockRateTiers(Vec<LockRateTier>),
+    SetCompounding(PlanKind, CompoundingFrequency),
+    SetStreakBoostTiers(Vec<StreakBoostTier>),
+    /// Rewards token and its exchange rate
+    SetRewardsToken(Address, i128),
+    /// Pool share of penalties in bps and epoch length in seconds
+    SetPenaltyPool(u32, u64),
+    SetPenaltyRouting(PenaltyRouting),
+    SetLockMaturityPolicy(LockMaturityPolicy),
+    SetGoalBonusConfig(GoalBonusConfig),
+    Rebalance,
+}
+
+/// An action waiting in the timelock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedAction {
+    pub action: TimelockAction,
+    /// Ledger timestamp from which anyone can execute the action
+    pub execute_after: u64,
+}
+
+/// Storage keys of signed minting and burning
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MintDataKey {
+    /// Marks a signed payload, by its SHA-256 hash, as redeemed
+    ConsumedPayload(BytesN<32>),
+    /// Marks a signed payload, by its SHA-256 hash, as revoked by the admin
+    RevokedPayload(BytesN<32>),
+    /// Maps user to their minted balance
+    MintedBalance(Address),
+    /// Cumulative amount ever minted
+    TotalMinted,
+    /// Cumulative amount ever burned
+    TotalBurned,
+    /// Maximum outstanding minted supply
+    SupplyCap,
+    /// Ed25519 keys allowed to sign mint and burn payloads (Vec<BytesN<32>>)
+    MintSigners,
+    /// Number of signers that must sign a payload (u32)
+    MintThreshold,
+    /// Admin public key staged by `rotate_admin_key`, awaiting confirmation
+    PendingAdminKey,
+    /// Per-user mint limits (MintLimits)
+    MintLimits,
+    /// Maps user to their current MintWindow
+    MintWindow(Address),
+    /// Maps a delegated minter's public key to its Minter record
+    Minter(BytesN<32>),
+    /// Maps a redeemed payload's hash to the keys that authorized it
+    MintAuthorizers(BytesN<32>),
+    /// Maps a secp256k1 signer's key id to its SEC-1 public key (BytesN<65>)
+    Secp256k1Key(BytesN<32>),
+}
+
+/// Kind of operation that can be paused on its own
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PauseScope {
+    /// Deposits and contributions into plans and groups
+    Deposits,
+    /// Withdrawals from plans, groups and asset balances
+    Withdrawals,
+    /// Minting from signed payloads
+    Mint,
+}
+
+/// Savings plan type without its parameters, used for per-type settings
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlanKind {
+    Flexi,
+    Lock,
+    Goal,
+    Group,
+}
+
+impl PlanType {
+    pub fn kind(&self) -> PlanKind {
+        match self {
+            PlanType::Flexi => PlanKind::Flexi,
+            PlanType::Lock(_) => PlanKind::Lock,
+            PlanType::Goal(..) => PlanKind::Goal,
+            PlanType::Group(..) => PlanKind::Group,
+        }
+    }
+}
+
+/// A plan type's interest rate from a given time on
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateChange {
+    pub rate_bps: u32,
+    pub effective_at: u64,
+}
+
+/// How often accrued interest is added to the balance it earns on
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompoundingFrequency {
+    /// Simple interest on the balance
+    None,
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// Interest accrual bookkeeping for a savings plan
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanAccrual {
+    /// When the plan's interest was last settled
+    pub updated_at: u64,
+    /// Settled interest not yet claimed
+    pub unclaimed: i128,
+    /// Interest credited to the plan so far
+    pub total_accrued: i128,
+}
+
+/// A user's self-imposed block on withdrawing from one of their plans
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanFreeze {
+    /// Seconds between asking to unfreeze and withdrawals reopening
+    pub unfreeze_delay: u64,
+    /// When withdrawals reopen, once an unfreeze has been requested
+    pub unfreeze_at: Option<u64>,
+}
+
+/// A named portion of a user's Flexi balance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlexiBucket {
+    pub name: Symbol,
+    pub balance: i128,
+}
+
+/// Where the round-ups of a user's Flexi deposits go
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundupGoal {
+    pub plan_id: u64,
+    /// Deposits are rounded up to a multiple of this amount
+    pub rounding_unit: i128,
+}
+
+/// Progress of a goal plan towards its target
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalProgress {
+    pub balance: i128,
+    pub target: i128,
+    /// Progress in basis points of the target, capped at 10_000
+    pub progress_bps: u32,
+    /// Average amount saved per day since the plan started
+    pub daily_rate: i128,
+    /// When the target is expected to be reached at the average rate, or was
+    /// reached; `None` if nothing has been saved yet
+    pub projected_completion: Option<u64>,
+}
+
+/// A registered goal plan category
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalCategory {
+    pub id: u32,
+    pub name: Symbol,
+}
+
+/// What a goal plan still needs to reach its target by its target date
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RequiredContribution {
+    pub remaining: i128,
+    pub per_week: i128,
+    pub per_month: i128,
+    /// The balance is behind a straight-line path to the target
+    pub off_track: bool,
+}
+
+/// A progress milestone within a goal plan
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalMilestone {
+    /// Percentage of the goal's target
+    pub percent: u32,
+    pub reached: bool,
+}
+
+/// Admin configuration for goal plan completion bonuses
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalBonusConfig {
+    /// Bonus in basis points of the goal's target
+    pub bonus_bps: u32,
+    /// Goals must be reached within this many seconds of the plan's start
+    pub deadline: u64,
+}
+
+/// What happens to funds left in a lock plan after its grace period
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IdleFundsPolicy {
+    /// Keepers may sweep the plan into the owner's Flexi balance
+    SweepToFlexi,
+    /// The plan keeps earning at this annual rate (bps) until withdrawn
+    ReducedRate(u32),
+}
+
+/// Admin configuration for matured lock plans
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockMaturityPolicy {
+    /// Seconds after maturity during which the plan is left untouched
+    pub grace_period: u64,
+    pub idle_policy: IdleFundsPolicy,
+}
+
+/// A Lock plan interest rate tier.
+///
+/// Applies to locks of at least `min_duration_days`, up to the next tier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockRateTier {
+    pub min_duration_days: u64,
+    /// Annual rate in basis points (e.g., 500 = 5.00%)
+    pub rate: u32,
+}
+
+/// Rate boost for plans with a contribution streak of at least `min_streak`
+/// weeks, up to the next tier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreakBoostTier {
+    pub min_streak: u32,
+    /// Added to the plan rate, in basis points
+    pub boost_bps: u32,
+}
+
+/// Token that interest can be paid in instead of the savings asset
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardsToken {
+    pub token: Address,
+    /// Token units per unit of interest, scaled by `interest::SCALE`
+    pub exchange_rate: i128,
+}
+
+/// Consecutive weeks in which a plan received a deposit
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContributionStreak {
+    pub count: u32,
+    /// Week (since the plan started) of the last counted deposit
+    pub last_period: u64,
+}
+
 /// Payload structure that the admin signs off-chain
 /// The user submits this along with the signature to mint tokens
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -189,6 +1034,81 @@ pub struct MintPayload {
     pub timestamp: u64,
     /// Expiry duration in seconds (signature valid for timestamp + expiry_duration)
     pub expiry_duration: u64,
+    /// Lock or goal plan of the user to credit instead of their minted balance
+    pub target_plan_id: Option<u64>,
+}
+
+/// Payload the admin signs off-chain to burn a user's minted balance, e.g.
+/// once an off-ramp payout has been sent
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BurnPayload {
+    /// The user whose minted balance is burned
+    pub user: Address,
+    pub amount: i128,
+    /// Unix timestamp when the signature was created
+    pub timestamp: u64,
+    /// Expiry duration in seconds (signature valid for timestamp + expiry_duration)
+    pub expiry_duration: u64,
+}
+
+/// Limits on what a single user can mint, against a compromised signer
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintLimits {
+    /// Largest single mint; zero for no limit
+    pub max_single: i128,
+    /// Most a user can mint within 24 hours; zero for no limit
+    pub max_per_window: i128,
+}
+
+/// What a user has minted since their 24-hour window opened
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintWindow {
+    pub start: u64,
+    pub minted: i128,
+}
+
+/// A delegated minter key with its own ceiling and expiry
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Minter {
+    /// Most the key can mint over its lifetime
+    pub ceiling: i128,
+    /// Amount minted under the key so far
+    pub minted: i128,
+    /// Ledger timestamp after which the key can no longer mint
+    pub expires_at: u64,
+}
+
+/// Signature scheme of a `SignerSignature`
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SignatureScheme {
+    Ed25519,
+    /// ECDSA over the SHA-256 of the message, with the signature's recovery id
+    Secp256k1(u32),
+}
+
+/// A signer's signature over a mint or burn payload
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SignerSignature {
+    pub scheme: SignatureScheme,
+    /// The Ed25519 public key, or the key id of a secp256k1 signer
+    pub public_key: BytesN<32>,
+    pub signature: BytesN<64>,
+}
+
+/// Result of one payload in a `mint_batch`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MintOutcome {
+    /// The payload was redeemed for this amount
+    Minted(i128),
+    /// The payload was skipped with this `ContractError` code
+    Failed(u32),
 }
 
 // View-specific structures (used by views.rs module)
@@ -213,7 +1133,9 @@ pub struct GoalSaveView {
     pub interest_rate: u32,
     pub is_completed: bool,
     pub contribution_type: u32,
-    pub goal_name: Symbol,
+    pub category_id: u32,
+    /// Behind schedule for the goal's target date, if it has one
+    pub off_track: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -229,3 +1151,93 @@ pub struct GroupSaveView {
     pub contribution_type: u32,
     pub group_id: u64,
 }
+
+/// How much of each early break fee goes to the penalty pool, and how often
+/// the pool is shared out
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PenaltyPoolConfig {
+    /// Share of each early break fee paid into the pool, in basis points
+    pub share_bps: u32,
+    /// Minimum seconds between distributions
+    pub epoch_length: u64,
+}
+
+/// How early break fees and late penalties are split. The shares sum to
+/// 10_000 basis points.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PenaltyRouting {
+    /// Share paid into the treasury, in basis points
+    pub treasury_bps: u32,
+    /// Share paid into the penalty pool, in basis points
+    pub pool_bps: u32,
+    /// Share burned, in basis points
+    pub burn_bps: u32,
+}
+
+/// Penalties waiting to be shared out among savers who keep their locks
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PenaltyPool {
+    /// Number of distributions so far
+    pub epoch: u32,
+    pub epoch_start: u64,
+    /// Penalties collected since the last distribution
+    pub pending: i128,
+    /// Penalties distributed per unit of locked principal, scaled by
+    /// `penalty_pool::SCALE`
+    pub acc_per_share: i128,
+    /// Principal locked across all active lock plans
+    pub total_locked: i128,
+}
+
+/// A user's stake in the penalty pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PenaltyShare {
+    /// Principal the user has in active lock plans
+    pub locked: i128,
+    /// `locked * acc_per_share` when last settled
+    pub debt: i128,
+    /// Distributed penalties not yet claimed
+    pub owed: i128,
+}
+
+/// Asset identifier of a Reflector-style price oracle
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OracleAsset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// Price of an asset in USD, scaled by the oracle's decimals
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// What to do when the oracle has no recent enough price
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OracleFallback {
+    /// Use the last fresh price the contract read
+    LastPrice,
+    /// Judge USD goals by their token target instead
+    TokenTarget,
+    /// Fail the operation with `StalePrice`
+    Reject,
+}
+
+/// Price oracle used for USD-denominated goals
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConfig {
+    pub oracle: Address,
+    /// Oldest price age accepted, in seconds
+    pub max_age: u64,
+    pub fallback: OracleFallback,
+}
@@ -0,0 +1,350 @@
+//! Deploys idle contract funds across registered yield strategies, such as a
+//! Blend lending pool adapter.
+//!
+//! The admin registers strategy contracts and gives each an allocation weight
+//! in basis points of the deployable funds, which are the contract's funds
+//! minus a liquidity buffer kept for withdrawals. `rebalance` moves funds
+//! between the contract and the strategies to match those weights.
+//! Withdrawals the buffer can't cover wait in the withdrawal queue until
+//! liquidity is recalled.
+//!
+//! Each strategy records the principal deployed to it; anything its position
+//! is worth beyond that is yield, which `harvest` brings back into the
+//! treasury, from where the treasurer can withdraw it.
+
+use crate::assets;
+use crate::errors::SavingsError;
+use crate::fees;
+use crate::storage_types::{DataKey, DataKeyExt, YieldConfig, YieldState, YieldStrategy};
+use crate::withdrawal_queue;
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
+use soroban_sdk::{
+    contractclient, symbol_short, token::TokenClient, vec, Address, Env, IntoVal, Symbol, Vec,
+};
+
+/// Maximum number of registered strategies
+pub const MAX_STRATEGIES: u32 = 10;
+
+/// Interface a yield strategy contract implements.
+#[allow(dead_code)]
+#[contractclient(name = "StrategyClient")]
+pub trait Strategy {
+    /// Transfers `amount` of the asset from `from` into its position.
+    fn deposit(env: Env, from: Address, amount: i128);
+    /// Pays `amount` out of `to`'s position to `to`.
+    fn withdraw(env: Env, to: Address, amount: i128);
+    /// Current value of `account`'s position, including earned yield.
+    fn report(env: Env, account: Address) -> i128;
+}
+
+/// Sets the asset deployed to strategies and the share of the contract's
+/// funds, in basis points, kept liquid.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `buffer_bps` exceeds 10_000
+/// * `InvariantViolation` - If funds in another asset are still deployed
+pub fn set_yield_config(env: &Env, asset: Address, buffer_bps: u32) -> Result<(), SavingsError> {
+    if buffer_bps > 10_000 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if let Some(current) = get_yield_config(env) {
+        if current.asset != asset && total_deployed(env) > 0 {
+            return Err(SavingsError::InvariantViolation);
+        }
+    }
+
+    env.storage().instance().set(
+        &DataKeyExt::YieldConfig,
+        &YieldConfig {
+            asset: asset.clone(),
+            buffer_bps,
+        },
+    );
+    env.events()
+        .publish((symbol_short!("set_yld"), asset), buffer_bps);
+    Ok(())
+}
+
+/// Registers a strategy contract with an allocation weight in basis points.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If the strategy is already registered
+/// * `MaxPlansExceeded` - If `MAX_STRATEGIES` are already registered
+/// * `InvalidAmount` - If the weights would add up to more than 10_000
+pub fn register_strategy(env: &Env, address: Address, weight_bps: u32) -> Result<(), SavingsError> {
+    let mut strategies = get_strategies(env);
+    if strategies.iter().any(|s| s.address == address) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if strategies.len() >= MAX_STRATEGIES {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+
+    strategies.push_back(YieldStrategy {
+        address: address.clone(),
+        weight_bps,
+        deployed: 0,
+    });
+    ensure_weights(&strategies)?;
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::YieldStrategies, &strategies);
+
+    env.events()
+        .publish((symbol_short!("strat_add"), address), weight_bps);
+    Ok(())
+}
+
+/// Changes a strategy's allocation weight. Funds move on the next
+/// `rebalance`.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `PlanNotFound` - If the strategy is not registered
+/// * `InvalidAmount` - If the weights would add up to more than 10_000
+pub fn set_strategy_weight(
+    env: &Env,
+    address: Address,
+    weight_bps: u32,
+) -> Result<(), SavingsError> {
+    let mut strategies = get_strategies(env);
+    let index = find_strategy(&strategies, &address)?;
+    let mut strategy = strategies.get(index).unwrap();
+    strategy.weight_bps = weight_bps;
+    strategies.set(index, strategy);
+    ensure_weights(&strategies)?;
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::YieldStrategies, &strategies);
+
+    env.events()
+        .publish((symbol_short!("strat_wt"), address), weight_bps);
+    Ok(())
+}
+
+/// Removes a strategy that no longer holds any deployed funds.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `PlanNotFound` - If the strategy is not registered
+/// * `InvariantViolation` - If funds are still deployed to it
+pub fn remove_strategy(env: &Env, address: Address) -> Result<(), SavingsError> {
+    let mut strategies = get_strategies(env);
+    let index = find_strategy(&strategies, &address)?;
+    if strategies.get(index).unwrap().deployed > 0 {
+        return Err(SavingsError::InvariantViolation);
+    }
+    strategies.remove(index);
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::YieldStrategies, &strategies);
+
+    env.events()
+        .publish((symbol_short!("strat_rm"), address), ());
+    Ok(())
+}
+
+/// Moves funds between the contract and its strategies so each strategy
+/// holds its weight's share of the deployable funds. Strategies over their
+/// target are drawn down before any are topped up.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `MissingParameter` - If no yield asset is configured
+pub fn rebalance(env: &Env) -> Result<(), SavingsError> {
+    let config = get_yield_config(env).ok_or(SavingsError::MissingParameter)?;
+    // Funds owed to queued withdrawals are not deployable
+    withdrawal_queue::process_withdrawal_queue(env)?;
+    let mut strategies = get_strategies(env);
+    let contract = env.current_contract_address();
+
+    let liquid = TokenClient::new(env, &config.asset).balance(&contract);
+    let total = liquid
+        .checked_add(total_deployed(env))
+        .ok_or(SavingsError::Overflow)?;
+    let deployable = total
+        - total
+            .checked_mul(config.buffer_bps as i128)
+            .ok_or(SavingsError::Overflow)?
+            / 10_000;
+    let target = |strategy: &YieldStrategy| deployable * strategy.weight_bps as i128 / 10_000;
+
+    for (index, mut strategy) in strategies.clone().iter().enumerate() {
+        let excess = strategy.deployed - target(&strategy);
+        if excess > 0 {
+            StrategyClient::new(env, &strategy.address).withdraw(&contract, &excess);
+            strategy.deployed -= excess;
+            strategies.set(index as u32, strategy);
+        }
+    }
+    for (index, mut strategy) in strategies.clone().iter().enumerate() {
+        let shortfall = target(&strategy) - strategy.deployed;
+        if shortfall > 0 {
+            // The strategy pulls the funds, so the contract authorizes that
+            // transfer
+            env.authorize_as_current_contract(vec![
+                env,
+                InvokerContractAuthEntry::Contract(SubContractInvocation {
+                    context: ContractContext {
+                        contract: config.asset.clone(),
+                        fn_name: Symbol::new(env, "transfer"),
+                        args: (contract.clone(), strategy.address.clone(), shortfall).into_val(env),
+                    },
+                    sub_invocations: vec![env],
+                }),
+            ]);
+            StrategyClient::new(env, &strategy.address).deposit(&contract, &shortfall);
+            strategy.deployed += shortfall;
+            strategies.set(index as u32, strategy);
+        }
+    }
+
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::YieldStrategies, &strategies);
+    env.events()
+        .publish((symbol_short!("rebalance"),), deployable);
+    Ok(())
+}
+
+/// Withdraws the yield every strategy has earned and credits it to the
+/// treasury. Yield in an asset other than the savings token goes to the fee
+/// recipient's balance of that asset instead, since the treasury only pays
+/// out the savings token. Anyone may trigger a harvest.
+///
+/// # Returns
+/// `Ok(i128)` - The yield harvested
+pub fn harvest(env: &Env) -> Result<i128, SavingsError> {
+    let contract = env.current_contract_address();
+    let mut harvested: i128 = 0;
+    for strategy in get_strategies(env).iter() {
+        let client = StrategyClient::new(env, &strategy.address);
+        let earned = client.report(&contract) - strategy.deployed;
+        if earned > 0 {
+            client.withdraw(&contract, &earned);
+            harvested = harvested
+                .checked_add(earned)
+                .ok_or(SavingsError::Overflow)?;
+        }
+    }
+    if harvested == 0 {
+        return Ok(0);
+    }
+
+    let total: i128 = env
+        .storage()
+        .instance()
+        .get(&DataKeyExt::YieldHarvested)
+        .unwrap_or(0);
+    env.storage().instance().set(
+        &DataKeyExt::YieldHarvested,
+        &total.checked_add(harvested).ok_or(SavingsError::Overflow)?,
+    );
+
+    let asset = get_yield_config(env).map(|config| config.asset);
+    match asset {
+        Some(asset) if !assets::is_savings_token(env, &asset) => {
+            let recipient: Option<Address> = env.storage().instance().get(&DataKey::FeeRecipient);
+            if let Some(recipient) = recipient {
+                assets::credit(env, &recipient, &Some(asset), harvested)?;
+            }
+        }
+        _ => fees::credit_treasury(env, harvested)?,
+    }
+
+    env.events().publish((symbol_short!("harvest"),), harvested);
+    Ok(harvested)
+}
+
+/// Brings `amount` of deployed principal back into the contract, drawing on
+/// strategies in registration order.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InsufficientBalance` - If less than `amount` is deployed
+pub fn recall_liquidity(env: &Env, amount: i128) -> Result<(), SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if amount > total_deployed(env) {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    let contract = env.current_contract_address();
+    let mut strategies = get_strategies(env);
+    let mut remaining = amount;
+    for (index, mut strategy) in strategies.clone().iter().enumerate() {
+        if remaining == 0 {
+            break;
+        }
+        let take = remaining.min(strategy.deployed);
+        if take > 0 {
+            StrategyClient::new(env, &strategy.address).withdraw(&contract, &take);
+            strategy.deployed -= take;
+            strategies.set(index as u32, strategy);
+            remaining -= take;
+        }
+    }
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::YieldStrategies, &strategies);
+    withdrawal_queue::process_withdrawal_queue(env)?;
+
+    env.events().publish((symbol_short!("yld_rcl"),), amount);
+    Ok(())
+}
+
+/// Returns the yield asset and liquidity buffer, if configured.
+pub fn get_yield_config(env: &Env) -> Option<YieldConfig> {
+    env.storage().instance().get(&DataKeyExt::YieldConfig)
+}
+
+/// Returns the registered strategies in registration order.
+pub fn get_strategies(env: &Env) -> Vec<YieldStrategy> {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::YieldStrategies)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Returns the principal deployed across strategies and the yield harvested
+/// so far.
+pub fn get_yield_state(env: &Env) -> YieldState {
+    YieldState {
+        deployed: total_deployed(env),
+        harvested: env
+            .storage()
+            .instance()
+            .get(&DataKeyExt::YieldHarvested)
+            .unwrap_or(0),
+    }
+}
+
+fn total_deployed(env: &Env) -> i128 {
+    get_strategies(env).iter().map(|s| s.deployed).sum()
+}
+
+fn find_strategy(strategies: &Vec<YieldStrategy>, address: &Address) -> Result<u32, SavingsError> {
+    strategies
+        .iter()
+        .position(|s| s.address == *address)
+        .map(|i| i as u32)
+        .ok_or(SavingsError::PlanNotFound)
+}
+
+fn ensure_weights(strategies: &Vec<YieldStrategy>) -> Result<(), SavingsError> {
+    let total: u32 = strategies.iter().map(|s| s.weight_bps).sum();
+    if total > 10_000 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use crate::{NesteraContract, NesteraContractClient, SavingsError};
+use soroban_sdk::{
+    contract, contractimpl, contracttype,
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env,
+};
+
+#[contracttype]
+enum PoolKey {
+    Asset,
+    Position(Address),
+}
+
+/// Strategy stand-in that tracks positions and lets tests add yield
+#[contract]
+struct MockStrategy;
+
+#[contractimpl]
+impl MockStrategy {
+    pub fn init(env: Env, asset: Address) {
+        env.storage().instance().set(&PoolKey::Asset, &asset);
+    }
+
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        Self::token(&env).transfer(&from, env.current_contract_address(), &amount);
+        Self::add_yield(env, from, amount);
+    }
+
+    pub fn withdraw(env: Env, to: Address, amount: i128) {
+        to.require_auth();
+        let position = Self::report(env.clone(), to.clone());
+        assert!(position >= amount);
+        env.storage()
+            .instance()
+            .set(&PoolKey::Position(to.clone()), &(position - amount));
+        Self::token(&env).transfer(&env.current_contract_address(), &to, &amount);
+    }
+
+    pub fn report(env: Env, account: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&PoolKey::Position(account))
+            .unwrap_or(0)
+    }
+
+    pub fn add_yield(env: Env, account: Address, amount: i128) {
+        let position = Self::report(env.clone(), account.clone());
+        env.storage()
+            .instance()
+            .set(&PoolKey::Position(account), &(position + amount));
+    }
+}
+
+impl MockStrategy {
+    fn token(env: &Env) -> TokenClient<'_> {
+        let asset: Address = env.storage().instance().get(&PoolKey::Asset).unwrap();
+        TokenClient::new(env, &asset)
+    }
+}
+
+fn setup_strategy(env: &Env, asset: &Address) -> (Address, MockStrategyClient<'static>) {
+    let id = env.register(MockStrategy, ());
+    let client = MockStrategyClient::new(env, &id);
+    client.init(asset);
+    (id, client)
+}
+
+#[test]
+fn test_rebalance_follows_weights_and_yield_is_harvested() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let asset = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    client.initialize(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(asset.clone()),
+    );
+    let treasury = Address::generate(&env);
+    client.set_fee_recipient(&treasury);
+
+    let token = TokenClient::new(&env, &asset);
+    let minter = StellarAssetClient::new(&env, &asset);
+    let (first_id, first) = setup_strategy(&env, &asset);
+    let (second_id, _) = setup_strategy(&env, &asset);
+    minter.mint(&contract_id, &10_000);
+
+    let res = client.try_rebalance();
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::MissingParameter));
+
+    // Keep 20% liquid; 50% and 30% of the other 8_000 go to the strategies
+    client.set_yield_config(&asset, &2_000);
+    client.register_strategy(&first_id, &5_000);
+    client.register_strategy(&second_id, &3_000);
+    let res = client.try_register_strategy(&Address::generate(&env), &2_001);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidAmount));
+    client.rebalance();
+    assert_eq!(token.balance(&first_id), 4_000);
+    assert_eq!(token.balance(&second_id), 2_400);
+    assert_eq!(token.balance(&contract_id), 3_600);
+
+    client.set_strategy_weight(&first_id, &2_000);
+    client.set_strategy_weight(&second_id, &6_000);
+    client.rebalance();
+    assert_eq!(token.balance(&first_id), 1_600);
+    assert_eq!(token.balance(&second_id), 4_800);
+    assert_eq!(client.get_yield_state().deployed, 6_400);
+
+    // Yield earned by a position is brought back for the treasury
+    minter.mint(&first_id, &300);
+    first.add_yield(&contract_id, &300);
+    assert_eq!(client.harvest(), 300);
+    assert_eq!(client.harvest(), 0);
+    assert_eq!(client.get_treasury_balance(), 300);
+    assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+
+    let res = client.try_remove_strategy(&first_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvariantViolation));
+    let res = client.try_recall_liquidity(&6_401);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InsufficientBalance));
+    client.recall_liquidity(&2_000);
+    let strategies = client.get_strategies();
+    assert_eq!(strategies.get(0).unwrap().deployed, 0);
+    assert_eq!(strategies.get(1).unwrap().deployed, 4_400);
+    client.remove_strategy(&first_id);
+    assert_eq!(client.get_strategies().len(), 1);
+    assert_eq!(token.balance(&contract_id), 5_900);
+
+    // The harvested yield can leave the contract
+    client.withdraw_treasury(&admin, &treasury, &300);
+    assert_eq!(client.get_treasury_balance(), 0);
+    assert_eq!(token.balance(&treasury), 300);
+    assert_eq!(token.balance(&contract_id), 5_600);
+}
+
+#[test]
+fn test_withdrawals_beyond_the_buffer_are_queued() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let asset = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token = TokenClient::new(&env, &asset);
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    client.initialize(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(asset.clone()),
+    );
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    for user in [&alice, &bob] {
+        client.initialize_user(user);
+        StellarAssetClient::new(&env, &asset).mint(user, &5_000);
+        client.deposit_flexi(user, &5_000);
+    }
+
+    // 2_000 stays liquid, 8_000 is deployed
+    let (strategy_id, _) = setup_strategy(&env, &asset);
+    client.set_yield_config(&asset, &2_000);
+    client.register_strategy(&strategy_id, &10_000);
+    client.rebalance();
+    assert_eq!(token.balance(&contract_id), 2_000);
+
+    client.withdraw_flexi(&alice, &3_000);
+    assert_eq!(client.get_flexi_balance(&alice), 2_000);
+    assert_eq!(token.balance(&alice), 0);
+    assert_eq!(client.get_queue_position(&0), Some(0));
+
+    // Bob could be paid from the buffer but waits behind Alice
+    client.withdraw_flexi(&bob, &500);
+    assert_eq!(token.balance(&bob), 0);
+    assert_eq!(client.get_queue_position(&1), Some(1));
+    assert_eq!(client.process_withdrawal_queue(), 0);
+
+    client.recall_liquidity(&1_500);
+    assert_eq!(token.balance(&alice), 3_000);
+    assert_eq!(token.balance(&bob), 500);
+    assert_eq!(client.get_withdrawal_request(&0), None);
+    assert_eq!(client.get_queue_position(&1), None);
+    assert_eq!(token.balance(&contract_id), 0);
+
+    // An empty queue lets withdrawals the contract can cover through
+    client.recall_liquidity(&1_000);
+    client.withdraw_flexi(&bob, &1_000);
+    assert_eq!(token.balance(&bob), 1_500);
+    assert_eq!(client.get_withdrawal_queue().tail, 2);
+}
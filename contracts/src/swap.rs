@@ -0,0 +1,131 @@
+//! Swap-on-deposit through a Soroswap-style DEX router.
+//!
+//! A user may fund a plan with a token other than the plan's asset. The
+//! contract takes the input token, swaps it into the plan's asset through
+//! the admin-configured router and deposits what it receives. The amount
+//! received is measured from the contract's own balance, and the deposit is
+//! rejected if it falls short of the user's minimum.
+
+use crate::assets;
+use crate::ensure_not_paused;
+use crate::errors::SavingsError;
+use crate::normalize;
+use crate::plans;
+use crate::storage_types::DataKeyExt;
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
+use soroban_sdk::{
+    contractclient, symbol_short, token::TokenClient, vec, Address, Env, IntoVal, Symbol, Vec,
+};
+
+/// Seconds a swap may wait in the mempool before the router rejects it
+pub const SWAP_DEADLINE: u64 = 300;
+
+/// Interface of the DEX router adapter.
+#[allow(dead_code)]
+#[contractclient(name = "SwapRouterClient")]
+pub trait SwapRouter {
+    /// Swaps exactly `amount_in` of `path[0]` for at least `amount_out_min`
+    /// of the last token in `path`. The router pulls the input from `to`
+    /// with a `transfer` to itself and pays the output to `to`.
+    ///
+    /// Returns the amounts along the path.
+    fn swap_exact_tokens_for_tokens(
+        env: Env,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128>;
+}
+
+/// Sets the DEX router used by `deposit_with_swap`.
+///
+/// The caller is responsible for checking admin authorization.
+pub fn set_swap_router(env: &Env, router: Address) {
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::SwapRouter, &router);
+    env.events().publish((symbol_short!("set_swap"),), router);
+}
+
+/// Returns the DEX router, if configured.
+pub fn get_swap_router(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKeyExt::SwapRouter)
+}
+
+/// Swaps `in_amount` of `in_token` into the plan's asset and deposits the
+/// proceeds into the plan.
+///
+/// `in_amount` is in `in_token`'s own units, which need not be supported;
+/// `min_out` and the result are in canonical precision.
+///
+/// # Returns
+/// `Ok(i128)` - The amount of the plan's asset deposited
+///
+/// # Errors
+/// * `InvalidAmount` - If `in_amount` or `min_out` is not positive
+/// * `MissingParameter` - If no router is set or the plan has no token
+/// * `InvalidPlanConfig` - If `in_token` already is the plan's asset
+/// * `SlippageExceeded` - If the swap returns less than `min_out`
+/// * Any error of depositing into the plan
+pub fn deposit_with_swap(
+    env: &Env,
+    user: Address,
+    plan_id: u64,
+    in_token: Address,
+    in_amount: i128,
+    min_out: i128,
+) -> Result<i128, SavingsError> {
+    ensure_not_paused(env)?;
+    user.require_auth();
+
+    if in_amount <= 0 || min_out <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let router = get_swap_router(env).ok_or(SavingsError::MissingParameter)?;
+    let out_token =
+        assets::get_plan_asset(env, &user, plan_id).ok_or(SavingsError::MissingParameter)?;
+    if in_token == out_token {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    let min_units = normalize::to_units_in(env, &out_token, min_out)?;
+    let contract = env.current_contract_address();
+    TokenClient::new(env, &in_token).transfer(&user, &contract, &in_amount);
+    let out = TokenClient::new(env, &out_token);
+    let before = out.balance(&contract);
+
+    // The router pulls the input, so the contract authorizes that transfer
+    env.authorize_as_current_contract(vec![
+        env,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: in_token.clone(),
+                fn_name: Symbol::new(env, "transfer"),
+                args: (contract.clone(), router.clone(), in_amount).into_val(env),
+            },
+            sub_invocations: vec![env],
+        }),
+    ]);
+    SwapRouterClient::new(env, &router).swap_exact_tokens_for_tokens(
+        &in_amount,
+        &min_units,
+        &vec![env, in_token.clone(), out_token.clone()],
+        &contract,
+        &env.ledger().timestamp().saturating_add(SWAP_DEADLINE),
+    );
+    let received = normalize::to_canonical(env, &out_token, out.balance(&contract) - before)?;
+    if received < min_out {
+        return Err(SavingsError::SlippageExceeded);
+    }
+
+    plans::credit_plan(env, user.clone(), plan_id, received)?;
+
+    env.events().publish(
+        (symbol_short!("swap_dep"), user, plan_id),
+        (in_token, in_amount, received),
+    );
+
+    Ok(received)
+}
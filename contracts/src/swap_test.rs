@@ -0,0 +1,121 @@
+use crate::{NesteraContract, NesteraContractClient, PlanType, SavingsError};
+use soroban_sdk::{
+    contract, contractimpl, contracttype,
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, BytesN, Env, Vec,
+};
+
+#[contracttype]
+enum RouterKey {
+    RateBps,
+}
+
+/// Router stand-in that swaps at a fixed rate out of its own reserves
+#[contract]
+struct MockRouter;
+
+#[contractimpl]
+impl MockRouter {
+    pub fn set_rate(env: Env, rate_bps: i128) {
+        env.storage().instance().set(&RouterKey::RateBps, &rate_bps);
+    }
+
+    pub fn swap_exact_tokens_for_tokens(
+        env: Env,
+        amount_in: i128,
+        _amount_out_min: i128,
+        path: Vec<Address>,
+        to: Address,
+        _deadline: u64,
+    ) -> Vec<i128> {
+        to.require_auth();
+        let router = env.current_contract_address();
+        TokenClient::new(&env, &path.get(0).unwrap()).transfer(&to, &router, &amount_in);
+        let rate: i128 = env.storage().instance().get(&RouterKey::RateBps).unwrap();
+        let amount_out = amount_in * rate / 10_000;
+        TokenClient::new(&env, &path.get(1).unwrap()).transfer(&router, &to, &amount_out);
+        vec![&env, amount_in, amount_out]
+    }
+}
+
+fn setup() -> (
+    Env,
+    NesteraContractClient<'static>,
+    Address,
+    Address,
+    Address,
+    MockRouterClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let usdc = env
+        .register_stellar_asset_contract_v2(issuer.clone())
+        .address();
+    let xlm = env.register_stellar_asset_contract_v2(issuer).address();
+
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    client.initialize(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Some(usdc.clone()),
+    );
+
+    let router_id = env.register(MockRouter, ());
+    let router = MockRouterClient::new(&env, &router_id);
+    router.set_rate(&2_000);
+    StellarAssetClient::new(&env, &usdc).mint(&router_id, &1_000_000);
+    client.set_swap_router(&router_id);
+
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    StellarAssetClient::new(&env, &xlm).mint(&user, &10_000);
+
+    (env, client, user, usdc, xlm, router)
+}
+
+#[test]
+fn test_deposit_with_swap_deposits_the_proceeds() {
+    let (env, client, user, usdc, xlm, router) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &0);
+
+    // 5_000 XLM at 0.2 USDC each
+    assert_eq!(
+        client.deposit_with_swap(&user, &plan_id, &xlm, &5_000, &900),
+        1_000
+    );
+    assert_eq!(
+        client.get_savings_plan(&user, &plan_id).unwrap().balance,
+        1_000
+    );
+    assert_eq!(client.get_user(&user).total_balance, 1_000);
+    assert_eq!(TokenClient::new(&env, &xlm).balance(&user), 5_000);
+    assert_eq!(TokenClient::new(&env, &xlm).balance(&router.address), 5_000);
+    assert_eq!(
+        TokenClient::new(&env, &usdc).balance(&client.address),
+        1_000
+    );
+}
+
+#[test]
+fn test_deposit_with_swap_enforces_minimum_output() {
+    let (env, client, user, usdc, xlm, router) = setup();
+    let plan_id = client.create_savings_plan(&user, &PlanType::Flexi, &0);
+    router.set_rate(&1_000);
+
+    assert_eq!(
+        client.try_deposit_with_swap(&user, &plan_id, &xlm, &5_000, &900),
+        Err(Ok(SavingsError::SlippageExceeded))
+    );
+    assert_eq!(TokenClient::new(&env, &xlm).balance(&user), 10_000);
+    assert_eq!(
+        client.try_deposit_with_swap(&user, &plan_id, &usdc, &5_000, &900),
+        Err(Ok(SavingsError::InvalidPlanConfig))
+    );
+    assert_eq!(
+        client.try_deposit_with_swap(&user, &plan_id, &xlm, &5_000, &0),
+        Err(Ok(SavingsError::InvalidAmount))
+    );
+}
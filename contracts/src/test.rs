@@ -11,7 +11,7 @@ fn setup() -> (Env, NesteraContractClient<'static>, Address) {
     let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
 
     env.mock_all_auths();
-    client.initialize(&admin, &admin_pk);
+    client.initialize(&admin, &admin_pk, &None);
 
     (env, client, admin)
 }
@@ -0,0 +1,183 @@
+//! Delay between scheduling a sensitive admin action and its taking effect.
+//!
+//! With a non-zero delay, rate, fee, strategy and parameter changes,
+//! rebalances, treasury withdrawals and upgrades can't
+//! be made directly. The admin queues them with `queue_action`, anyone may
+//! execute them once the delay has passed, and the admin may cancel them
+//! until then. Users see every pending change on-chain and have the delay to
+//! exit before it applies. The delay itself can only be changed through the
+//! timelock once set, so it can't be dropped to push a change through.
+
+use crate::config;
+use crate::errors::SavingsError;
+use crate::fees;
+use crate::interest;
+use crate::penalty_pool;
+use crate::plans;
+use crate::rates;
+use crate::storage_types::{ControlDataKey, QueuedAction, TimelockAction};
+use crate::strategies;
+use crate::upgrade;
+use soroban_sdk::{symbol_short, Env};
+
+/// Returns the timelock delay in seconds; zero when there is no timelock.
+pub fn get_timelock_delay(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&ControlDataKey::TimelockDelay)
+        .unwrap_or(0)
+}
+
+/// Fails if sensitive actions must go through the timelock.
+///
+/// # Errors
+/// * `TooEarly` - If a timelock delay is set
+pub(crate) fn ensure_no_timelock(env: &Env) -> Result<(), SavingsError> {
+    if get_timelock_delay(env) > 0 {
+        return Err(SavingsError::TooEarly);
+    }
+    Ok(())
+}
+
+/// Sets the timelock delay directly, which is only possible while there is
+/// no timelock.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `TooEarly` - If a timelock delay is already set
+pub(crate) fn set_timelock_delay(env: &Env, delay: u64) -> Result<(), SavingsError> {
+    ensure_no_timelock(env)?;
+    store_delay(env, delay);
+    Ok(())
+}
+
+/// Queues an action to become executable once the delay has passed and
+/// returns its id.
+///
+/// The caller is responsible for checking admin authorization.
+pub(crate) fn queue_action(env: &Env, action: TimelockAction) -> u64 {
+    let id: u64 = env
+        .storage()
+        .instance()
+        .get(&ControlDataKey::NextTimelockId)
+        .unwrap_or(1);
+    let execute_after = env
+        .ledger()
+        .timestamp()
+        .saturating_add(get_timelock_delay(env));
+    env.storage().persistent().set(
+        &ControlDataKey::QueuedAction(id),
+        &QueuedAction {
+            action: action.clone(),
+            execute_after,
+        },
+    );
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::NextTimelockId, &(id + 1));
+    env.events()
+        .publish((symbol_short!("tl_queue"), id), (action, execute_after));
+    id
+}
+
+/// Applies a queued action whose delay has passed. Anyone may execute it.
+///
+/// # Errors
+/// * `PlanNotFound` - If no action is queued under `id`
+/// * `TooEarly` - If the delay has not passed yet
+/// * Any error of the action itself
+pub(crate) fn execute_action(env: &Env, id: u64) -> Result<(), SavingsError> {
+    let queued = get_queued_action(env, id).ok_or(SavingsError::PlanNotFound)?;
+    if env.ledger().timestamp() < queued.execute_after {
+        return Err(SavingsError::TooEarly);
+    }
+    env.storage()
+        .persistent()
+        .remove(&ControlDataKey::QueuedAction(id));
+    match queued.action {
+        TimelockAction::SetFlexiRate(rate) => rates::set_flexi_rate(env, rate)?,
+        TimelockAction::SetGoalRate(rate) => rates::set_goal_rate(env, rate)?,
+        TimelockAction::SetGroupRate(rate) => rates::set_group_rate(env, rate)?,
+        TimelockAction::SetLockRate(duration_days, rate) => {
+            rates::set_lock_rate(env, duration_days, rate)?
+        }
+        TimelockAction::SetInterestRate(kind, rate_bps, effective_at) => {
+            interest::set_interest_rate(env, kind, rate_bps, effective_at)?
+        }
+        TimelockAction::SetEarlyBreakFeeBps(bps) => fees::set_early_break_fee_bps(env, bps)?,
+        TimelockAction::SetFeeRecipient(recipient) => fees::set_fee_recipient(env, recipient),
+        TimelockAction::SetGroupCreationFee(fee) => fees::set_group_creation_fee(env, fee)?,
+        TimelockAction::SetYieldConfig(asset, buffer_bps) => {
+            strategies::set_yield_config(env, asset, buffer_bps)?
+        }
+        TimelockAction::RegisterStrategy(address, weight_bps) => {
+            strategies::register_strategy(env, address, weight_bps)?
+        }
+        TimelockAction::SetStrategyWeight(address, weight_bps) => {
+            strategies::set_strategy_weight(env, address, weight_bps)?
+        }
+        TimelockAction::RemoveStrategy(address) => strategies::remove_strategy(env, address)?,
+        TimelockAction::Upgrade(new_wasm_hash) => upgrade::upgrade(env, new_wasm_hash),
+        TimelockAction::SetTimelockDelay(delay) => store_delay(env, delay),
+        TimelockAction::UpdateConfig(config) => config::update_config(env, config)?,
+        TimelockAction::SetTransferFees(deposit_bps, withdrawal_bps) => {
+            fees::set_transfer_fees(env, deposit_bps, withdrawal_bps)?
+        }
+        TimelockAction::WithdrawTreasury(to, amount) => fees::withdraw_treasury(env, to, amount)?,
+        TimelockAction::SetTreasurer(treasurer) => fees::set_treasurer(env, treasurer),
+        TimelockAction::SetPerformanceFee(kind, bps) => fees::set_performance_fee(env, kind, bps)?,
+        TimelockAction::SetReferralShare(bps) => fees::set_referral_share(env, bps)?,
+        TimelockAction::SetFeeTiers(tiers) => fees::set_fee_tiers(env, tiers)?,
+        TimelockAction::SetLockRateTiers(tiers) => rates::set_lock_rate_tiers(env, tiers)?,
+        TimelockAction::SetCompounding(kind, frequency) => {
+            interest::set_compounding(env, kind, frequency)?
+        }
+        TimelockAction::SetStreakBoostTiers(tiers) => interest::set_streak_boost_tiers(env, tiers)?,
+        TimelockAction::SetRewardsToken(token, exchange_rate) => {
+            interest::set_rewards_token(env, token, exchange_rate)?
+        }
+        TimelockAction::SetPenaltyPool(share_bps, epoch_length) => {
+            penalty_pool::set_penalty_pool(env, share_bps, epoch_length)?
+        }
+        TimelockAction::SetPenaltyRouting(routing) => {
+            penalty_pool::set_penalty_routing(env, routing)?
+        }
+        TimelockAction::SetLockMaturityPolicy(policy) => {
+            plans::set_lock_maturity_policy(env, policy)?
+        }
+        TimelockAction::SetGoalBonusConfig(config) => plans::set_goal_bonus_config(env, config)?,
+        TimelockAction::Rebalance => strategies::rebalance(env)?,
+    }
+    env.events().publish((symbol_short!("tl_exec"), id), ());
+    Ok(())
+}
+
+/// Cancels a queued action.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `PlanNotFound` - If no action is queued under `id`
+pub(crate) fn cancel_action(env: &Env, id: u64) -> Result<(), SavingsError> {
+    let key = ControlDataKey::QueuedAction(id);
+    if !env.storage().persistent().has(&key) {
+        return Err(SavingsError::PlanNotFound);
+    }
+    env.storage().persistent().remove(&key);
+    env.events().publish((symbol_short!("tl_cancel"), id), ());
+    Ok(())
+}
+
+pub fn get_queued_action(env: &Env, id: u64) -> Option<QueuedAction> {
+    env.storage()
+        .persistent()
+        .get(&ControlDataKey::QueuedAction(id))
+}
+
+fn store_delay(env: &Env, delay: u64) {
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::TimelockDelay, &delay);
+    env.events().publish((symbol_short!("tl_delay"),), delay);
+}
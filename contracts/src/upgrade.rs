@@ -0,0 +1,67 @@
+//! Upgrading the contract's code in place and migrating its storage.
+//!
+//! `upgrade` swaps the contract's WASM while keeping its address and all of
+//! its storage. When a new version changes how data is laid out, it bumps
+//! `CURRENT_SCHEMA_VERSION` and adds a migration step that rewrites storage
+//! from the previous layout. After upgrading, the admin calls `migrate` once
+//! per version, in order, until the stored schema version is current.
+
+use crate::config;
+use crate::errors::SavingsError;
+use crate::storage_types::ControlDataKey;
+use soroban_sdk::{symbol_short, BytesN, Env};
+
+/// Version of the storage layout this code reads and writes
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Records that storage uses the current layout, for fresh deployments.
+pub(crate) fn init_schema_version(env: &Env) {
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::SchemaVersion, &CURRENT_SCHEMA_VERSION);
+}
+
+/// Returns the version of the stored data's layout. Deployments that predate
+/// schema versioning use version 1.
+pub fn get_schema_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&ControlDataKey::SchemaVersion)
+        .unwrap_or(1)
+}
+
+/// Replaces the contract's code with the uploaded WASM `new_wasm_hash`. The
+/// new code takes effect once the current invocation finishes.
+///
+/// The caller is responsible for checking admin authorization.
+pub(crate) fn upgrade(env: &Env, new_wasm_hash: BytesN<32>) {
+    env.deployer()
+        .update_current_contract_wasm(new_wasm_hash.clone());
+    env.events()
+        .publish((symbol_short!("upgrade"),), new_wasm_hash);
+}
+
+/// Migrates storage from the previous schema version to `version`.
+///
+/// The caller is responsible for checking admin authorization.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If `version` is not the one after the stored
+///   version, or is newer than this code supports
+pub(crate) fn migrate(env: &Env, version: u32) -> Result<(), SavingsError> {
+    let from = get_schema_version(env);
+    if version != from + 1 || version > CURRENT_SCHEMA_VERSION {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    // Each layout change adds its step here, rewriting storage written by
+    // version `from` into the layout of `version`.
+    if version == 2 {
+        config::migrate_early_break_fee(env);
+    }
+    env.storage()
+        .instance()
+        .set(&ControlDataKey::SchemaVersion, &version);
+    env.events()
+        .publish((symbol_short!("migrate"),), (from, version));
+    Ok(())
+}
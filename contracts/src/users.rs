@@ -1,8 +1,9 @@
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{symbol_short, Address, Env, Vec};
 
 use crate::ensure_not_paused;
 use crate::errors::SavingsError;
-use crate::storage_types::{DataKey, User};
+use crate::lock;
+use crate::storage_types::{ControlDataKey, DataKey, PlanType, SavingsPlan, User};
 
 /// Check if a user exists in storage
 ///
@@ -23,14 +24,63 @@ pub fn user_exists(env: &Env, user: &Address) -> bool {
 /// * `env` - The contract environment
 /// * `user` - The address of the user to retrieve
 ///
+/// `total_balance` is derived from the ledgers holding the user's funds
+/// rather than stored; see `total_balance`.
+///
 /// # Returns
 /// `Ok(User)` if found, `Err(SavingsError::UserNotFound)` otherwise
 pub fn get_user(env: &Env, user: &Address) -> Result<User, SavingsError> {
     let key = DataKey::User(user.clone());
-    env.storage()
+    let mut user_data: User = env
+        .storage()
         .persistent()
         .get(&key)
-        .ok_or(SavingsError::UserNotFound)
+        .ok_or(SavingsError::UserNotFound)?;
+    user_data.total_balance = total_balance(env, user, user_data.savings_count)?;
+    Ok(user_data)
+}
+
+/// Sums the ledgers holding a user's funds: the Flexi balance, active
+/// savings plans (other than group plans, which are accounted for by their
+/// group) and active Lock Saves.
+///
+/// # Returns
+/// `Ok(i128)` on success, `Err(SavingsError::InvariantViolation)` if any of
+/// the ledgers is negative
+fn total_balance(env: &Env, user: &Address, savings_count: u32) -> Result<i128, SavingsError> {
+    let flexi: i128 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::FlexiBalance(user.clone()))
+        .unwrap_or(0);
+    let mut balances = Vec::from_array(env, [flexi]);
+    for plan_id in 1..=savings_count as u64 {
+        if let Some(plan) = env
+            .storage()
+            .persistent()
+            .get::<DataKey, SavingsPlan>(&DataKey::SavingsPlan(user.clone(), plan_id))
+        {
+            if !plan.is_withdrawn && !matches!(plan.plan_type, PlanType::Group(..)) {
+                balances.push_back(plan.balance);
+            }
+        }
+    }
+    for lock_id in lock::get_user_lock_saves(env, user).iter() {
+        if let Some(lock_save) = lock::get_lock_save(env, lock_id) {
+            if !lock_save.is_withdrawn {
+                balances.push_back(lock_save.amount);
+            }
+        }
+    }
+
+    let mut total: i128 = 0;
+    for balance in balances.iter() {
+        if balance < 0 {
+            return Err(SavingsError::InvariantViolation);
+        }
+        total = total.checked_add(balance).ok_or(SavingsError::Overflow)?;
+    }
+    Ok(total)
 }
 
 /// Initialize a new user in the savings contract
@@ -66,3 +116,49 @@ pub fn initialize_user(env: &Env, user: Address) -> Result<(), SavingsError> {
 
     Ok(())
 }
+
+/// Initialize a new user referred by an existing one
+///
+/// The referrer receives the configured referral share of the fees and
+/// penalties the new user pays.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `user` - The address of the user to initialize
+/// * `referrer` - The existing user who referred them
+///
+/// # Returns
+/// `Ok(())` on success, `Err(SavingsError::UserNotFound)` if the referrer has
+/// no account, `Err(SavingsError::InvalidPlanConfig)` if users refer
+/// themselves
+///
+/// # Authorization
+/// Requires authorization from the user being initialized
+pub fn initialize_referred_user(
+    env: &Env,
+    user: Address,
+    referrer: Address,
+) -> Result<(), SavingsError> {
+    if referrer == user {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if !user_exists(env, &referrer) {
+        return Err(SavingsError::UserNotFound);
+    }
+    initialize_user(env, user.clone())?;
+
+    env.storage()
+        .persistent()
+        .set(&ControlDataKey::Referrer(user.clone()), &referrer);
+    env.events()
+        .publish((symbol_short!("referred"), referrer), user);
+
+    Ok(())
+}
+
+/// Get the user who referred `user`, if any
+pub fn get_referrer(env: &Env, user: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&ControlDataKey::Referrer(user.clone()))
+}
@@ -22,9 +22,9 @@ fn to_lock_save(plan: &SavingsPlan) -> Option<LockSaveView> {
     }
 }
 
-fn to_goal_save(plan: &SavingsPlan) -> Option<GoalSaveView> {
+fn to_goal_save(env: &Env, user: &Address, plan: &SavingsPlan) -> Option<GoalSaveView> {
     match &plan.plan_type {
-        PlanType::Goal(goal_name, target_amount, contribution_type) => Some(GoalSaveView {
+        PlanType::Goal(category_id, target_amount, contribution_type) => Some(GoalSaveView {
             plan_id: plan.plan_id,
             balance: plan.balance,
             target_amount: *target_amount,
@@ -32,7 +32,8 @@ fn to_goal_save(plan: &SavingsPlan) -> Option<GoalSaveView> {
             interest_rate: plan.interest_rate,
             is_completed: plan.is_completed,
             contribution_type: *contribution_type,
-            goal_name: goal_name.clone(),
+            category_id: *category_id,
+            off_track: crate::plans::goal_off_track(env, user, plan, *target_amount),
         }),
         _ => None,
     }
@@ -117,6 +118,36 @@ pub fn get_user_matured_lock_saves(
     Ok(matured_plans)
 }
 
+/// Lists the IDs of the user's unwithdrawn lock plans that mature between now
+/// and `window` seconds from now, in plan ID order.
+pub fn get_upcoming_maturities(
+    env: &Env,
+    user: Address,
+    window: u64,
+) -> Result<Vec<u64>, SavingsError> {
+    let user_data: User = crate::users::get_user(env, &user)?;
+    let mut upcoming = Vec::new(env);
+    let current_time = env.ledger().timestamp();
+    let horizon = current_time.saturating_add(window);
+
+    for i in 1..=user_data.savings_count {
+        let plan_id = i as u64;
+        let key = DataKey::SavingsPlan(user.clone(), plan_id);
+
+        if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
+            if let Some(lock_save) = to_lock_save(&plan) {
+                if !lock_save.is_withdrawn
+                    && lock_save.locked_until >= current_time
+                    && lock_save.locked_until <= horizon
+                {
+                    upcoming.push_back(plan_id);
+                }
+            }
+        }
+    }
+    Ok(upcoming)
+}
+
 pub fn get_lock_save(env: &Env, user: Address, lock_id: u64) -> Result<LockSaveView, SavingsError> {
     let key = DataKey::SavingsPlan(user, lock_id);
     let plan = env
@@ -148,8 +179,9 @@ pub fn get_user_live_goal_saves(
         let key = DataKey::SavingsPlan(user.clone(), plan_id);
 
         if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
-            if let Some(goal_save) = to_goal_save(&plan) {
-                if !goal_save.is_completed {
+            if let Some(goal_save) = to_goal_save(env, &user, &plan) {
+                // Abandoned goals are withdrawn without being completed
+                if !goal_save.is_completed && !plan.is_withdrawn {
                     live_plans.push_back(goal_save);
                 }
             }
@@ -174,7 +206,7 @@ pub fn get_user_completed_goal_saves(
         let key = DataKey::SavingsPlan(user.clone(), plan_id);
 
         if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
-            if let Some(goal_save) = to_goal_save(&plan) {
+            if let Some(goal_save) = to_goal_save(env, &user, &plan) {
                 if goal_save.is_completed {
                     completed_plans.push_back(goal_save);
                 }
@@ -185,14 +217,14 @@ pub fn get_user_completed_goal_saves(
 }
 
 pub fn get_goal_save(env: &Env, user: Address, goal_id: u64) -> Result<GoalSaveView, SavingsError> {
-    let key = DataKey::SavingsPlan(user, goal_id);
+    let key = DataKey::SavingsPlan(user.clone(), goal_id);
     let plan = env
         .storage()
         .persistent()
         .get::<DataKey, SavingsPlan>(&key)
         .ok_or(SavingsError::PlanNotFound)?;
 
-    to_goal_save(&plan).ok_or(SavingsError::PlanNotFound)
+    to_goal_save(env, &user, &plan).ok_or(SavingsError::PlanNotFound)
 }
 
 // ===========================================================================
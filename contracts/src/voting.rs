@@ -0,0 +1,215 @@
+use crate::config;
+use crate::ensure_not_paused;
+use crate::errors::SavingsError;
+use crate::group;
+use crate::storage_types::{
+    DataKey, GroupChange, GroupChangeProposal, GroupMode, GroupSave, GroupStatus,
+};
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+/// Default time members have to vote on a group change (7 days); see
+/// `ProtocolConfig::group_change_ttl`
+pub const GROUP_CHANGE_TTL: u64 = 7 * 24 * 60 * 60;
+
+/// Proposes an amendment to a running group's target, member cap or payout
+/// beneficiary.
+///
+/// Any member may propose; the proposer's vote is counted immediately, so a
+/// change that already meets quorum is applied right away.
+///
+/// # Returns
+/// `Ok(u64)` - The ID of the new proposal
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `NotGroupMember` - If the proposer is not a member of the group
+/// * `PlanCompleted` - If the group is no longer active
+/// * `InvalidAmount` - If a new target is not above the amount already raised
+/// * `InvalidGroupConfig` - If the change doesn't apply to this group
+/// * `UserNotFound` - If a new beneficiary has no account to be paid
+pub fn propose_group_change(
+    env: &Env,
+    proposer: Address,
+    group_id: u64,
+    change: GroupChange,
+) -> Result<u64, SavingsError> {
+    ensure_not_paused(env)?;
+    proposer.require_auth();
+
+    let group = load_group(env, group_id)?;
+    if !group::is_member(env, group_id, &proposer) {
+        return Err(SavingsError::NotGroupMember);
+    }
+    validate_change(env, &group, &change)?;
+
+    let now = env.ledger().timestamp();
+    let proposal_id = get_next_change_id(env);
+    let mut votes = Vec::new(env);
+    votes.push_back(proposer.clone());
+
+    let mut proposal = GroupChangeProposal {
+        id: proposal_id,
+        group_id,
+        proposer: proposer.clone(),
+        change,
+        votes,
+        created_at: now,
+        expires_at: now
+            .checked_add(config::get_config(env).group_change_ttl)
+            .ok_or(SavingsError::Overflow)?,
+        is_executed: false,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::NextGroupChangeId, &(proposal_id + 1));
+
+    env.events()
+        .publish((symbol_short!("grp_chg"), proposer, group_id), proposal_id);
+
+    try_execute(env, &mut proposal, group)?;
+
+    Ok(proposal_id)
+}
+
+/// Votes in favour of a pending group change.
+///
+/// The change is applied as soon as the share of current members who voted
+/// for it reaches the group's `change_quorum`.
+///
+/// # Returns
+/// `Ok(true)` if this vote carried the change, `Ok(false)` otherwise
+///
+/// # Errors
+/// * `PlanNotFound` - If the proposal or its group doesn't exist
+/// * `NotGroupMember` - If the voter is not a member of the group
+/// * `PlanCompleted` - If the proposal was already executed or the group closed
+/// * `TooLate` - If the proposal has expired
+/// * `InvalidGroupConfig` - If the voter already voted
+pub fn vote_group_change(
+    env: &Env,
+    voter: Address,
+    proposal_id: u64,
+) -> Result<bool, SavingsError> {
+    ensure_not_paused(env)?;
+    voter.require_auth();
+
+    let mut proposal = get_group_change(env, proposal_id).ok_or(SavingsError::PlanNotFound)?;
+    let group = load_group(env, proposal.group_id)?;
+
+    if !group::is_member(env, proposal.group_id, &voter) {
+        return Err(SavingsError::NotGroupMember);
+    }
+    if proposal.is_executed {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if env.ledger().timestamp() > proposal.expires_at {
+        return Err(SavingsError::TooLate);
+    }
+    if proposal.votes.contains(&voter) {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    proposal.votes.push_back(voter.clone());
+
+    env.events().publish(
+        (symbol_short!("grp_vote"), voter, proposal.group_id),
+        proposal_id,
+    );
+
+    try_execute(env, &mut proposal, group)
+}
+
+/// Returns a group change proposal by ID.
+pub fn get_group_change(env: &Env, proposal_id: u64) -> Option<GroupChangeProposal> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GroupChangeProposal(proposal_id))
+}
+
+// ========== Helper Functions ==========
+
+fn load_group(env: &Env, group_id: u64) -> Result<GroupSave, SavingsError> {
+    group::get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)
+}
+
+fn get_next_change_id(env: &Env) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::NextGroupChangeId)
+        .unwrap_or(1)
+}
+
+/// Checks that a change can be applied to the group in its current state.
+fn validate_change(env: &Env, group: &GroupSave, change: &GroupChange) -> Result<(), SavingsError> {
+    if group.is_completed || group.status != GroupStatus::Active {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    match change {
+        GroupChange::TargetAmount(target) => {
+            // Rotating groups pay out per round and have no pooled target
+            if group.mode != GroupMode::Pooled {
+                return Err(SavingsError::InvalidGroupConfig);
+            }
+            if *target <= group.current_amount {
+                return Err(SavingsError::InvalidAmount);
+            }
+        }
+        GroupChange::MaxMembers(max_members) => {
+            if *max_members != 0 && *max_members < group.member_count {
+                return Err(SavingsError::InvalidGroupConfig);
+            }
+        }
+        GroupChange::PayoutBeneficiary(Some(beneficiary)) => {
+            if !crate::users::user_exists(env, beneficiary) {
+                return Err(SavingsError::UserNotFound);
+            }
+        }
+        GroupChange::PayoutBeneficiary(None) => {}
+    }
+
+    Ok(())
+}
+
+/// Counts votes from current members only, so members who left no longer
+/// count towards quorum.
+fn has_quorum(env: &Env, group: &GroupSave, proposal: &GroupChangeProposal) -> bool {
+    let mut votes: u64 = 0;
+    for voter in proposal.votes.iter() {
+        if group::is_member(env, group.id, &voter) {
+            votes += 1;
+        }
+    }
+    votes * 100 >= group.member_count as u64 * group.change_quorum as u64
+}
+
+/// Applies the change if quorum is met and persists the proposal.
+fn try_execute(
+    env: &Env,
+    proposal: &mut GroupChangeProposal,
+    mut group: GroupSave,
+) -> Result<bool, SavingsError> {
+    let executed = has_quorum(env, &group, proposal);
+    if executed {
+        // Re-validate: the group may have moved on since the proposal was made
+        validate_change(env, &group, &proposal.change)?;
+        match proposal.change.clone() {
+            GroupChange::TargetAmount(target) => group.target_amount = target,
+            GroupChange::MaxMembers(max_members) => group.max_members = max_members,
+            GroupChange::PayoutBeneficiary(beneficiary) => group.payout_beneficiary = beneficiary,
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::GroupSave(group.id), &group);
+        proposal.is_executed = true;
+
+        env.events()
+            .publish((symbol_short!("grp_amend"), group.id), proposal.id);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupChangeProposal(proposal.id), proposal);
+
+    Ok(executed)
+}
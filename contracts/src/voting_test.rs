@@ -0,0 +1,148 @@
+use crate::{GroupChange, NesteraContract, NesteraContractClient, SavingsError};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env, String,
+};
+
+fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &admin_pk, &None);
+
+    (env, client, admin)
+}
+
+/// Creates a group with a creator and two further members.
+fn setup_group(
+    env: &Env,
+    client: &NesteraContractClient<'static>,
+) -> (u64, Address, Address, Address) {
+    let creator = Address::generate(env);
+    let member1 = Address::generate(env);
+    let member2 = Address::generate(env);
+
+    client.initialize_user(&creator);
+    client.initialize_user(&member1);
+    client.initialize_user(&member2);
+
+    let group_id = client.create_group_save(
+        &creator,
+        &String::from_str(env, "Village Fund"),
+        &String::from_str(env, "Community savings"),
+        &String::from_str(env, "savings"),
+        &10_000,
+        &0,
+        &100,
+        &true,
+        &1,
+        &1_000_000,
+    );
+    client.join_group_save(&member1, &group_id);
+    client.join_group_save(&member2, &group_id);
+
+    (group_id, creator, member1, member2)
+}
+
+#[test]
+fn test_majority_vote_amends_target() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, member2) = setup_group(&env, &client);
+
+    let proposal_id =
+        client.propose_group_change(&member1, &group_id, &GroupChange::TargetAmount(20_000));
+
+    // One of three is below the default 51% quorum
+    assert_eq!(
+        client.get_group_save(&group_id).unwrap().target_amount,
+        10_000
+    );
+
+    assert!(client.vote_group_change(&creator, &proposal_id));
+    assert_eq!(
+        client.get_group_save(&group_id).unwrap().target_amount,
+        20_000
+    );
+    assert!(client.get_group_change(&proposal_id).unwrap().is_executed);
+
+    let res = client.try_vote_group_change(&member2, &proposal_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::PlanCompleted));
+}
+
+#[test]
+fn test_member_cap_vote_blocks_joins() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, member2) = setup_group(&env, &client);
+
+    client.set_group_change_quorum(&creator, &group_id, &100);
+    let proposal_id = client.propose_group_change(&creator, &group_id, &GroupChange::MaxMembers(3));
+    assert!(!client.vote_group_change(&member1, &proposal_id));
+    assert!(client.vote_group_change(&member2, &proposal_id));
+    assert_eq!(client.get_group_save(&group_id).unwrap().max_members, 3);
+
+    let late = Address::generate(&env);
+    client.initialize_user(&late);
+    let res = client.try_join_group_save(&late, &group_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::GroupFull));
+
+    // A cap below the current member count is rejected
+    let res = client.try_propose_group_change(&creator, &group_id, &GroupChange::MaxMembers(2));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+}
+
+#[test]
+fn test_change_proposal_rules() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, _member2) = setup_group(&env, &client);
+    let outsider = Address::generate(&env);
+    client.initialize_user(&outsider);
+
+    let res =
+        client.try_propose_group_change(&outsider, &group_id, &GroupChange::TargetAmount(20_000));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::NotGroupMember));
+
+    // The target cannot drop to or below what was already raised
+    client.contribute_to_group_save(&member1, &group_id, &500);
+    let res = client.try_propose_group_change(&creator, &group_id, &GroupChange::TargetAmount(500));
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidAmount));
+
+    let proposal_id =
+        client.propose_group_change(&creator, &group_id, &GroupChange::TargetAmount(8_000));
+    let res = client.try_vote_group_change(&creator, &proposal_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidGroupConfig));
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += crate::voting::GROUP_CHANGE_TTL + 1);
+    let res = client.try_vote_group_change(&member1, &proposal_id);
+    assert_eq!(res.unwrap_err(), Ok(SavingsError::TooLate));
+}
+
+#[test]
+fn test_members_vote_to_replace_beneficiary() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, member1, member2) = setup_group(&env, &client);
+    let couple = Address::generate(&env);
+    client.initialize_user(&couple);
+
+    client.set_group_payout_beneficiary(&creator, &group_id, &Some(creator.clone()));
+
+    let proposal_id = client.propose_group_change(
+        &member1,
+        &group_id,
+        &GroupChange::PayoutBeneficiary(Some(couple.clone())),
+    );
+    assert!(client.vote_group_change(&member2, &proposal_id));
+    assert_eq!(
+        client.get_group_save(&group_id).unwrap().payout_beneficiary,
+        Some(couple.clone())
+    );
+
+    client.contribute_to_group_save(&member1, &group_id, &10_000);
+    client.distribute_group_save(&group_id);
+    assert_eq!(client.get_flexi_balance(&couple), 10_000);
+    assert_eq!(client.get_flexi_balance(&creator), 0);
+}
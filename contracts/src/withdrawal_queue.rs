@@ -0,0 +1,116 @@
+//! First-in, first-out queue for withdrawals the contract can't pay yet.
+//!
+//! With funds deployed to yield strategies, the contract only holds its
+//! liquidity buffer of the yield asset. A withdrawal of that asset the buffer
+//! can't cover is queued instead of failing: the user's internal balance is
+//! already debited and the tokens are owed to them. Queued requests are paid
+//! in order whenever liquidity comes back, after `recall_liquidity`, before
+//! `rebalance` deploys anything, or when anyone calls
+//! `process_withdrawal_queue`. A new withdrawal queues behind any waiting
+//! request, so nobody can jump the line.
+
+use crate::errors::SavingsError;
+use crate::normalize;
+use crate::storage_types::{DataKeyExt, WithdrawalQueue, WithdrawalRequest};
+use crate::strategies;
+use soroban_sdk::{symbol_short, token::TokenClient, Address, Env};
+
+/// Whether a withdrawal of `units` of `asset` must wait in the queue.
+pub(crate) fn must_queue(env: &Env, asset: &Address, units: i128) -> bool {
+    let is_yield_asset =
+        strategies::get_yield_config(env).is_some_and(|config| &config.asset == asset);
+    if !is_yield_asset {
+        return false;
+    }
+    let queue = get_withdrawal_queue(env);
+    queue.head < queue.tail
+        || TokenClient::new(env, asset).balance(&env.current_contract_address()) < units
+}
+
+/// Adds a withdrawal of `amount`, in canonical precision, to the back of the
+/// queue.
+///
+/// # Returns
+/// The request id
+pub(crate) fn enqueue(env: &Env, user: &Address, asset: &Address, amount: i128) -> u64 {
+    let mut queue = get_withdrawal_queue(env);
+    let id = queue.tail;
+    env.storage().persistent().set(
+        &DataKeyExt::WithdrawalRequest(id),
+        &WithdrawalRequest {
+            id,
+            user: user.clone(),
+            asset: asset.clone(),
+            amount,
+            requested_at: env.ledger().timestamp(),
+        },
+    );
+    queue.tail += 1;
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::WithdrawalQueue, &queue);
+    env.events()
+        .publish((symbol_short!("wd_queue"), user.clone(), id), amount);
+    id
+}
+
+/// Pays queued withdrawals in order for as long as the contract's liquid
+/// balance covers the next one. Anyone may trigger it.
+///
+/// # Returns
+/// `Ok(u32)` - The number of requests paid
+pub fn process_withdrawal_queue(env: &Env) -> Result<u32, SavingsError> {
+    let mut queue = get_withdrawal_queue(env);
+    let contract = env.current_contract_address();
+    let mut paid = 0;
+    while queue.head < queue.tail {
+        let key = DataKeyExt::WithdrawalRequest(queue.head);
+        let request: WithdrawalRequest = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(SavingsError::DataCorruption)?;
+        let units = normalize::to_units_out(env, &request.asset, request.amount)?;
+        let token = TokenClient::new(env, &request.asset);
+        if token.balance(&contract) < units {
+            break;
+        }
+
+        env.storage().persistent().remove(&key);
+        queue.head += 1;
+        if units > 0 {
+            token.transfer(&contract, &request.user, &units);
+        }
+        env.events().publish(
+            (symbol_short!("wd_paid"), request.user, request.id),
+            request.amount,
+        );
+        paid += 1;
+    }
+    env.storage()
+        .instance()
+        .set(&DataKeyExt::WithdrawalQueue, &queue);
+    Ok(paid)
+}
+
+/// Returns the bounds of the withdrawal queue.
+pub fn get_withdrawal_queue(env: &Env) -> WithdrawalQueue {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::WithdrawalQueue)
+        .unwrap_or(WithdrawalQueue { head: 0, tail: 0 })
+}
+
+/// Returns a queued withdrawal, or `None` once it has been paid.
+pub fn get_withdrawal_request(env: &Env, id: u64) -> Option<WithdrawalRequest> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::WithdrawalRequest(id))
+}
+
+/// Returns how many requests are ahead of a queued withdrawal, or `None`
+/// once it has been paid.
+pub fn get_queue_position(env: &Env, id: u64) -> Option<u64> {
+    let queue = get_withdrawal_queue(env);
+    (queue.head <= id && id < queue.tail).then(|| id - queue.head)
+}